@@ -47,294 +47,408 @@
 #define LB_WATCHLIST_GROUP_SECURITIES 2
 
 /**
- * Adjust type
+ * Language identifer
  */
-typedef enum lb_adjust_type_t {
+typedef enum lb_language_t {
   /**
-   * Actual
+   * zh-CN
    */
-  AdjustTypeNoAdjust,
+  Language_ZH_CN,
   /**
-   * Adjust forward
+   * zh-HK
    */
-  AdjustTypeForward,
-} lb_adjust_type_t;
+  Language_ZH_HK,
+  /**
+   * en
+   */
+  Language_EN,
+} lb_language_t;
 
 /**
- * Balance type
+ * Language identifer
  */
-typedef enum lb_balance_type_t {
+typedef enum lb_push_candlestick_mode_t {
   /**
-   * Unknown
+   * Real-time
    */
-  BalanceTypeUnknown,
+  PushCandlestickMode_Realtime,
   /**
-   * Cash
+   * Confirmed
    */
-  BalanceTypeCash,
+  PushCandlestickMode_Confirmed,
+} lb_push_candlestick_mode_t;
+
+/**
+ * Error kind, mapped from the known error codes
+ */
+typedef enum lb_error_kind_t {
   /**
-   * Stock
+   * The buying power is insufficient for the order
    */
-  BalanceTypeStock,
+  ErrorKindInsufficientBuyingPower,
   /**
-   * Fund
+   * The market is closed
    */
-  BalanceTypeFund,
-} lb_balance_type_t;
+  ErrorKindMarketClosed,
+  /**
+   * The price does not match the tick size of the security
+   */
+  ErrorKindInvalidPriceTick,
+  /**
+   * Too many requests
+   */
+  ErrorKindRateLimited,
+  /**
+   * The access token is expired
+   */
+  ErrorKindTokenExpired,
+  /**
+   * The account has no permission for the request
+   */
+  ErrorKindPermissionDenied,
+  /**
+   * Other errors
+   */
+  ErrorKindOther,
+} lb_error_kind_t;
 
 /**
- * Calc index
+ * Trade status
  */
-typedef enum lb_calc_index_t {
+typedef enum lb_trade_status_t {
   /**
-   * Latest price
+   * Normal
    */
-  CalcIndexLastDone,
+  TradeStatusNormal,
   /**
-   * Change value
+   * Suspension
    */
-  CalcIndexChangeValue,
+  TradeStatusHalted,
   /**
-   * Change rate
+   * Delisted
    */
-  CalcIndexChangeRate,
+  TradeStatusDelisted,
   /**
-   * Volume
+   * Fuse
    */
-  CalcIndexVolume,
+  TradeStatusFuse,
   /**
-   * Turnover
+   * Papare List
    */
-  CalcIndexTurnover,
+  TradeStatusPrepareList,
   /**
-   * Year-to-date change ratio
+   * Code Moved
    */
-  CalcIndexYtdChangeRate,
+  TradeStatusCodeMoved,
   /**
-   * Turnover rate
+   * To Be Opened
    */
-  CalcIndexTurnoverRate,
+  TradeStatusToBeOpened,
   /**
-   * Total market value
+   * Split Stock Halts
    */
-  CalcIndexTotalMarketValue,
+  TradeStatusSplitStockHalts,
   /**
-   * Capital flow
+   * Expired
    */
-  CalcIndexCapitalFlow,
+  TradeStatusExpired,
   /**
-   * Amplitude
+   * Warrant To BeListed
    */
-  CalcIndexAmplitude,
+  TradeStatusWarrantPrepareList,
   /**
-   * Volume ratio
+   * Suspend
    */
-  CalcIndexVolumeRatio,
+  TradeStatusSuspendTrade,
+} lb_trade_status_t;
+
+/**
+ * Trade session
+ */
+typedef enum lb_trade_session_t {
   /**
-   * PE (TTM)
+   * Trading
    */
-  CalcIndexPeTtmRatio,
+  TradeSessionIntraday,
   /**
-   * PB
+   * Pre-Trading
    */
-  CalcIndexPbRatio,
+  TradeSessionPre,
   /**
-   * Dividend ratio (TTM)
+   * Post-Trading
    */
-  CalcIndexDividendRatioTtm,
+  TradeSessionPost,
   /**
-   * Five days change ratio
+   * Post-Trading
    */
-  CalcIndexFiveDayChangeRate,
+  TradeSessionOvernight,
+} lb_trade_session_t;
+
+/**
+ * Trade direction
+ */
+typedef enum lb_trade_direction_t {
   /**
-   * Ten days change ratio
+   * Neutral
    */
-  CalcIndexTenDayChangeRate,
+  TradeDirectionNeutral,
   /**
-   * Half year change ratio
+   * Down
    */
-  CalcIndexHalfYearChangeRate,
+  TradeDirectionDown,
   /**
-   * Five minutes change ratio
+   * Up
    */
-  CalcIndexFiveMinutesChangeRate,
+  TradeDirectionUp,
+} lb_trade_direction_t;
+
+/**
+ * Candlestick period
+ */
+typedef enum lb_period_t {
   /**
-   * Expiry date
+   * Unknown
    */
-  CalcIndexExpiryDate,
+  PeriodUnknown,
   /**
-   * Strike price
+   * One Minute
    */
-  CalcIndexStrikePrice,
+  PeriodMin1,
   /**
-   * Upper bound price
+   * Two Minutes
    */
-  CalcIndexUpperStrikePrice,
+  PeriodMin2,
   /**
-   * Lower bound price
+   * Three Minutes
    */
-  CalcIndexLowerStrikePrice,
+  PeriodMin3,
   /**
-   * Outstanding quantity
+   * Five Minutes
    */
-  CalcIndexOutstandingQty,
+  PeriodMin5,
   /**
-   * Outstanding ratio
+   * Ten Minutes
    */
-  CalcIndexOutstandingRatio,
+  PeriodMin10,
   /**
-   * Premium
+   * Fifteen Minutes
    */
-  CalcIndexPremium,
+  PeriodMin15,
   /**
-   * In/out of the bound
+   * Twenty Minutes
    */
-  CalcIndexItmOtm,
+  PeriodMin20,
   /**
-   * Implied volatility
+   * Thirty Minutes
    */
-  CalcIndexImpliedVolatility,
+  PeriodMin30,
   /**
-   * Warrant delta
+   * Forty-Five Minutes
    */
-  CalcIndexWarrantDelta,
+  PeriodMin45,
   /**
-   * Call price
+   * One Hour
    */
-  CalcIndexCallPrice,
+  PeriodMin60,
   /**
-   * Price interval from the call price
+   * Two Hours
    */
-  CalcIndexToCallPrice,
+  PeriodMin120,
   /**
-   * Effective leverage
+   * Three Hours
    */
-  CalcIndexEffectiveLeverage,
+  PeriodMin180,
   /**
-   * Leverage ratio
+   * Four Hours
    */
-  CalcIndexLeverageRatio,
+  PeriodMin240,
   /**
-   * Conversion ratio
+   * Daily
    */
-  CalcIndexConversionRatio,
+  PeriodDay,
   /**
-   * Breakeven point
+   * Weekly
    */
-  CalcIndexBalancePoint,
+  PeriodWeek,
   /**
-   * Open interest
+   * Monthly
    */
-  CalcIndexOpenInterest,
+  PeriodMonth,
   /**
-   * Delta
+   * Quarterly
    */
-  CalcIndexDelta,
+  PeriodQuarter,
   /**
-   * Gamma
+   * Yearly
    */
-  CalcIndexGamma,
+  PeriodYear,
+} lb_period_t;
+
+/**
+ * Trade sessions
+ */
+typedef enum lb_trade_sessions_t {
   /**
-   * Theta
+   * Intraday
    */
-  CalcIndexTheta,
+  TradeSessionsIntraday = 0,
   /**
-   * Vega
+   * All
    */
-  CalcIndexVega,
+  TradeSessionsAll = 100,
+} lb_trade_sessions_t;
+
+/**
+ * Adjust type
+ */
+typedef enum lb_adjust_type_t {
   /**
-   * Rho
+   * Actual
    */
-  CalcIndexRho,
-} lb_calc_index_t;
+  AdjustTypeNoAdjust,
+  /**
+   * Adjust forward
+   */
+  AdjustTypeForward,
+} lb_adjust_type_t;
 
 /**
- * Cash flow direction
+ * Warrant sort by
  */
-typedef enum lb_cash_flow_direction_t {
+typedef enum lb_warrant_sort_by_t {
+  /**
+   * Last done
+   */
+  WarrantSortByLastDone,
+  /**
+   * Change rate
+   */
+  WarrantSortByChangeRate,
+  /**
+   * Change value
+   */
+  WarrantSortByChangeValue,
+  /**
+   * Volume
+   */
+  WarrantSortByVolume,
+  /**
+   * Turnover
+   */
+  WarrantSortByTurnover,
+  /**
+   * Expiry date
+   */
+  WarrantSortByExpiryDate,
+  /**
+   * Strike price
+   */
+  WarrantSortByStrikePrice,
+  /**
+   * Upper strike price
+   */
+  WarrantSortByUpperStrikePrice,
+  /**
+   * Lower strike price
+   */
+  WarrantSortByLowerStrikePrice,
+  /**
+   * Outstanding quantity
+   */
+  WarrantSortByOutstandingQuantity,
+  /**
+   * Outstanding ratio
+   */
+  WarrantSortByOutstandingRatio,
+  /**
+   * Premium
+   */
+  WarrantSortByPremium,
   /**
-   * Unknown
+   * In/out of the bound
    */
-  CashFlowDirectionUnknown,
+  WarrantSortByItmOtm,
   /**
-   * Out
+   * Implied volatility
    */
-  CashFlowDirectionOut,
+  WarrantSortByImpliedVolatility,
   /**
-   * In
+   * Greek value delta
    */
-  CashFlowDirectionIn,
-} lb_cash_flow_direction_t;
-
-/**
- * Charge category code
- */
-typedef enum lb_charge_category_code_t {
+  WarrantSortByDelta,
   /**
-   * Unknown
+   * Call price
    */
-  ChargeCategoryCodeUnknown,
+  WarrantSortByCallPrice,
   /**
-   * Broker
+   * Price interval from the call price
    */
-  ChargeCategoryCodeBroker,
+  WarrantSortByToCallPrice,
   /**
-   * Third
+   * Effective leverage
    */
-  ChargeCategoryCodeThird,
-} lb_charge_category_code_t;
-
-/**
- * Commission-free Status
- */
-typedef enum lb_commission_free_status_t {
+  WarrantSortByEffectiveLeverage,
   /**
-   * Unknown
+   * Leverage ratio
    */
-  CommissionFreeStatusUnknown,
+  WarrantSortByLeverageRatio,
   /**
-   * None
+   * Conversion ratio
    */
-  CommissionFreeStatusNone,
+  WarrantSortByConversionRatio,
   /**
-   * Commission-free amount to be calculated
+   * Breakeven point
    */
-  CommissionFreeStatusCalculated,
+  WarrantSortByBalancePoint,
   /**
-   * Pending commission-free
+   * Status
    */
-  CommissionFreeStatusPending,
+  WarrantSortByStatus,
+} lb_warrant_sort_by_t;
+
+/**
+ * Sort order type
+ */
+typedef enum lb_sort_order_type_t {
   /**
-   * Commission-free applied
+   * Ascending
    */
-  CommissionFreeStatusReady,
-} lb_commission_free_status_t;
+  SortOrderAscending,
+  /**
+   * Descending
+   */
+  SortOrderDescending,
+} lb_sort_order_type_t;
 
 /**
- * Deduction status
+ * Warrant type
  */
-typedef enum lb_deduction_status_t {
+typedef enum lb_warrant_type_t {
   /**
    * Unknown
    */
-  DeductionStatusUnknown,
+  WarrantTypeUnknown,
   /**
-   * Pending Settlement
+   * Put
    */
-  DeductionStatusNone,
+  WarrantTypePut,
   /**
-   * Commission-free amount to be calculated
+   * Call
    */
-  DeductionStatusNoData,
+  WarrantTypeCall,
   /**
-   * Pending commission-free
+   * Bull
    */
-  DeductionStatusPending,
+  WarrantTypeBull,
   /**
-   * Commission-free applied
+   * Bear
    */
-  DeductionStatusDone,
-} lb_deduction_status_t;
+  WarrantTypeBear,
+  /**
+   * Inline
+   */
+  WarrantTypeInline,
+} lb_warrant_type_t;
 
 /**
  * Filter warrant expiry date type
@@ -373,44 +487,22 @@ typedef enum lb_filter_warrant_in_out_bounds_type_t {
 } lb_filter_warrant_in_out_bounds_type_t;
 
 /**
- * Data granularity
- */
-typedef enum lb_granularity_t {
-  /**
-   * Unknown
-   */
-  GranularityUnknown,
-  /**
-   * Daily
-   */
-  GranularityDaily,
-  /**
-   * Weekly
-   */
-  GranularityWeekly,
-  /**
-   * Monthly
-   */
-  GranularityMonthly,
-} lb_granularity_t;
-
-/**
- * Language identifer
+ * Warrant status
  */
-typedef enum lb_language_t {
+typedef enum lb_warrant_status_t {
   /**
-   * zh-CN
+   * Suspend
    */
-  Language_ZH_CN,
+  WarrantStatusSuspend,
   /**
-   * zh-HK
+   * Prepare List
    */
-  Language_ZH_HK,
+  WarrantStatusPrepareList,
   /**
-   * en
+   * Normal
    */
-  Language_EN,
-} lb_language_t;
+  WarrantStatusNormal,
+} lb_warrant_status_t;
 
 /**
  * Market type
@@ -443,182 +535,216 @@ typedef enum lb_market_t {
 } lb_market_t;
 
 /**
- * Option direction
+ * Calc index
  */
-typedef enum lb_option_direction_t {
+typedef enum lb_calc_index_t {
   /**
-   * Unknown
+   * Latest price
    */
-  OptionDirectionUnknown,
+  CalcIndexLastDone,
   /**
-   * Put
+   * Change value
    */
-  OptionDirectionPut,
+  CalcIndexChangeValue,
   /**
-   * Call
+   * Change rate
    */
-  OptionDirectionCall,
-} lb_option_direction_t;
-
-/**
- * Option type
- */
-typedef enum lb_option_type_t {
+  CalcIndexChangeRate,
   /**
-   * Unknown
+   * Volume
    */
-  OptionTypeUnknown,
+  CalcIndexVolume,
   /**
-   * American
+   * Turnover
    */
-  OptionTypeAmerican,
+  CalcIndexTurnover,
   /**
-   * Enrope
+   * Year-to-date change ratio
    */
-  OptionTypeEurope,
-} lb_option_type_t;
-
-/**
- * Order side
- */
-typedef enum lb_order_side_t {
+  CalcIndexYtdChangeRate,
   /**
-   * Unknown
+   * Turnover rate
    */
-  OrderSideUnknown,
+  CalcIndexTurnoverRate,
   /**
-   * Buy
+   * Total market value
    */
-  OrderSideBuy,
+  CalcIndexTotalMarketValue,
   /**
-   * Sell
+   * Capital flow
    */
-  OrderSideSell,
-} lb_order_side_t;
-
-/**
- * Order status
- */
-typedef enum lb_order_status_t {
+  CalcIndexCapitalFlow,
   /**
-   * Unknown
+   * Amplitude
    */
-  OrderStatusUnknown,
+  CalcIndexAmplitude,
   /**
-   * Not reported
+   * Volume ratio
    */
-  OrderStatusNotReported,
+  CalcIndexVolumeRatio,
   /**
-   * Not reported (Replaced Order)
+   * PE (TTM)
    */
-  OrderStatusReplacedNotReported,
+  CalcIndexPeTtmRatio,
   /**
-   * Not reported (Protected Order)
+   * PB
    */
-  OrderStatusProtectedNotReported,
+  CalcIndexPbRatio,
   /**
-   * Not reported (Conditional Order)
+   * Dividend ratio (TTM)
    */
-  OrderStatusVarietiesNotReported,
+  CalcIndexDividendRatioTtm,
+  /**
+   * Five days change ratio
+   */
+  CalcIndexFiveDayChangeRate,
+  /**
+   * Ten days change ratio
+   */
+  CalcIndexTenDayChangeRate,
+  /**
+   * Half year change ratio
+   */
+  CalcIndexHalfYearChangeRate,
+  /**
+   * Five minutes change ratio
+   */
+  CalcIndexFiveMinutesChangeRate,
+  /**
+   * Expiry date
+   */
+  CalcIndexExpiryDate,
+  /**
+   * Strike price
+   */
+  CalcIndexStrikePrice,
+  /**
+   * Upper bound price
+   */
+  CalcIndexUpperStrikePrice,
+  /**
+   * Lower bound price
+   */
+  CalcIndexLowerStrikePrice,
+  /**
+   * Outstanding quantity
+   */
+  CalcIndexOutstandingQty,
+  /**
+   * Outstanding ratio
+   */
+  CalcIndexOutstandingRatio,
   /**
-   * Filled
+   * Premium
    */
-  OrderStatusFilled,
+  CalcIndexPremium,
   /**
-   * Wait To New
+   * In/out of the bound
    */
-  OrderStatusWaitToNew,
+  CalcIndexItmOtm,
   /**
-   * New
+   * Implied volatility
    */
-  OrderStatusNew,
+  CalcIndexImpliedVolatility,
   /**
-   * Wait To Replace
+   * Warrant delta
    */
-  OrderStatusWaitToReplace,
+  CalcIndexWarrantDelta,
   /**
-   * Pending Replace
+   * Call price
    */
-  OrderStatusPendingReplace,
+  CalcIndexCallPrice,
   /**
-   * Replaced
+   * Price interval from the call price
    */
-  OrderStatusReplaced,
+  CalcIndexToCallPrice,
   /**
-   * Partial Filled
+   * Effective leverage
    */
-  OrderStatusPartialFilled,
+  CalcIndexEffectiveLeverage,
   /**
-   * Wait To Cancel
+   * Leverage ratio
    */
-  OrderStatusWaitToCancel,
+  CalcIndexLeverageRatio,
   /**
-   * Pending Cancel
+   * Conversion ratio
    */
-  OrderStatusPendingCancel,
+  CalcIndexConversionRatio,
   /**
-   * Rejected
+   * Breakeven point
    */
-  OrderStatusRejected,
+  CalcIndexBalancePoint,
   /**
-   * Canceled
+   * Open interest
    */
-  OrderStatusCanceled,
+  CalcIndexOpenInterest,
   /**
-   * Expired
+   * Delta
    */
-  OrderStatusExpired,
+  CalcIndexDelta,
   /**
-   * Partial Withdrawal
+   * Gamma
    */
-  OrderStatusPartialWithdrawal,
-} lb_order_status_t;
-
-/**
- * Order tag
- */
-typedef enum lb_order_tag_t {
+  CalcIndexGamma,
   /**
-   * Unknown
+   * Theta
    */
-  OrderTagUnknown,
+  CalcIndexTheta,
   /**
-   * Normal Order
+   * Vega
    */
-  OrderTagNormal,
+  CalcIndexVega,
   /**
-   * Long term Order
+   * Rho
    */
-  OrderTagLongTerm,
+  CalcIndexRho,
+} lb_calc_index_t;
+
+/**
+ * Trade session
+ */
+typedef enum lb_securities_update_mode_t {
   /**
-   * Grey Order
+   * Add securities
    */
-  OrderTagGrey,
+  SecuritiesUpdateModeAdd,
   /**
-   * Force Selling
+   * Remove securities
    */
-  OrderTagMarginCall,
+  SecuritiesUpdateModeRemove,
   /**
-   * OTC
+   * Replace securities
    */
-  OrderTagOffline,
+  SecuritiesUpdateModeReplace,
+} lb_securities_update_mode_t;
+
+/**
+ * Security list category
+ */
+typedef enum lb_security_list_category_t {
   /**
-   * Option Exercise Long
+   * Overnight
    */
-  OrderTagCreditor,
+  SecurityListCategoryOvernight,
+} lb_security_list_category_t;
+
+/**
+ * Order side
+ */
+typedef enum lb_order_side_t {
   /**
-   * Option Exercise Short
+   * Unknown
    */
-  OrderTagDebtor,
+  OrderSideUnknown,
   /**
-   * Wavier Of Option Exercise
+   * Buy
    */
-  OrderTagNonExercise,
+  OrderSideBuy,
   /**
-   * Trade Allocation
+   * Sell
    */
-  OrderTagAllocatedSub,
-} lb_order_tag_t;
+  OrderSideSell,
+} lb_order_side_t;
 
 /**
  * Order type
@@ -683,270 +809,160 @@ typedef enum lb_order_type_t {
 } lb_order_type_t;
 
 /**
- * Enable or disable outside regular trading hours
- */
-typedef enum lb_outside_rth_t {
-  /**
-   * Unknown
-   */
-  OutsideRTHUnknown,
-  /**
-   * Regular trading hour only
-   */
-  OutsideRTHOnly,
-  /**
-   * Any time
-   */
-  OutsideRTHAnyTime,
-  /**
-   * Overnight
-   */
-  OutsideRTHOvernight,
-} lb_outside_rth_t;
-
-/**
- * Candlestick period
+ * Order status
  */
-typedef enum lb_period_t {
+typedef enum lb_order_status_t {
   /**
    * Unknown
    */
-  PeriodUnknown,
-  /**
-   * One Minute
-   */
-  PeriodMin1,
-  /**
-   * Two Minutes
-   */
-  PeriodMin2,
-  /**
-   * Three Minutes
-   */
-  PeriodMin3,
-  /**
-   * Five Minutes
-   */
-  PeriodMin5,
-  /**
-   * Ten Minutes
-   */
-  PeriodMin10,
-  /**
-   * Fifteen Minutes
-   */
-  PeriodMin15,
-  /**
-   * Twenty Minutes
-   */
-  PeriodMin20,
-  /**
-   * Thirty Minutes
-   */
-  PeriodMin30,
-  /**
-   * Forty-Five Minutes
-   */
-  PeriodMin45,
-  /**
-   * One Hour
-   */
-  PeriodMin60,
-  /**
-   * Two Hours
-   */
-  PeriodMin120,
-  /**
-   * Three Hours
-   */
-  PeriodMin180,
-  /**
-   * Four Hours
-   */
-  PeriodMin240,
-  /**
-   * Daily
-   */
-  PeriodDay,
-  /**
-   * Weekly
-   */
-  PeriodWeek,
-  /**
-   * Monthly
-   */
-  PeriodMonth,
-  /**
-   * Quarterly
-   */
-  PeriodQuarter,
-  /**
-   * Yearly
-   */
-  PeriodYear,
-} lb_period_t;
-
-/**
- * Language identifer
- */
-typedef enum lb_push_candlestick_mode_t {
-  /**
-   * Real-time
-   */
-  PushCandlestickMode_Realtime,
-  /**
-   * Confirmed
-   */
-  PushCandlestickMode_Confirmed,
-} lb_push_candlestick_mode_t;
-
-/**
- * Trade session
- */
-typedef enum lb_securities_update_mode_t {
-  /**
-   * Add securities
-   */
-  SecuritiesUpdateModeAdd,
+  OrderStatusUnknown,
   /**
-   * Remove securities
+   * Not reported
    */
-  SecuritiesUpdateModeRemove,
+  OrderStatusNotReported,
   /**
-   * Replace securities
-   */
-  SecuritiesUpdateModeReplace,
-} lb_securities_update_mode_t;
-
-/**
- * Adjust type
- */
-typedef enum lb_security_board_t {
+   * Not reported (Replaced Order)
+   */
+  OrderStatusReplacedNotReported,
   /**
-   * Unknown
+   * Not reported (Protected Order)
    */
-  SecurityBoardUnknown,
+  OrderStatusProtectedNotReported,
   /**
-   * US Main Board
+   * Not reported (Conditional Order)
    */
-  SecurityBoardUSMain,
+  OrderStatusVarietiesNotReported,
   /**
-   * US Pink Board
+   * Filled
    */
-  SecurityBoardUSPink,
+  OrderStatusFilled,
   /**
-   * Dow Jones Industrial Average
+   * Wait To New
    */
-  SecurityBoardUSDJI,
+  OrderStatusWaitToNew,
   /**
-   * Nasdsaq Index
+   * New
    */
-  SecurityBoardUSNSDQ,
+  OrderStatusNew,
   /**
-   * US Industry Board
+   * Wait To Replace
    */
-  SecurityBoardUSSector,
+  OrderStatusWaitToReplace,
   /**
-   * US Option
+   * Pending Replace
    */
-  SecurityBoardUSOption,
+  OrderStatusPendingReplace,
   /**
-   * US Sepecial Option
+   * Replaced
    */
-  SecurityBoardUSOptionS,
+  OrderStatusReplaced,
   /**
-   * Hong Kong Equity Securities
+   * Partial Filled
    */
-  SecurityBoardHKEquity,
+  OrderStatusPartialFilled,
   /**
-   * HK PreIPO Security
+   * Wait To Cancel
    */
-  SecurityBoardHKPreIPO,
+  OrderStatusWaitToCancel,
   /**
-   * HK Warrant
+   * Pending Cancel
    */
-  SecurityBoardHKWarrant,
+  OrderStatusPendingCancel,
   /**
-   * Hang Seng Index
+   * Rejected
    */
-  SecurityBoardHKHS,
+  OrderStatusRejected,
   /**
-   * HK Industry Board
+   * Canceled
    */
-  SecurityBoardHKSector,
+  OrderStatusCanceled,
   /**
-   * SH Main Board(Connect)
+   * Expired
    */
-  SecurityBoardSHMainConnect,
+  OrderStatusExpired,
   /**
-   * SH Main Board(Non Connect)
+   * Partial Withdrawal
    */
-  SecurityBoardSHMainNonConnect,
+  OrderStatusPartialWithdrawal,
+} lb_order_status_t;
+
+/**
+ * Order tag
+ */
+typedef enum lb_order_tag_t {
   /**
-   * SH Science and Technology Innovation Board
+   * Unknown
    */
-  SecurityBoardSHSTAR,
+  OrderTagUnknown,
   /**
-   * CN Index
+   * Normal Order
    */
-  SecurityBoardCNIX,
+  OrderTagNormal,
   /**
-   * CN Industry Board
+   * Long term Order
    */
-  SecurityBoardCNSector,
+  OrderTagLongTerm,
   /**
-   * SZ Main Board(Connect)
+   * Grey Order
    */
-  SecurityBoardSZMainConnect,
+  OrderTagGrey,
   /**
-   * SZ Main Board(Non Connect)
+   * Force Selling
    */
-  SecurityBoardSZMainNonConnect,
+  OrderTagMarginCall,
   /**
-   * SZ Gem Board(Connect)
+   * OTC
    */
-  SecurityBoardSZGEMConnect,
+  OrderTagOffline,
   /**
-   * SZ Gem Board(Non Connect)
+   * Option Exercise Long
    */
-  SecurityBoardSZGEMNonConnect,
+  OrderTagCreditor,
   /**
-   * SG Main Board
+   * Option Exercise Short
    */
-  SecurityBoardSGMain,
+  OrderTagDebtor,
   /**
-   * Singapore Straits Index
+   * Wavier Of Option Exercise
    */
-  SecurityBoardSTI,
+  OrderTagNonExercise,
   /**
-   * SG Industry Board
+   * Trade Allocation
    */
-  SecurityBoardSGSector,
-} lb_security_board_t;
+  OrderTagAllocatedSub,
+} lb_order_tag_t;
 
 /**
- * Security list category
+ * Order tag
  */
-typedef enum lb_security_list_category_t {
+typedef enum lb_trigger_status_t {
   /**
-   * Overnight
+   * Unknown
    */
-  SecurityListCategoryOvernight,
-} lb_security_list_category_t;
+  TriggerStatusUnknown,
+  /**
+   * Deactive
+   */
+  TriggerStatusDeactive,
+  /**
+   * Active
+   */
+  TriggerStatusActive,
+  /**
+   * Released
+   */
+  TriggerStatusReleased,
+} lb_trigger_status_t;
 
 /**
- * Sort order type
+ * Topic type
  */
-typedef enum lb_sort_order_type_t {
-  /**
-   * Ascending
-   */
-  SortOrderAscending,
+typedef enum lb_topic_type_t {
   /**
-   * Descending
+   * Trading
    */
-  SortOrderDescending,
-} lb_sort_order_type_t;
+  TopicPrivate,
+} lb_topic_type_t;
 
 /**
  * Time in force Type
@@ -971,282 +987,306 @@ typedef enum lb_time_in_force_type_t {
 } lb_time_in_force_type_t;
 
 /**
- * Topic type
+ * Enable or disable outside regular trading hours
  */
-typedef enum lb_topic_type_t {
+typedef enum lb_outside_rth_t {
   /**
-   * Trading
+   * Unknown
    */
-  TopicPrivate,
-} lb_topic_type_t;
-
-/**
- * Trade direction
- */
-typedef enum lb_trade_direction_t {
+  OutsideRTHUnknown,
   /**
-   * Neutral
+   * Regular trading hour only
    */
-  TradeDirectionNeutral,
+  OutsideRTHOnly,
   /**
-   * Down
+   * Any time
    */
-  TradeDirectionDown,
+  OutsideRTHAnyTime,
   /**
-   * Up
+   * Overnight
    */
-  TradeDirectionUp,
-} lb_trade_direction_t;
+  OutsideRTHOvernight,
+} lb_outside_rth_t;
 
 /**
- * Trade session
+ * Balance type
  */
-typedef enum lb_trade_session_t {
+typedef enum lb_balance_type_t {
   /**
-   * Trading
+   * Unknown
    */
-  TradeSessionIntraday,
+  BalanceTypeUnknown,
   /**
-   * Pre-Trading
+   * Cash
    */
-  TradeSessionPre,
+  BalanceTypeCash,
   /**
-   * Post-Trading
+   * Stock
    */
-  TradeSessionPost,
+  BalanceTypeStock,
   /**
-   * Post-Trading
+   * Fund
    */
-  TradeSessionOvernight,
-} lb_trade_session_t;
+  BalanceTypeFund,
+} lb_balance_type_t;
 
 /**
- * Trade sessions
+ * Adjust type
  */
-typedef enum lb_trade_sessions_t {
+typedef enum lb_security_board_t {
   /**
-   * Intraday
+   * Unknown
    */
-  TradeSessionsIntraday = 0,
+  SecurityBoardUnknown,
   /**
-   * All
+   * US Main Board
    */
-  TradeSessionsAll = 100,
-} lb_trade_sessions_t;
-
-/**
- * Trade status
- */
-typedef enum lb_trade_status_t {
+  SecurityBoardUSMain,
   /**
-   * Normal
+   * US Pink Board
    */
-  TradeStatusNormal,
+  SecurityBoardUSPink,
   /**
-   * Suspension
+   * Dow Jones Industrial Average
    */
-  TradeStatusHalted,
+  SecurityBoardUSDJI,
   /**
-   * Delisted
+   * Nasdsaq Index
    */
-  TradeStatusDelisted,
+  SecurityBoardUSNSDQ,
   /**
-   * Fuse
+   * US Industry Board
    */
-  TradeStatusFuse,
+  SecurityBoardUSSector,
   /**
-   * Papare List
+   * US Option
    */
-  TradeStatusPrepareList,
+  SecurityBoardUSOption,
   /**
-   * Code Moved
+   * US Sepecial Option
+   */
+  SecurityBoardUSOptionS,
+  /**
+   * Hong Kong Equity Securities
+   */
+  SecurityBoardHKEquity,
+  /**
+   * HK PreIPO Security
+   */
+  SecurityBoardHKPreIPO,
+  /**
+   * HK Warrant
+   */
+  SecurityBoardHKWarrant,
+  /**
+   * Hang Seng Index
+   */
+  SecurityBoardHKHS,
+  /**
+   * HK Industry Board
    */
-  TradeStatusCodeMoved,
+  SecurityBoardHKSector,
   /**
-   * To Be Opened
+   * SH Main Board(Connect)
    */
-  TradeStatusToBeOpened,
+  SecurityBoardSHMainConnect,
   /**
-   * Split Stock Halts
+   * SH Main Board(Non Connect)
    */
-  TradeStatusSplitStockHalts,
+  SecurityBoardSHMainNonConnect,
   /**
-   * Expired
+   * SH Science and Technology Innovation Board
    */
-  TradeStatusExpired,
+  SecurityBoardSHSTAR,
   /**
-   * Warrant To BeListed
+   * CN Index
    */
-  TradeStatusWarrantPrepareList,
+  SecurityBoardCNIX,
   /**
-   * Suspend
+   * CN Industry Board
    */
-  TradeStatusSuspendTrade,
-} lb_trade_status_t;
-
-/**
- * Order tag
- */
-typedef enum lb_trigger_status_t {
+  SecurityBoardCNSector,
   /**
-   * Unknown
+   * SZ Main Board(Connect)
    */
-  TriggerStatusUnknown,
+  SecurityBoardSZMainConnect,
   /**
-   * Deactive
+   * SZ Main Board(Non Connect)
    */
-  TriggerStatusDeactive,
+  SecurityBoardSZMainNonConnect,
   /**
-   * Active
+   * SZ Gem Board(Connect)
    */
-  TriggerStatusActive,
+  SecurityBoardSZGEMConnect,
   /**
-   * Released
+   * SZ Gem Board(Non Connect)
    */
-  TriggerStatusReleased,
-} lb_trigger_status_t;
-
-/**
- * Warrant sort by
- */
-typedef enum lb_warrant_sort_by_t {
+  SecurityBoardSZGEMNonConnect,
   /**
-   * Last done
+   * SG Main Board
    */
-  WarrantSortByLastDone,
+  SecurityBoardSGMain,
   /**
-   * Change rate
+   * Singapore Straits Index
    */
-  WarrantSortByChangeRate,
+  SecurityBoardSTI,
   /**
-   * Change value
+   * SG Industry Board
    */
-  WarrantSortByChangeValue,
+  SecurityBoardSGSector,
+} lb_security_board_t;
+
+/**
+ * Option type
+ */
+typedef enum lb_option_type_t {
   /**
-   * Volume
+   * Unknown
    */
-  WarrantSortByVolume,
+  OptionTypeUnknown,
   /**
-   * Turnover
+   * American
    */
-  WarrantSortByTurnover,
+  OptionTypeAmerican,
   /**
-   * Expiry date
+   * Enrope
    */
-  WarrantSortByExpiryDate,
+  OptionTypeEurope,
+} lb_option_type_t;
+
+/**
+ * Option direction
+ */
+typedef enum lb_option_direction_t {
   /**
-   * Strike price
+   * Unknown
    */
-  WarrantSortByStrikePrice,
+  OptionDirectionUnknown,
   /**
-   * Upper strike price
+   * Put
    */
-  WarrantSortByUpperStrikePrice,
+  OptionDirectionPut,
   /**
-   * Lower strike price
+   * Call
    */
-  WarrantSortByLowerStrikePrice,
+  OptionDirectionCall,
+} lb_option_direction_t;
+
+/**
+ * Cash flow direction
+ */
+typedef enum lb_cash_flow_direction_t {
   /**
-   * Outstanding quantity
+   * Unknown
    */
-  WarrantSortByOutstandingQuantity,
+  CashFlowDirectionUnknown,
   /**
-   * Outstanding ratio
+   * Out
    */
-  WarrantSortByOutstandingRatio,
+  CashFlowDirectionOut,
   /**
-   * Premium
+   * In
    */
-  WarrantSortByPremium,
+  CashFlowDirectionIn,
+} lb_cash_flow_direction_t;
+
+/**
+ * Commission-free Status
+ */
+typedef enum lb_commission_free_status_t {
   /**
-   * In/out of the bound
+   * Unknown
    */
-  WarrantSortByItmOtm,
+  CommissionFreeStatusUnknown,
   /**
-   * Implied volatility
+   * None
    */
-  WarrantSortByImpliedVolatility,
+  CommissionFreeStatusNone,
   /**
-   * Greek value delta
+   * Commission-free amount to be calculated
    */
-  WarrantSortByDelta,
+  CommissionFreeStatusCalculated,
   /**
-   * Call price
+   * Pending commission-free
    */
-  WarrantSortByCallPrice,
+  CommissionFreeStatusPending,
   /**
-   * Price interval from the call price
+   * Commission-free applied
    */
-  WarrantSortByToCallPrice,
+  CommissionFreeStatusReady,
+} lb_commission_free_status_t;
+
+/**
+ * Deduction status
+ */
+typedef enum lb_deduction_status_t {
   /**
-   * Effective leverage
+   * Unknown
    */
-  WarrantSortByEffectiveLeverage,
+  DeductionStatusUnknown,
   /**
-   * Leverage ratio
+   * Pending Settlement
    */
-  WarrantSortByLeverageRatio,
+  DeductionStatusNone,
   /**
-   * Conversion ratio
+   * Commission-free amount to be calculated
    */
-  WarrantSortByConversionRatio,
+  DeductionStatusNoData,
   /**
-   * Breakeven point
+   * Pending commission-free
    */
-  WarrantSortByBalancePoint,
+  DeductionStatusPending,
   /**
-   * Status
+   * Commission-free applied
    */
-  WarrantSortByStatus,
-} lb_warrant_sort_by_t;
+  DeductionStatusDone,
+} lb_deduction_status_t;
 
 /**
- * Warrant status
+ * Charge category code
  */
-typedef enum lb_warrant_status_t {
+typedef enum lb_charge_category_code_t {
   /**
-   * Suspend
+   * Unknown
    */
-  WarrantStatusSuspend,
+  ChargeCategoryCodeUnknown,
   /**
-   * Prepare List
+   * Broker
    */
-  WarrantStatusPrepareList,
+  ChargeCategoryCodeBroker,
   /**
-   * Normal
+   * Third
    */
-  WarrantStatusNormal,
-} lb_warrant_status_t;
+  ChargeCategoryCodeThird,
+} lb_charge_category_code_t;
 
 /**
- * Warrant type
+ * Data granularity
  */
-typedef enum lb_warrant_type_t {
+typedef enum lb_granularity_t {
   /**
    * Unknown
    */
-  WarrantTypeUnknown,
-  /**
-   * Put
-   */
-  WarrantTypePut,
-  /**
-   * Call
-   */
-  WarrantTypeCall,
+  GranularityUnknown,
   /**
-   * Bull
+   * Daily
    */
-  WarrantTypeBull,
+  GranularityDaily,
   /**
-   * Bear
+   * Weekly
    */
-  WarrantTypeBear,
+  GranularityWeekly,
   /**
-   * Inline
+   * Monthly
    */
-  WarrantTypeInline,
-} lb_warrant_type_t;
+  GranularityMonthly,
+} lb_granularity_t;
+
+/**
+ * A token to cancel the calls of the contexts returned by
+ * `lb_quote_context_with_call_options` or `lb_trade_context_with_call_options`
+ */
+typedef struct lb_cancellation_token_t lb_cancellation_token_t;
 
 /**
  * Configuration options for LongPort sdk
@@ -3788,6 +3828,26 @@ typedef struct lb_history_market_temperature_response_t {
 extern "C" {
 #endif // __cplusplus
 
+/**
+ * Create a new cancellation token
+ */
+struct lb_cancellation_token_t *lb_cancellation_token_new(void);
+
+/**
+ * Free the cancellation token, the contexts using it are not affected
+ */
+void lb_cancellation_token_free(struct lb_cancellation_token_t *token);
+
+/**
+ * Cancels the calls, the pending and the following calls fail
+ */
+void lb_cancellation_token_cancel(const struct lb_cancellation_token_t *token);
+
+/**
+ * Returns `true` if the token is cancelled
+ */
+bool lb_cancellation_token_is_cancelled(const struct lb_cancellation_token_t *token);
+
 /**
  * Create a new `Config` from the given environment variables
  *
@@ -3816,6 +3876,22 @@ extern "C" {
  */
 struct lb_config_t *lb_config_from_env(struct lb_error_t **error);
 
+/**
+ * Create a new `Config` from a TOML file, or a YAML file if the extension
+ * is `.yaml` or `.yml`
+ *
+ * The keys of the file are the names of the environment variables without
+ * the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+ * `push_candlestick_mode`.
+ *
+ * The options of `profile` in the `profiles` table override the top level
+ * options, if `profile` is null, the `LONGPORT_PROFILE` environment variable
+ * is used. The environment variables override the options of the file.
+ */
+struct lb_config_t *lb_config_from_file(const char *path,
+                                        const char *profile,
+                                        struct lb_error_t **error);
+
 struct lb_config_t *lb_config_new(const char *app_key,
                                   const char *app_secret,
                                   const char *access_token,
@@ -3850,6 +3926,28 @@ const char *lb_error_message(const struct lb_error_t *error);
 
 int64_t lb_error_code(const struct lb_error_t *error);
 
+/**
+ * Returns the kind of the error
+ */
+enum lb_error_kind_t lb_error_kind(const struct lb_error_t *error);
+
+/**
+ * Returns the trace id of the failed HTTP request, or null
+ */
+const char *lb_error_trace_id(const struct lb_error_t *error);
+
+/**
+ * Returns `true` if the request may succeed when it is sent again, e.g. it
+ * is rate limited, timed out or the connection is lost
+ */
+bool lb_error_is_retryable(const struct lb_error_t *error);
+
+/**
+ * Returns `true` if the credentials are invalid or expired, or have no
+ * permission for the request
+ */
+bool lb_error_is_auth(const struct lb_error_t *error);
+
 /**
  * Create a HTTP client
  */
@@ -3904,6 +4002,21 @@ void lb_quote_context_retain(const struct lb_quote_context_t *ctx);
 
 void lb_quote_context_release(const struct lb_quote_context_t *ctx);
 
+/**
+ * Returns a new context sharing the connection with `ctx`, whose calls fail
+ * if they are not completed within `timeout` milliseconds or before
+ * `deadline` (a unix timestamp in milliseconds), or when
+ * `cancellation_token` is cancelled
+ *
+ * `0` means no timeout or deadline, and `cancellation_token` can be null.
+ * The push events are only delivered to the callbacks set on `ctx`. The
+ * returned context must be released with `lb_quote_context_release`.
+ */
+const struct lb_quote_context_t *lb_quote_context_with_call_options(const struct lb_quote_context_t *ctx,
+                                                                    uint64_t timeout,
+                                                                    int64_t deadline,
+                                                                    const struct lb_cancellation_token_t *cancellation_token);
+
 uintptr_t lb_quote_context_ref_count(const struct lb_quote_context_t *ctx);
 
 void lb_quote_context_set_userdata(const struct lb_quote_context_t *ctx, void *userdata);
@@ -4340,6 +4453,21 @@ void lb_trade_context_retain(const struct lb_trade_context_t *ctx);
 
 void lb_trade_context_release(const struct lb_trade_context_t *ctx);
 
+/**
+ * Returns a new context sharing the connection with `ctx`, whose calls fail
+ * if they are not completed within `timeout` milliseconds or before
+ * `deadline` (a unix timestamp in milliseconds), or when
+ * `cancellation_token` is cancelled
+ *
+ * `0` means no timeout or deadline, and `cancellation_token` can be null.
+ * The push events are only delivered to the callbacks set on `ctx`. The
+ * returned context must be released with `lb_trade_context_release`.
+ */
+const struct lb_trade_context_t *lb_trade_context_with_call_options(const struct lb_trade_context_t *ctx,
+                                                                    uint64_t timeout,
+                                                                    int64_t deadline,
+                                                                    const struct lb_cancellation_token_t *cancellation_token);
+
 uintptr_t lb_trade_context_ref_count(const struct lb_trade_context_t *ctx);
 
 void lb_trade_context_set_userdata(const struct lb_trade_context_t *ctx, void *userdata);
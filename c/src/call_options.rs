@@ -0,0 +1,58 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use longport::{CallOptions, CancellationToken};
+
+/// A token to cancel the calls of the contexts returned by
+/// `lb_quote_context_with_call_options` or `lb_trade_context_with_call_options`
+pub struct CCancellationToken(CancellationToken);
+
+/// Create a new cancellation token
+#[unsafe(no_mangle)]
+pub extern "C" fn lb_cancellation_token_new() -> *mut CCancellationToken {
+    Box::into_raw(Box::new(CCancellationToken(CancellationToken::new())))
+}
+
+/// Free the cancellation token, the contexts using it are not affected
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_cancellation_token_free(token: *mut CCancellationToken) {
+    let _ = Box::from_raw(token);
+}
+
+/// Cancels the calls, the pending and the following calls fail
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_cancellation_token_cancel(token: *const CCancellationToken) {
+    (*token).0.cancel();
+}
+
+/// Returns `true` if the token is cancelled
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_cancellation_token_is_cancelled(
+    token: *const CCancellationToken,
+) -> bool {
+    (*token).0.is_cancelled()
+}
+
+/// `timeout` is in milliseconds and `deadline` is a unix timestamp in
+/// milliseconds, `0` means no timeout or deadline
+pub(crate) unsafe fn call_options(
+    timeout: u64,
+    deadline: i64,
+    cancellation_token: *const CCancellationToken,
+) -> CallOptions {
+    let mut options = CallOptions::new();
+    if timeout > 0 {
+        options = options.timeout(Duration::from_millis(timeout));
+    }
+    if deadline > 0 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let remaining = Duration::from_millis(deadline.saturating_sub(now).max(0) as u64);
+        options = options.deadline(Instant::now() + remaining);
+    }
+    if !cancellation_token.is_null() {
+        options = options.cancellation_token((*cancellation_token).0.clone());
+    }
+    options
+}
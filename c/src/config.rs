@@ -54,6 +54,37 @@ pub unsafe extern "C" fn lb_config_from_env(error: *mut *mut CError) -> *mut CCo
     }
 }
 
+/// Create a new `Config` from a TOML file, or a YAML file if the extension
+/// is `.yaml` or `.yml`
+///
+/// The keys of the file are the names of the environment variables without
+/// the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+/// `push_candlestick_mode`.
+///
+/// The options of `profile` in the `profiles` table override the top level
+/// options, if `profile` is null, the `LONGPORT_PROFILE` environment variable
+/// is used. The environment variables override the options of the file.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_config_from_file(
+    path: *const c_char,
+    profile: *const c_char,
+    error: *mut *mut CError,
+) -> *mut CConfig {
+    let path = CStr::from_ptr(path).to_str().expect("invalid path");
+    let profile =
+        (!profile.is_null()).then(|| CStr::from_ptr(profile).to_str().expect("invalid profile"));
+    match Config::from_file(path, profile) {
+        Ok(config) => {
+            set_error(error, None);
+            Box::into_raw(Box::new(CConfig(Arc::new(config))))
+        }
+        Err(err) => {
+            set_error(error, Some(err));
+            std::ptr::null_mut()
+        }
+    }
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_config_new(
     app_key: *const c_char,
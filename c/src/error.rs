@@ -2,19 +2,31 @@ use std::os::raw::c_char;
 
 use longport::Error;
 
-use crate::types::{CString, ToFFI};
+use crate::types::{CErrorKind, CString, ToFFI};
 
 pub struct CError {
     code: i64,
     message: CString,
+    trace_id: Option<CString>,
+    kind: CErrorKind,
+    retryable: bool,
+    auth: bool,
 }
 
 impl From<Error> for CError {
     fn from(err: Error) -> Self {
+        let kind = err.kind().into();
+        let retryable = err.is_retryable();
+        let auth = err.is_auth();
+        let trace_id = err.trace_id().map(|trace_id| trace_id.to_string().into());
         let err = err.into_simple_error();
         Self {
             code: err.code().unwrap_or_default(),
             message: err.message().to_string().into(),
+            trace_id,
+            kind,
+            retryable,
+            auth,
         }
     }
 }
@@ -43,3 +55,32 @@ pub unsafe extern "C" fn lb_error_message(error: *const CError) -> *const c_char
 pub unsafe extern "C" fn lb_error_code(error: *const CError) -> i64 {
     (*error).code
 }
+
+/// Returns the kind of the error
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_error_kind(error: *const CError) -> CErrorKind {
+    (*error).kind
+}
+
+/// Returns the trace id of the failed HTTP request, or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_error_trace_id(error: *const CError) -> *const c_char {
+    match &(*error).trace_id {
+        Some(trace_id) => trace_id.to_ffi_type(),
+        None => std::ptr::null(),
+    }
+}
+
+/// Returns `true` if the request may succeed when it is sent again, e.g. it
+/// is rate limited, timed out or the connection is lost
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_error_is_retryable(error: *const CError) -> bool {
+    (*error).retryable
+}
+
+/// Returns `true` if the credentials are invalid or expired, or have no
+/// permission for the request
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_error_is_auth(error: *const CError) -> bool {
+    (*error).auth
+}
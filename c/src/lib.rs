@@ -1,6 +1,7 @@
 #![allow(unsafe_op_in_unsafe_fn)]
 
 mod async_call;
+mod call_options;
 mod callback;
 mod config;
 mod error;
@@ -16,6 +16,7 @@ use parking_lot::Mutex;
 
 use crate::{
     async_call::{CAsyncCallback, CAsyncResult, execute_async},
+    call_options::{CCancellationToken, call_options},
     callback::{CFreeUserDataFunc, Callback},
     config::CConfig,
     quote_context::{
@@ -278,6 +279,33 @@ pub unsafe extern "C" fn lb_quote_context_release(ctx: *const CQuoteContext) {
     let _ = Arc::from_raw(ctx);
 }
 
+/// Returns a new context sharing the connection with `ctx`, whose calls fail
+/// if they are not completed within `timeout` milliseconds or before
+/// `deadline` (a unix timestamp in milliseconds), or when
+/// `cancellation_token` is cancelled
+///
+/// `0` means no timeout or deadline, and `cancellation_token` can be null.
+/// The push events are only delivered to the callbacks set on `ctx`. The
+/// returned context must be released with `lb_quote_context_release`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_quote_context_with_call_options(
+    ctx: *const CQuoteContext,
+    timeout: u64,
+    deadline: i64,
+    cancellation_token: *const CCancellationToken,
+) -> *const CQuoteContext {
+    let options = call_options(timeout, deadline, cancellation_token);
+    Arc::into_raw(Arc::new(CQuoteContext {
+        ctx: (*ctx).ctx.with_call_options(options),
+        quote_level: OnceLock::new(),
+        state: Mutex::new(CQuoteContextState {
+            userdata: std::ptr::null_mut(),
+            callbacks: Callbacks::default(),
+            free_userdata: None,
+        }),
+    }))
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_quote_context_ref_count(ctx: *const CQuoteContext) -> usize {
     Arc::increment_strong_count(ctx);
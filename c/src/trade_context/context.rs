@@ -14,6 +14,7 @@ use time::OffsetDateTime;
 
 use crate::{
     async_call::{CAsyncCallback, CAsyncResult, execute_async},
+    call_options::{CCancellationToken, call_options},
     callback::{CFreeUserDataFunc, Callback},
     config::CConfig,
     trade_context::{
@@ -144,6 +145,32 @@ pub unsafe extern "C" fn lb_trade_context_release(ctx: *const CTradeContext) {
     let _ = Arc::from_raw(ctx);
 }
 
+/// Returns a new context sharing the connection with `ctx`, whose calls fail
+/// if they are not completed within `timeout` milliseconds or before
+/// `deadline` (a unix timestamp in milliseconds), or when
+/// `cancellation_token` is cancelled
+///
+/// `0` means no timeout or deadline, and `cancellation_token` can be null.
+/// The push events are only delivered to the callbacks set on `ctx`. The
+/// returned context must be released with `lb_trade_context_release`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn lb_trade_context_with_call_options(
+    ctx: *const CTradeContext,
+    timeout: u64,
+    deadline: i64,
+    cancellation_token: *const CCancellationToken,
+) -> *const CTradeContext {
+    let options = call_options(timeout, deadline, cancellation_token);
+    Arc::into_raw(Arc::new(CTradeContext {
+        ctx: (*ctx).ctx.with_call_options(options),
+        state: Mutex::new(CTradeContextState {
+            userdata: std::ptr::null_mut(),
+            callbacks: Callbacks::default(),
+            free_userdata: None,
+        }),
+    }))
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn lb_trade_context_ref_count(ctx: *const CTradeContext) -> usize {
     Arc::increment_strong_count(ctx);
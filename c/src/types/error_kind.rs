@@ -0,0 +1,30 @@
+use longport_c_macros::CEnum;
+
+/// Error kind, mapped from the known error codes
+#[derive(Debug, Copy, Clone, Eq, PartialEq, CEnum)]
+#[c(remote = "longport::ErrorKind")]
+#[allow(clippy::enum_variant_names)]
+#[repr(C)]
+pub enum CErrorKind {
+    /// The buying power is insufficient for the order
+    #[c(remote = "InsufficientBuyingPower")]
+    ErrorKindInsufficientBuyingPower,
+    /// The market is closed
+    #[c(remote = "MarketClosed")]
+    ErrorKindMarketClosed,
+    /// The price does not match the tick size of the security
+    #[c(remote = "InvalidPriceTick")]
+    ErrorKindInvalidPriceTick,
+    /// Too many requests
+    #[c(remote = "RateLimited")]
+    ErrorKindRateLimited,
+    /// The access token is expired
+    #[c(remote = "TokenExpired")]
+    ErrorKindTokenExpired,
+    /// The account has no permission for the request
+    #[c(remote = "PermissionDenied")]
+    ErrorKindPermissionDenied,
+    /// Other errors
+    #[c(remote = "Other")]
+    ErrorKindOther,
+}
@@ -2,6 +2,7 @@ mod array;
 mod cow;
 mod datetime;
 mod decimal;
+mod error_kind;
 mod language;
 mod market;
 mod option;
@@ -14,6 +15,7 @@ pub(crate) use array::CVec;
 pub(crate) use cow::CCow;
 pub(crate) use datetime::{CDate, CDateTime, CTime};
 pub(crate) use decimal::CDecimal;
+pub(crate) use error_kind::CErrorKind;
 pub(crate) use language::CLanguage;
 pub(crate) use market::CMarket;
 pub(crate) use option::COption;
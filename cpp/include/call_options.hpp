@@ -0,0 +1,41 @@
+#pragma once
+
+#include <chrono>
+#include <memory>
+#include <optional>
+
+typedef struct lb_cancellation_token_t lb_cancellation_token_t;
+
+namespace longport {
+
+/// A token to cancel the calls of the contexts returned by
+/// `with_call_options`, the copies share the same token
+class CancellationToken
+{
+private:
+  std::shared_ptr<lb_cancellation_token_t> token_;
+
+public:
+  CancellationToken();
+
+  operator const lb_cancellation_token_t*() const;
+
+  /// Cancels the calls, the pending and the following calls fail
+  void cancel() const;
+
+  /// Returns `true` if the token is cancelled
+  bool is_cancelled() const;
+};
+
+/// Options of the `QuoteContext` and `TradeContext` calls
+struct CallOptions
+{
+  /// The calls fail if they are not completed within the timeout
+  std::optional<std::chrono::milliseconds> timeout;
+  /// The calls fail if they are not completed before the deadline
+  std::optional<std::chrono::system_clock::time_point> deadline;
+  /// The calls fail when the token is cancelled
+  std::optional<CancellationToken> cancellation_token;
+};
+
+} // namespace longport
@@ -84,6 +84,21 @@ public:
   /// - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
   static Status from_env(Config& config);
 
+  /// Create a new `Config` from a TOML file, or a YAML file if the extension
+  /// is `.yaml` or `.yml`
+  ///
+  /// The keys of the file are the names of the environment variables without
+  /// the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+  /// `push_candlestick_mode`.
+  ///
+  /// The options of `profile` in the `profiles` table override the top level
+  /// options, if `profile` is `std::nullopt`, the `LONGPORT_PROFILE`
+  /// environment variable is used. The environment variables override the
+  /// options of the file.
+  static Status from_file(Config& config,
+                          const std::string& path,
+                          const std::optional<std::string>& profile = std::nullopt);
+
   /// Gets a new `access_token`
   void refresh_access_token(int64_t expired_at,
                             AsyncCallback<void*, std::string> callback);
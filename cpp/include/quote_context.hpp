@@ -1,6 +1,7 @@
 #pragma once
 
 #include "async_result.hpp"
+#include "call_options.hpp"
 #include "callback.hpp"
 #include "config.hpp"
 #include "push.hpp"
@@ -31,6 +32,11 @@ public:
   static void create(const Config& config,
                      AsyncCallback<QuoteContext, void> callback);
 
+  /// Returns a `QuoteContext` sharing the connection with this one, whose calls
+  /// use the options, the push events are only delivered to the callbacks set
+  /// on this context
+  QuoteContext with_call_options(const CallOptions& options) const;
+
   /// Returns the member id
   int64_t member_id();
 
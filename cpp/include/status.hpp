@@ -5,6 +5,26 @@
 typedef struct lb_error_t lb_error_t;
 
 namespace longport {
+
+/// Error kind, mapped from the known error codes
+enum class ErrorKind
+{
+  /// The buying power is insufficient for the order
+  InsufficientBuyingPower,
+  /// The market is closed
+  MarketClosed,
+  /// The price does not match the tick size of the security
+  InvalidPriceTick,
+  /// Too many requests
+  RateLimited,
+  /// The access token is expired
+  TokenExpired,
+  /// The account has no permission for the request
+  PermissionDenied,
+  /// Other errors
+  Other,
+};
+
 class Status
 {
 private:
@@ -31,6 +51,20 @@ public:
 
   /// Returns the error message
   const char* message() const;
+
+  /// Returns the kind of the error
+  ErrorKind kind() const;
+
+  /// Returns the trace id of the failed HTTP request, or `nullptr`
+  const char* trace_id() const;
+
+  /// Returns `true` if the request may succeed when it is sent again, e.g. it
+  /// is rate limited, timed out or the connection is lost
+  bool is_retryable() const;
+
+  /// Returns `true` if the credentials are invalid or expired, or have no
+  /// permission for the request
+  bool is_auth() const;
 };
 
 } // namespace longport
\ No newline at end of file
@@ -1,6 +1,7 @@
 #pragma once
 
 #include "async_result.hpp"
+#include "call_options.hpp"
 #include "callback.hpp"
 #include "config.hpp"
 #include "push.hpp"
@@ -31,6 +32,11 @@ public:
   static void create(const Config& config,
                      AsyncCallback<TradeContext, void> callback);
 
+  /// Returns a `TradeContext` sharing the connection with this one, whose calls
+  /// use the options, the push events are only delivered to the callbacks set
+  /// on this context
+  TradeContext with_call_options(const CallOptions& options) const;
+
   /// Subscribe
   void subscribe(const std::vector<TopicType>& topics,
                  AsyncCallback<TradeContext, void> callback) const;
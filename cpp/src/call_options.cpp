@@ -0,0 +1,28 @@
+#include "call_options.hpp"
+#include "longport.h"
+
+namespace longport {
+
+CancellationToken::CancellationToken()
+  : token_(lb_cancellation_token_new(), lb_cancellation_token_free)
+{
+}
+
+CancellationToken::operator const lb_cancellation_token_t*() const
+{
+  return token_.get();
+}
+
+void
+CancellationToken::cancel() const
+{
+  lb_cancellation_token_cancel(token_.get());
+}
+
+bool
+CancellationToken::is_cancelled() const
+{
+  return lb_cancellation_token_is_cancelled(token_.get());
+}
+
+} // namespace longport
@@ -81,6 +81,21 @@ Config::from_env(Config& config)
   return status;
 }
 
+Status
+Config::from_file(Config& config,
+                  const std::string& path,
+                  const std::optional<std::string>& profile)
+{
+  lb_error_t* err = nullptr;
+  lb_config_t* config_ptr = lb_config_from_file(
+    path.c_str(), profile ? profile->c_str() : nullptr, &err);
+  Status status(err);
+  if (status.is_ok()) {
+    config.config_ = config_ptr;
+  }
+  return status;
+}
+
 void
 Config::refresh_access_token(int64_t expired_at,
                              AsyncCallback<void*, std::string> callback)
@@ -64,4 +64,51 @@ Status::message() const
   return err_ ? lb_error_message(err_) : "no error";
 }
 
+/// Returns the kind of the error
+ErrorKind
+Status::kind() const
+{
+  if (!err_) {
+    return ErrorKind::Other;
+  }
+  switch (lb_error_kind(err_)) {
+    case ErrorKindInsufficientBuyingPower:
+      return ErrorKind::InsufficientBuyingPower;
+    case ErrorKindMarketClosed:
+      return ErrorKind::MarketClosed;
+    case ErrorKindInvalidPriceTick:
+      return ErrorKind::InvalidPriceTick;
+    case ErrorKindRateLimited:
+      return ErrorKind::RateLimited;
+    case ErrorKindTokenExpired:
+      return ErrorKind::TokenExpired;
+    case ErrorKindPermissionDenied:
+      return ErrorKind::PermissionDenied;
+    default:
+      return ErrorKind::Other;
+  }
+}
+
+/// Returns the trace id of the failed HTTP request, or `nullptr`
+const char*
+Status::trace_id() const
+{
+  return err_ ? lb_error_trace_id(err_) : nullptr;
+}
+
+/// Returns `true` if the request may succeed when it is sent again
+bool
+Status::is_retryable() const
+{
+  return err_ && lb_error_is_retryable(err_);
+}
+
+/// Returns `true` if the credentials are invalid or expired, or have no
+/// permission for the request
+bool
+Status::is_auth() const
+{
+  return err_ && lb_error_is_auth(err_);
+}
+
 } // namespace longport
\ No newline at end of file
@@ -80,6 +80,28 @@ TradeContext::create(const Config& config,
     new AsyncCallback<TradeContext, void>(callback));
 }
 
+TradeContext
+TradeContext::with_call_options(const CallOptions& options) const
+{
+  using namespace std::chrono;
+
+  const lb_cancellation_token_t* cancellation_token = nullptr;
+  if (options.cancellation_token) {
+    cancellation_token = *options.cancellation_token;
+  }
+  auto* ctx_ptr = lb_trade_context_with_call_options(
+    ctx_,
+    options.timeout ? (uint64_t)options.timeout->count() : 0,
+    options.deadline ? (int64_t)duration_cast<milliseconds>(
+                         options.deadline->time_since_epoch())
+                         .count()
+                     : 0,
+    cancellation_token);
+  TradeContext ctx(ctx_ptr);
+  lb_trade_context_release(ctx_ptr);
+  return ctx;
+}
+
 void
 TradeContext::subscribe(const std::vector<TopicType>& topics,
                         AsyncCallback<TradeContext, void> callback) const
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use longport::{quote::QuoteMuxServer, Config};
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    // Other processes connect to this server by setting
+    // `LONGPORT_QUOTE_MUX_PATH` to the same path.
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "/tmp/longport-quote.sock".to_string());
+    let config = Arc::new(Config::from_env()?);
+    let server = QuoteMuxServer::bind(config, path).await?;
+    server.run().await?;
+    Ok(())
+}
@@ -0,0 +1,72 @@
+package com.longport;
+
+import java.time.Duration;
+import java.time.Instant;
+
+/**
+ * Options of the `QuoteContext` and `TradeContext` calls
+ */
+public class CallOptions {
+    private Duration timeout;
+    private Instant deadline;
+    private CancellationToken cancellationToken;
+
+    /**
+     * Specifies the timeout of the calls, the calls fail if they are not
+     * completed within the timeout
+     * 
+     * @param timeout Timeout
+     * @return this object
+     */
+    public CallOptions timeout(Duration timeout) {
+        this.timeout = timeout;
+        return this;
+    }
+
+    /**
+     * Specifies the deadline of the calls, the calls fail if they are not
+     * completed before the deadline
+     * 
+     * @param deadline Deadline
+     * @return this object
+     */
+    public CallOptions deadline(Instant deadline) {
+        this.deadline = deadline;
+        return this;
+    }
+
+    /**
+     * Specifies the cancellation token, the calls fail when it is cancelled
+     * 
+     * @param cancellationToken Cancellation token
+     * @return this object
+     */
+    public CallOptions cancellationToken(CancellationToken cancellationToken) {
+        this.cancellationToken = cancellationToken;
+        return this;
+    }
+
+    /**
+     * @hidden
+     * @return Timeout in milliseconds, `0` if not specified
+     */
+    public long getTimeoutMillis() {
+        return timeout != null ? Math.max(timeout.toMillis(), 1) : 0;
+    }
+
+    /**
+     * @hidden
+     * @return Deadline as a unix timestamp in milliseconds, `0` if not specified
+     */
+    public long getDeadlineMillis() {
+        return deadline != null ? Math.max(deadline.toEpochMilli(), 1) : 0;
+    }
+
+    /**
+     * @hidden
+     * @return Cancellation token pointer, `0` if not specified
+     */
+    public long getCancellationTokenRaw() {
+        return cancellationToken != null ? cancellationToken.getRaw() : 0;
+    }
+}
@@ -0,0 +1,45 @@
+package com.longport;
+
+/**
+ * A token to cancel the calls of the contexts returned by
+ * `withCallOptions`
+ */
+public class CancellationToken implements AutoCloseable {
+    private long raw;
+
+    /**
+     * Create a new cancellation token
+     */
+    public CancellationToken() {
+        this.raw = SdkNative.newCancellationToken();
+    }
+
+    /**
+     * @hidden
+     * @return Token pointer
+     */
+    public long getRaw() {
+        return this.raw;
+    }
+
+    /**
+     * Cancels the calls, the pending and the following calls fail
+     */
+    public void cancel() {
+        SdkNative.cancellationTokenCancel(this.raw);
+    }
+
+    /**
+     * Returns `true` if the token is cancelled
+     * 
+     * @return `true` if the token is cancelled
+     */
+    public boolean isCancelled() {
+        return SdkNative.cancellationTokenIsCancelled(this.raw);
+    }
+
+    @Override
+    public void close() throws Exception {
+        SdkNative.freeCancellationToken(raw);
+    }
+}
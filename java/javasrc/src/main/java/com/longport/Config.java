@@ -50,6 +50,27 @@ public class Config implements AutoCloseable {
         return new Config(SdkNative.newConfigFromEnv());
     }
 
+    /**
+     * Create a new `Config` from a TOML file, or a YAML file if the extension is
+     * `.yaml` or `.yml`
+     * <p>
+     * The keys of the file are the names of the environment variables without
+     * the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+     * `push_candlestick_mode`.
+     * <p>
+     * The options of `profile` in the `profiles` table override the top level
+     * options, if `profile` is null, the `LONGPORT_PROFILE` environment variable
+     * is used. The environment variables override the options of the file.
+     * 
+     * @param path    The path of the config file
+     * @param profile The name of the profile, may be null
+     * @return Config object
+     * @throws OpenApiException If an error occurs
+     */
+    public static Config fromFile(String path, String profile) throws OpenApiException {
+        return new Config(SdkNative.newConfigFromFile(path, profile));
+    }
+
     /**
      * @hidden
      * @return Context pointer
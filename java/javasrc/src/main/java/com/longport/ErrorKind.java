@@ -0,0 +1,35 @@
+package com.longport;
+
+/**
+ * Error kind, mapped from the known error codes
+ */
+public enum ErrorKind {
+    /**
+     * The buying power is insufficient for the order
+     */
+    InsufficientBuyingPower,
+    /**
+     * The market is closed
+     */
+    MarketClosed,
+    /**
+     * The price does not match the tick size of the security
+     */
+    InvalidPriceTick,
+    /**
+     * Too many requests
+     */
+    RateLimited,
+    /**
+     * The access token is expired
+     */
+    TokenExpired,
+    /**
+     * The account has no permission for the request
+     */
+    PermissionDenied,
+    /**
+     * Other errors
+     */
+    Other,
+}
@@ -3,12 +3,26 @@ package com.longport;
 public class OpenApiException extends Exception {
     private Long code;
     private String message;
+    private String traceId;
+    private ErrorKind kind = ErrorKind.Other;
+    private boolean retryable;
+    private boolean auth;
 
     public OpenApiException(Long code, String message) {
         this.code = code;
         this.message = message;
     }
 
+    public OpenApiException(Long code, String message, String traceId, ErrorKind kind, boolean retryable,
+            boolean auth) {
+        this.code = code;
+        this.message = message;
+        this.traceId = traceId;
+        this.kind = kind;
+        this.retryable = retryable;
+        this.auth = auth;
+    }
+
     public Long getCode() {
         return code;
     }
@@ -17,8 +31,47 @@ public class OpenApiException extends Exception {
         return message;
     }
 
+    /**
+     * Returns the trace id of the failed HTTP request
+     * 
+     * @return Trace id, or {@code null}
+     */
+    public String getTraceId() {
+        return traceId;
+    }
+
+    /**
+     * Returns the kind of the error
+     * 
+     * @return Error kind
+     */
+    public ErrorKind getKind() {
+        return kind;
+    }
+
+    /**
+     * Returns {@code true} if the request may succeed when it is sent again,
+     * e.g. it is rate limited, timed out or the connection is lost
+     * 
+     * @return Whether the request can be retried
+     */
+    public boolean isRetryable() {
+        return retryable;
+    }
+
+    /**
+     * Returns {@code true} if the credentials are invalid or expired, or have
+     * no permission for the request
+     * 
+     * @return Whether the error is caused by the credentials
+     */
+    public boolean isAuth() {
+        return auth;
+    }
+
     @Override
     public String toString() {
-        return "OpenApiException [code=" + code + ", message=" + message + "]";
+        return "OpenApiException [code=" + code + ", kind=" + kind + ", traceId=" + traceId + ", message=" + message
+                + "]";
     }
 }
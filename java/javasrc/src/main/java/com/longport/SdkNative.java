@@ -30,15 +30,28 @@ public class SdkNative {
 
         public static native long newConfigFromEnv();
 
+        public static native long newConfigFromFile(String path, String profile);
+
         public static native void configRefreshAccessToken(long config, OffsetDateTime expired_at,
                         AsyncCallback callback);
 
         public static native void freeConfig(long config);
 
+        public static native long newCancellationToken();
+
+        public static native void freeCancellationToken(long token);
+
+        public static native void cancellationTokenCancel(long token);
+
+        public static native boolean cancellationTokenIsCancelled(long token);
+
         public static native void newQuoteContext(long config, AsyncCallback callback);
 
         public static native void freeQuoteContext(long config);
 
+        public static native long quoteContextWithCallOptions(long context, long timeout, long deadline,
+                        long cancellationToken);
+
         public static native long quoteContextGetMemberId(long context);
 
         public static native String quoteContextGetQuoteLevel(long context);
@@ -159,6 +172,9 @@ public class SdkNative {
 
         public static native void freeTradeContext(long config);
 
+        public static native long tradeContextWithCallOptions(long context, long timeout, long deadline,
+                        long cancellationToken);
+
         public static native void tradeContextSetOnOrderChanged(long context, OrderChangedHandler handler);
 
         public static native void tradeContextSubscribe(long context, TopicType[] topics, AsyncCallback callback);
@@ -31,6 +31,22 @@ public class QuoteContext implements AutoCloseable {
         SdkNative.freeQuoteContext(raw);
     }
 
+    /**
+     * Returns a `QuoteContext` sharing the connection and the callbacks with
+     * this one, whose calls use the options
+     * <p>
+     * The returned context must be closed separately.
+     * 
+     * @param options Call options
+     * @return A `QuoteContext` object
+     */
+    public QuoteContext withCallOptions(CallOptions options) {
+        QuoteContext ctx = new QuoteContext();
+        ctx.raw = SdkNative.quoteContextWithCallOptions(this.raw, options.getTimeoutMillis(),
+                options.getDeadlineMillis(), options.getCancellationTokenRaw());
+        return ctx;
+    }
+
     /**
      * Returns the member ID
      * 
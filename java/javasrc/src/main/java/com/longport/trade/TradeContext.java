@@ -29,6 +29,22 @@ public class TradeContext implements AutoCloseable {
         SdkNative.freeTradeContext(raw);
     }
 
+    /**
+     * Returns a `TradeContext` sharing the connection and the callbacks with
+     * this one, whose calls use the options
+     * <p>
+     * The returned context must be closed separately.
+     * 
+     * @param options Call options
+     * @return A `TradeContext` object
+     */
+    public TradeContext withCallOptions(CallOptions options) {
+        TradeContext ctx = new TradeContext();
+        ctx.raw = SdkNative.tradeContextWithCallOptions(this.raw, options.getTimeoutMillis(),
+                options.getDeadlineMillis(), options.getCancellationTokenRaw());
+        return ctx;
+    }
+
     /**
      * Set order changed event callback, after receiving the order changed event, it
      * will call back to this handler.
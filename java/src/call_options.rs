@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use jni::{
+    JNIEnv,
+    objects::JClass,
+    sys::{jboolean, jlong},
+};
+use longport::{CallOptions, CancellationToken};
+
+/// `timeout` is in milliseconds and `deadline` is a unix timestamp in
+/// milliseconds, `0` means no timeout, deadline or cancellation token
+pub(crate) unsafe fn call_options(
+    timeout: jlong,
+    deadline: jlong,
+    cancellation_token: jlong,
+) -> CallOptions {
+    let mut options = CallOptions::new();
+    if timeout > 0 {
+        options = options.timeout(Duration::from_millis(timeout as u64));
+    }
+    if deadline > 0 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let remaining = Duration::from_millis(deadline.saturating_sub(now).max(0) as u64);
+        options = options.deadline(Instant::now() + remaining);
+    }
+    if cancellation_token != 0 {
+        options =
+            options.cancellation_token((*(cancellation_token as *const CancellationToken)).clone());
+    }
+    options
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_longport_SdkNative_newCancellationToken(
+    _env: JNIEnv,
+    _class: JClass,
+) -> jlong {
+    Box::into_raw(Box::new(CancellationToken::new())) as jlong
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_freeCancellationToken(
+    _env: JNIEnv,
+    _class: JClass,
+    token: jlong,
+) {
+    let _ = Box::from_raw(token as *mut CancellationToken);
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_cancellationTokenCancel(
+    _env: JNIEnv,
+    _class: JClass,
+    token: jlong,
+) {
+    (*(token as *const CancellationToken)).cancel();
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_cancellationTokenIsCancelled(
+    _env: JNIEnv,
+    _class: JClass,
+    token: jlong,
+) -> jboolean {
+    (*(token as *const CancellationToken)).is_cancelled() as jboolean
+}
@@ -78,6 +78,21 @@ pub extern "system" fn Java_com_longport_SdkNative_newConfigFromEnv(
     })
 }
 
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_longport_SdkNative_newConfigFromFile(
+    mut env: JNIEnv,
+    _class: JClass,
+    path: JString,
+    profile: JString,
+) -> jlong {
+    jni_result(&mut env, 0, |env| {
+        let path = String::from_jvalue(env, path.into())?;
+        let profile = <Option<String>>::from_jvalue(env, profile.into())?;
+        let config = Config::from_file(path, profile.as_deref())?;
+        Ok(Box::into_raw(Box::new(config)) as jlong)
+    })
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_freeConfig(
     _env: JNIEnv,
@@ -6,7 +6,10 @@ use jni::{
     objects::{JObject, JThrowable, JValue},
 };
 
-use crate::init::{LONG_CLASS, OPENAPI_EXCEPTION_CLASS};
+use crate::{
+    init::{LONG_CLASS, OPENAPI_EXCEPTION_CLASS},
+    types::IntoJValue,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum JniError {
@@ -42,6 +45,9 @@ impl JniError {
         err: longport::Error,
     ) -> Result<JObject<'a>> {
         let exception_cls = OPENAPI_EXCEPTION_CLASS.get().unwrap();
+        let kind = err.kind().into_jvalue(env)?.l()?;
+        let retryable = err.is_retryable();
+        let auth = err.is_auth();
         let err = err.into_simple_error();
 
         let code = match err.code() {
@@ -51,11 +57,22 @@ impl JniError {
             None => JObject::null(),
         };
         let message: JObject = env.new_string(err.message())?.into();
+        let trace_id = match err.trace_id() {
+            Some(trace_id) if !trace_id.is_empty() => env.new_string(trace_id)?.into(),
+            _ => JObject::null(),
+        };
 
         env.new_object(
             exception_cls,
-            "(Ljava/lang/Long;Ljava/lang/String;)V",
-            &[JValue::from(&code), JValue::from(&message)],
+            "(Ljava/lang/Long;Ljava/lang/String;Ljava/lang/String;Lcom/longport/ErrorKind;ZZ)V",
+            &[
+                JValue::from(&code),
+                JValue::from(&message),
+                JValue::from(&trace_id),
+                JValue::from(&kind),
+                JValue::from(retryable),
+                JValue::from(auth),
+            ],
         )
     }
 
@@ -81,6 +81,7 @@ pub extern "system" fn Java_com_longport_SdkNative_init<'a>(
         longport::Language,
         longport::PushCandlestickMode,
         longport::Market,
+        longport::ErrorKind,
         longport::quote::TradeStatus,
         longport::quote::TradeSession,
         longport::quote::TradeDirection,
@@ -1,7 +1,9 @@
 #![allow(clippy::missing_safety_doc)]
+#![allow(clippy::result_large_err)]
 #![allow(unsafe_op_in_unsafe_fn)]
 
 mod async_util;
+mod call_options;
 mod config;
 mod error;
 mod http_client;
@@ -20,6 +20,7 @@ use time::{Date, PrimitiveDateTime};
 
 use crate::{
     async_util,
+    call_options::call_options,
     error::jni_result,
     init::QUOTE_CONTEXT_CLASS,
     types::{
@@ -164,6 +165,23 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_freeQuoteContext(
     let _ = Box::from_raw(ctx as *mut ContextObj);
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextWithCallOptions(
+    _env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    timeout: i64,
+    deadline: i64,
+    cancellation_token: i64,
+) -> i64 {
+    let context = &*(ctx as *const ContextObj);
+    let options = call_options(timeout, deadline, cancellation_token);
+    Box::into_raw(Box::new(ContextObj {
+        ctx: context.ctx.with_call_options(options),
+        callbacks: context.callbacks.clone(),
+    })) as i64
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_quoteContextGetMemberId(
     mut _env: JNIEnv,
@@ -21,6 +21,7 @@ use time::{Date, OffsetDateTime};
 
 use crate::{
     async_util,
+    call_options::call_options,
     error::jni_result,
     init::TRADE_CONTEXT_CLASS,
     types::{FromJValue, IntoJValue, ObjectArray, get_field, set_field},
@@ -109,6 +110,23 @@ pub unsafe extern "system" fn Java_com_longport_SdkNative_freeTradeContext(
     let _ = Box::from_raw(ctx as *mut ContextObj);
 }
 
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_longport_SdkNative_tradeContextWithCallOptions(
+    _env: JNIEnv,
+    _class: JClass,
+    ctx: i64,
+    timeout: i64,
+    deadline: i64,
+    cancellation_token: i64,
+) -> i64 {
+    let context = &*(ctx as *const ContextObj);
+    let options = call_options(timeout, deadline, cancellation_token);
+    Box::into_raw(Box::new(ContextObj {
+        ctx: context.ctx.with_call_options(options),
+        callbacks: context.callbacks.clone(),
+    })) as i64
+}
+
 #[unsafe(no_mangle)]
 pub unsafe extern "system" fn Java_com_longport_SdkNative_tradeContextSetOnOrderChanged(
     mut env: JNIEnv,
@@ -30,6 +30,20 @@ impl_java_enum!(
     [Unknown, US, HK, CN, SG, Crypto]
 );
 
+impl_java_enum!(
+    "com/longport/ErrorKind",
+    longport::ErrorKind,
+    [
+        InsufficientBuyingPower,
+        MarketClosed,
+        InvalidPriceTick,
+        RateLimited,
+        TokenExpired,
+        PermissionDenied,
+        Other
+    ]
+);
+
 impl_java_enum!(
     "com/longport/quote/TradeStatus",
     longport::quote::TradeStatus,
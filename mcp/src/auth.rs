@@ -0,0 +1,428 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures_util::StreamExt;
+use poem::{
+    Body, Endpoint, IntoResponse, Middleware, Request, Response,
+    http::{Method, StatusCode, header},
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::server::{ACCOUNT_TOOLS, QUOTE_TOOLS, WRITE_TOOLS};
+
+/// The max size of a request body
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// The scope of an access token
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Scope {
+    /// Read the quotes
+    Quote,
+    /// Read the account, positions, orders and watchlist
+    Account,
+    /// Submit, replace and cancel orders, and modify the watchlist
+    Trade,
+}
+
+impl Scope {
+    /// Returns the scope required to call the tool, or `None` if the tool is
+    /// unknown
+    pub(crate) fn of_tool(name: &str) -> Option<Self> {
+        if WRITE_TOOLS.contains(&name) {
+            Some(Scope::Trade)
+        } else if ACCOUNT_TOOLS.contains(&name) {
+            Some(Scope::Account)
+        } else if QUOTE_TOOLS.contains(&name) {
+            Some(Scope::Quote)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenConfig {
+    /// The name of the client, used in the audit logs
+    name: String,
+    token: String,
+    scopes: HashSet<Scope>,
+    /// The max number of tool calls per minute
+    rate_limit: Option<u32>,
+}
+
+/// The access tokens file
+///
+/// ```toml
+/// [[tokens]]
+/// name = "alice"
+/// token = "secret"
+/// scopes = ["quote", "account", "trade"]
+/// rate_limit = 60
+/// ```
+#[derive(Debug, Deserialize)]
+struct AuthConfig {
+    tokens: Vec<TokenConfig>,
+}
+
+/// Allows at most `limit` calls per minute
+struct RateLimiter {
+    limit: u32,
+    window: Mutex<(Instant, u32)>,
+}
+
+impl RateLimiter {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            window: Mutex::new((Instant::now(), 0)),
+        }
+    }
+
+    fn acquire(&self, n: u32) -> bool {
+        let mut window = self.window.lock().unwrap();
+        let (start, count) = &mut *window;
+        if start.elapsed() >= Duration::from_secs(60) {
+            *start = Instant::now();
+            *count = 0;
+        }
+        if *count + n > self.limit {
+            return false;
+        }
+        *count += n;
+        true
+    }
+}
+
+/// The client authenticated by an access token
+#[derive(Clone)]
+pub(crate) struct Client {
+    name: Arc<str>,
+    scopes: HashSet<Scope>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl Client {
+    /// Returns `true` if the client can call the tool, the unknown tools are
+    /// always denied
+    pub(crate) fn can_call(&self, tool: &str) -> bool {
+        Scope::of_tool(tool).is_some_and(|scope| self.has_scope(scope))
+    }
+
+    /// Returns `true` if the client has the scope
+    pub(crate) fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// Returns `true` if both clients are authenticated by the same access
+    /// token, the clients of a token share its name
+    pub(crate) fn is_same(&self, other: &Client) -> bool {
+        Arc::ptr_eq(&self.name, &other.name)
+    }
+}
+
+/// A middleware authenticating the requests with bearer tokens, checking the
+/// scopes and the rate limits of the tool calls, and writing the audit logs
+#[derive(Default)]
+pub(crate) struct BearerAuth {
+    clients: Arc<HashMap<String, Client>>,
+}
+
+impl BearerAuth {
+    /// Loads the access tokens from a TOML file
+    pub(crate) fn from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let config: AuthConfig = toml::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(Self::from_config(config))
+    }
+
+    fn from_config(config: AuthConfig) -> Self {
+        let clients = config
+            .tokens
+            .into_iter()
+            .map(|config| {
+                let client = Client {
+                    name: config.name.into(),
+                    scopes: config.scopes,
+                    rate_limiter: config
+                        .rate_limit
+                        .map(|limit| Arc::new(RateLimiter::new(limit))),
+                };
+                (config.token, client)
+            })
+            .collect();
+        Self {
+            clients: Arc::new(clients),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for BearerAuth {
+    type Output = BearerAuthEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        BearerAuthEndpoint {
+            inner: ep,
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+pub(crate) struct BearerAuthEndpoint<E> {
+    inner: E,
+    clients: Arc<HashMap<String, Client>>,
+}
+
+struct ToolCall {
+    id: Value,
+    name: String,
+    arguments: Value,
+}
+
+/// Returns the `tools/call` requests of a JSON-RPC message or batch
+fn tool_calls(body: &[u8]) -> Vec<ToolCall> {
+    let messages = match serde_json::from_slice(body) {
+        Ok(Value::Array(messages)) => messages,
+        Ok(message) => vec![message],
+        Err(_) => return vec![],
+    };
+    messages
+        .into_iter()
+        .filter(|message| message["method"] == "tools/call")
+        .map(|mut message| ToolCall {
+            id: message["id"].clone(),
+            name: message["params"]["name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            arguments: message
+                .pointer_mut("/params/arguments")
+                .map(Value::take)
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+impl<E: Endpoint> Endpoint for BearerAuthEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        let client = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| self.clients.get(token.trim()))
+            .cloned();
+        let Some(client) = client else {
+            return Ok(StatusCode::UNAUTHORIZED
+                .with_header(header::WWW_AUTHENTICATE, "Bearer")
+                .into_response());
+        };
+
+        let mut calls = vec![];
+        if req.method() == Method::POST {
+            let body = req.take_body().into_bytes_limit(MAX_BODY_SIZE).await?;
+            calls = tool_calls(&body);
+            req.set_body(body);
+        }
+
+        for call in &calls {
+            if !client.can_call(&call.name) {
+                tracing::warn!(
+                    target: "audit",
+                    client = %client.name,
+                    tool = %call.name,
+                    arguments = %call.arguments,
+                    "tool call denied, unknown tool or missing scope"
+                );
+                return Ok(StatusCode::FORBIDDEN.into_response());
+            }
+        }
+        if let Some(rate_limiter) = &client.rate_limiter
+            && !calls.is_empty()
+            && !rate_limiter.acquire(calls.len() as u32)
+        {
+            tracing::warn!(
+                target: "audit",
+                client = %client.name,
+                "tool call denied, rate limit exceeded"
+            );
+            return Ok(StatusCode::TOO_MANY_REQUESTS.into_response());
+        }
+        for call in &calls {
+            tracing::info!(
+                target: "audit",
+                client = %client.name,
+                id = %call.id,
+                tool = %call.name,
+                arguments = %call.arguments,
+                "tool call"
+            );
+        }
+
+        let client_name = client.name.clone();
+        req.extensions_mut().insert(client);
+        let mut resp = self.inner.call(req).await?.into_response();
+        if !calls.is_empty() {
+            let ids = calls
+                .iter()
+                .map(|call| call.id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let status = resp.status();
+            let body = resp.take_body().into_bytes_stream().inspect(move |chunk| {
+                if let Ok(chunk) = chunk {
+                    tracing::info!(
+                        target: "audit",
+                        client = %client_name,
+                        ids = %ids,
+                        status = %status,
+                        result = %String::from_utf8_lossy(chunk),
+                        "tool result"
+                    );
+                }
+            });
+            resp.set_body(Body::from_bytes_stream(body));
+        }
+        Ok(resp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poem::{EndpointExt, endpoint::make_sync};
+    use serde_json::json;
+
+    use super::*;
+
+    fn endpoint() -> impl Endpoint<Output = Response> {
+        let config = toml::from_str(
+            r#"
+            [[tokens]]
+            name = "reader"
+            token = "quote-token"
+            scopes = ["quote"]
+            rate_limit = 2
+            "#,
+        )
+        .unwrap();
+        make_sync(|_| "ok")
+            .with(BearerAuth::from_config(config))
+            .map_to_response()
+    }
+
+    fn request(token: Option<&str>, body: Value) -> Request {
+        let mut builder = Request::builder().method(Method::POST);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(body.to_string())
+    }
+
+    fn tool_call(id: u32, name: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": {} },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_missing_token() {
+        let ep = endpoint();
+        let resp = ep.get_response(request(None, tool_call(1, "quote"))).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = ep
+            .get_response(request(Some("invalid"), tool_call(1, "quote")))
+            .await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        let ep = endpoint();
+        let resp = ep
+            .get_response(request(Some("quote-token"), tool_call(1, "quote")))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = ep
+            .get_response(request(
+                Some("quote-token"),
+                tool_call(1, "account_balance"),
+            ))
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = ep
+            .get_response(request(Some("quote-token"), tool_call(1, "unknown_tool")))
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_batch() {
+        let ep = endpoint();
+        let resp = ep
+            .get_response(request(
+                Some("quote-token"),
+                json!([tool_call(1, "quote"), tool_call(2, "submit_order")]),
+            ))
+            .await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let ep = endpoint();
+        let resp = ep
+            .get_response(request(
+                Some("quote-token"),
+                json!([tool_call(1, "quote"), tool_call(2, "depth")]),
+            ))
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = ep
+            .get_response(request(Some("quote-token"), tool_call(3, "quote")))
+            .await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_same_client() {
+        let config = toml::from_str(
+            r#"
+            [[tokens]]
+            name = "reader"
+            token = "token-1"
+            scopes = ["quote"]
+
+            [[tokens]]
+            name = "reader"
+            token = "token-2"
+            scopes = ["quote"]
+            "#,
+        )
+        .unwrap();
+        let auth = BearerAuth::from_config(config);
+        let client = &auth.clients["token-1"];
+        assert!(client.is_same(&client.clone()));
+        assert!(!client.is_same(&auth.clients["token-2"]));
+    }
+
+    #[tokio::test]
+    async fn test_body_size() {
+        let ep = endpoint();
+        let resp = ep
+            .get_response(request(
+                Some("quote-token"),
+                Value::String("x".repeat(MAX_BODY_SIZE)),
+            ))
+            .await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
@@ -1,12 +1,27 @@
+#![allow(clippy::result_large_err)]
+
+mod auth;
+mod orders;
+mod overview;
+mod prompts;
+mod resources;
 mod server;
+mod session;
+mod stdio;
+mod streamable_http;
 
 use std::{path::PathBuf, sync::Arc};
 
+use auth::{BearerAuth, Client};
 use clap::Parser;
-use longport::{Config, QuoteContext, TradeContext};
-use poem::{EndpointExt, Route, Server, listener::TcpListener, middleware::Cors};
-use poem_mcpserver::{McpServer, stdio::stdio, streamable_http};
-use server::Longport;
+use longport::{Config, Decimal, QuoteContext, TradeContext};
+use orders::OrderLimits;
+use poem::{EndpointExt, IntoEndpoint, Route, Server, listener::TcpListener, middleware::Cors};
+use poem_mcpserver::McpServer;
+use resources::ResourceHub;
+use server::{ACCOUNT_TOOLS, Longport, QUOTE_TOOLS, WRITE_TOOLS};
+use session::Session;
+use stdio::stdio;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 
 #[derive(Parser)]
@@ -17,14 +32,40 @@ struct Cli {
     /// Bind address for the SSE server.
     #[clap(long, default_value = "127.0.0.1:8000")]
     bind: String,
+    /// Access tokens file of the Streamable-HTTP transport
+    ///
+    /// A TOML file with the tokens, their scopes (`quote`, `account` and
+    /// `trade`) and their rate limits. The requests without a valid bearer
+    /// token are rejected and every tool call is written to the audit logs.
+    #[clap(long)]
+    auth_tokens: Option<PathBuf>,
     /// Log directory
     #[clap(long)]
     log_dir: Option<PathBuf>,
     /// Read-only mode
     ///
-    /// This mode is used to prevent submitting orders to the exchange.
+    /// This mode is used to prevent submitting, replacing and cancelling orders
+    /// and modifying the watchlist.
     #[clap(long, default_value_t = false)]
     readonly: bool,
+    /// Allow submitting and replacing orders without confirmation
+    ///
+    /// By default `submit_order` and `replace_order` are disabled, the orders
+    /// must be previewed with `preview_order` or `preview_replace_order` and
+    /// sent with `confirm_order`. This mode enables `submit_order` and
+    /// `replace_order`.
+    #[clap(long, default_value_t = false)]
+    allow_direct_orders: bool,
+    /// The max number of orders submitted in a session
+    #[clap(long)]
+    max_orders: Option<usize>,
+    /// The max total notional (price × quantity) of the orders submitted in a
+    /// session, in each currency
+    ///
+    /// The orders without a known price are rejected, and the replacements
+    /// count the increase of the notional.
+    #[clap(long)]
+    max_order_notional: Option<Decimal>,
 }
 
 #[tokio::main]
@@ -46,26 +87,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             .inspect_err(|err| tracing::error!(error = %err, "failed to load config"))?
             .dont_print_quote_packages(),
     );
-    let (quote_context, _) = QuoteContext::try_new(config.clone()).await?;
-    let (trade_context, _) = TradeContext::try_new(config.clone()).await?;
-    let readonly = cli.readonly;
+    let (quote_context, quote_receiver) = QuoteContext::try_new(config.clone()).await?;
+    let (trade_context, trade_receiver) = TradeContext::try_new(config.clone()).await?;
+    let hub = ResourceHub::new(
+        quote_context.clone(),
+        trade_context.clone(),
+        quote_receiver,
+        trade_receiver,
+    );
+    let options = ServerOptions {
+        readonly: cli.readonly,
+        allow_direct_orders: cli.allow_direct_orders,
+        order_limits: OrderLimits {
+            max_orders: cli.max_orders,
+            max_notional: cli.max_order_notional,
+        },
+    };
 
     if !cli.http {
         tracing::info!("Starting MCP server with stdio transport");
-        let server = create_mcp_server(quote_context, trade_context, readonly);
-        stdio(server).await?;
+        let session = create_session(quote_context, trade_context, hub, options, None);
+        stdio(session).await?;
     } else {
         tracing::info!(
             "Starting MCP server with Streamable-HTTP transport, listening on {}",
             cli.bind
         );
         let listener = TcpListener::bind(&cli.bind);
+        let auth_enabled = cli.auth_tokens.is_some();
+        let auth = match &cli.auth_tokens {
+            Some(path) => Some(BearerAuth::from_file(path).inspect_err(
+                |err| tracing::error!(error = %err, "failed to load the access tokens"),
+            )?),
+            None => None,
+        };
         let app = Route::new()
             .at(
                 "/",
-                streamable_http::endpoint(move |_| {
-                    create_mcp_server(quote_context.clone(), trade_context.clone(), readonly)
-                }),
+                streamable_http::endpoint(move |req| {
+                    create_session(
+                        quote_context.clone(),
+                        trade_context.clone(),
+                        hub.clone(),
+                        options,
+                        req.extensions().get::<Client>(),
+                    )
+                })
+                .into_endpoint()
+                .with_if(auth_enabled, auth.unwrap_or_default()),
             )
             .with(Cors::new());
         Server::new(listener).run(app).await?;
@@ -74,14 +143,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn create_mcp_server(
+#[derive(Copy, Clone)]
+struct ServerOptions {
+    readonly: bool,
+    allow_direct_orders: bool,
+    order_limits: OrderLimits,
+}
+
+/// Creates a session, `client` is the client authenticated by the access token
+fn create_session(
     quote_context: QuoteContext,
     trade_context: TradeContext,
-    readonly: bool,
-) -> McpServer<Longport> {
-    let mut server = McpServer::new().tools(Longport::new(quote_context, trade_context));
-    if readonly {
-        server = server.disable_tools(["submit_order"]);
+    hub: Arc<ResourceHub>,
+    options: ServerOptions,
+    client: Option<&Client>,
+) -> Session {
+    let mut disabled_tools = Vec::new();
+    if options.readonly {
+        disabled_tools.extend(WRITE_TOOLS);
+    } else if !options.allow_direct_orders {
+        disabled_tools.extend(["submit_order", "replace_order"]);
+    }
+    if let Some(client) = client {
+        disabled_tools.extend(
+            QUOTE_TOOLS
+                .into_iter()
+                .chain(ACCOUNT_TOOLS)
+                .chain(WRITE_TOOLS)
+                .filter(|tool| !client.can_call(tool)),
+        );
     }
-    server
+    let server = McpServer::new()
+        .tools(Longport::new(
+            quote_context,
+            trade_context,
+            options.order_limits,
+        ))
+        .disable_tools(disabled_tools);
+    Session::new(server, hub, client.cloned())
 }
@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use longport::{
+    Decimal,
+    trade::{ReplaceOrderOptions, SubmitOrderOptions},
+};
+
+/// The time a confirmation token of `preview_order` is valid
+pub(crate) const CONFIRMATION_TOKEN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The limits of the orders submitted in a session
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct OrderLimits {
+    /// The max number of orders
+    pub(crate) max_orders: Option<usize>,
+    /// The max total notional (price × quantity) of the orders, applied to
+    /// each currency separately
+    pub(crate) max_notional: Option<Decimal>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum OrderError {
+    #[error(transparent)]
+    Longport(#[from] longport::Error),
+    #[error("invalid or expired confirmation token, preview the order again")]
+    InvalidToken,
+    #[error("the session can submit at most {max} orders")]
+    TooManyOrders { max: usize },
+    #[error(
+        "the order notional {notional} {currency} exceeds the notional limit of the session, {used} of {max} {currency} used"
+    )]
+    NotionalExceeded {
+        currency: String,
+        notional: Decimal,
+        used: Decimal,
+        max: Decimal,
+    },
+    #[error("the price of {symbol} is unknown, the notional of the order cannot be checked")]
+    UnknownPrice { symbol: String },
+}
+
+/// The notional of an order in the currency of the security
+#[derive(Debug, Clone)]
+pub(crate) struct Notional {
+    pub(crate) currency: String,
+    pub(crate) amount: Decimal,
+}
+
+/// The usage of the limits by an order request
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Usage {
+    /// The number of new orders
+    pub(crate) orders: usize,
+    /// The notional counted in the limits, `None` if the notional is not
+    /// limited
+    pub(crate) notional: Option<Notional>,
+}
+
+/// An order request waiting for the confirmation
+pub(crate) enum OrderRequest {
+    Submit(SubmitOrderOptions),
+    Replace {
+        order_id: String,
+        opts: ReplaceOrderOptions,
+    },
+}
+
+struct PendingOrder {
+    request: OrderRequest,
+    usage: Usage,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    pending: HashMap<String, PendingOrder>,
+    orders: usize,
+    notional: HashMap<String, Decimal>,
+}
+
+impl State {
+    fn add(&mut self, usage: &Usage) {
+        self.orders += usage.orders;
+        if let Some(notional) = &usage.notional {
+            *self.notional.entry(notional.currency.clone()).or_default() += notional.amount;
+        }
+    }
+
+    fn remove(&mut self, usage: &Usage) {
+        self.orders = self.orders.saturating_sub(usage.orders);
+        if let Some(notional) = &usage.notional
+            && let Some(used) = self.notional.get_mut(&notional.currency)
+        {
+            *used -= notional.amount;
+        }
+    }
+}
+
+/// The orders previewed and submitted in a session
+pub(crate) struct OrderGuard {
+    limits: OrderLimits,
+    state: Mutex<State>,
+}
+
+impl OrderGuard {
+    pub(crate) fn new(limits: OrderLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn limits(&self) -> OrderLimits {
+        self.limits
+    }
+
+    fn check(&self, state: &State, usage: &Usage) -> Result<(), OrderError> {
+        if let Some(max) = self.limits.max_orders
+            && usage.orders > 0
+            && state.orders + usage.orders > max
+        {
+            return Err(OrderError::TooManyOrders { max });
+        }
+        if let Some(max) = self.limits.max_notional
+            && let Some(notional) = &usage.notional
+        {
+            let used = state
+                .notional
+                .get(&notional.currency)
+                .copied()
+                .unwrap_or_default();
+            if used + notional.amount > max {
+                return Err(OrderError::NotionalExceeded {
+                    currency: notional.currency.clone(),
+                    notional: notional.amount,
+                    used,
+                    max,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether an order request is within the limits
+    pub(crate) fn check_limits(&self, usage: &Usage) -> Result<(), OrderError> {
+        self.check(&self.state.lock().unwrap(), usage)
+    }
+
+    /// Counts an order request in the limits before sending it, the usage must
+    /// be released if the request fails
+    pub(crate) fn reserve(&self, usage: &Usage) -> Result<(), OrderError> {
+        let mut state = self.state.lock().unwrap();
+        self.check(&state, usage)?;
+        state.add(usage);
+        Ok(())
+    }
+
+    /// Releases the usage of an order request which failed
+    pub(crate) fn release(&self, usage: &Usage) {
+        self.state.lock().unwrap().remove(usage);
+    }
+
+    /// Adds a previewed order request, returns the confirmation token
+    pub(crate) fn add_pending(&self, request: OrderRequest, usage: Usage) -> String {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        state.pending.retain(|_, order| order.expires_at > now);
+
+        let token = format!("{:016x}", rand::random::<u128>());
+        state.pending.insert(
+            token.clone(),
+            PendingOrder {
+                request,
+                usage,
+                expires_at: now + CONFIRMATION_TOKEN_TIMEOUT,
+            },
+        );
+        token
+    }
+
+    /// Takes the previewed order request of the confirmation token and counts
+    /// it in the limits, the usage must be released if the request fails
+    pub(crate) fn take_pending(&self, token: &str) -> Result<(OrderRequest, Usage), OrderError> {
+        let mut state = self.state.lock().unwrap();
+        let order = state
+            .pending
+            .remove(token)
+            .filter(|order| order.expires_at > Instant::now())
+            .ok_or(OrderError::InvalidToken)?;
+        self.check(&state, &order.usage)?;
+        state.add(&order.usage);
+        Ok((order.request, order.usage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(orders: usize, currency: &str, amount: i64) -> Usage {
+        Usage {
+            orders,
+            notional: Some(Notional {
+                currency: currency.to_string(),
+                amount: amount.into(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_notional_per_currency() {
+        let guard = OrderGuard::new(OrderLimits {
+            max_orders: Some(3),
+            max_notional: Some(100.into()),
+        });
+        guard.reserve(&usage(1, "USD", 80)).unwrap();
+        guard.reserve(&usage(1, "HKD", 80)).unwrap();
+        assert!(matches!(
+            guard.reserve(&usage(1, "USD", 30)),
+            Err(OrderError::NotionalExceeded { currency, .. }) if currency == "USD"
+        ));
+
+        // a replacement is not a new order
+        guard.reserve(&usage(0, "HKD", 20)).unwrap();
+        guard.reserve(&usage(1, "USD", 20)).unwrap();
+        assert!(matches!(
+            guard.reserve(&usage(1, "CNY", 1)),
+            Err(OrderError::TooManyOrders { max: 3 })
+        ));
+
+        guard.release(&usage(1, "USD", 20));
+        guard.reserve(&usage(1, "CNY", 1)).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, HashMap};
+
+use longport::{
+    Decimal, Error, Market, QuoteContext, TradeContext,
+    quote::{
+        AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
+        MarketCalendar, MarketTemperature, Period, SecurityCalcIndex, SecurityQuote,
+        SecurityStaticInfo, TradeSession, TradeSessions,
+    },
+    trade::AccountBalance,
+};
+use serde::Serialize;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// The indexes of `symbol_overview`
+const OVERVIEW_CALC_INDEXES: [CalcIndex; 11] = [
+    CalcIndex::ChangeRate,
+    CalcIndex::Volume,
+    CalcIndex::Turnover,
+    CalcIndex::TurnoverRate,
+    CalcIndex::TotalMarketValue,
+    CalcIndex::PeTtmRatio,
+    CalcIndex::PbRatio,
+    CalcIndex::DividendRatioTtm,
+    CalcIndex::FiveDayChangeRate,
+    CalcIndex::HalfYearChangeRate,
+    CalcIndex::YtdChangeRate,
+];
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PositionSummary {
+    account_channel: String,
+    symbol: String,
+    symbol_name: String,
+    currency: String,
+    quantity: Decimal,
+    cost_price: Decimal,
+    /// `None` if the quote is not available, the market value is the cost
+    last_done: Option<Decimal>,
+    market_value: Decimal,
+    unrealized_pnl: Decimal,
+    /// `None` if the cost is zero
+    unrealized_pnl_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct CurrencyExposure {
+    currency: String,
+    market_value: Decimal,
+    cost: Decimal,
+    unrealized_pnl: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PortfolioSummary {
+    balances: Vec<AccountBalance>,
+    positions: Vec<PositionSummary>,
+    exposures: Vec<CurrencyExposure>,
+}
+
+/// Returns the balances, the positions valued at the latest prices and the
+/// market value of the positions in each currency
+pub(crate) async fn portfolio_summary(
+    quote_context: &QuoteContext,
+    trade_context: &TradeContext,
+) -> Result<PortfolioSummary, Error> {
+    let (balances, positions) = tokio::try_join!(
+        trade_context.account_balance(None),
+        trade_context.stock_positions(None),
+    )?;
+    let positions = positions
+        .channels
+        .into_iter()
+        .flat_map(|channel| {
+            let account_channel = channel.account_channel;
+            channel
+                .positions
+                .into_iter()
+                .map(move |position| (account_channel.clone(), position))
+        })
+        .collect::<Vec<_>>();
+
+    let mut symbols = positions
+        .iter()
+        .map(|(_, position)| position.symbol.clone())
+        .collect::<Vec<_>>();
+    symbols.sort();
+    symbols.dedup();
+    let last_done = match symbols.is_empty() {
+        true => HashMap::new(),
+        false => quote_context
+            .quote(symbols)
+            .await?
+            .into_iter()
+            .map(|quote| (quote.symbol, quote.last_done))
+            .collect(),
+    };
+
+    let mut exposures: BTreeMap<String, CurrencyExposure> = BTreeMap::new();
+    let positions = positions
+        .into_iter()
+        .map(|(account_channel, position)| {
+            let last_done = last_done.get(&position.symbol).copied();
+            let cost = position.cost_price * position.quantity;
+            let market_value = last_done.map_or(cost, |price| price * position.quantity);
+            let unrealized_pnl = market_value - cost;
+
+            let exposure = exposures.entry(position.currency.clone()).or_default();
+            exposure.currency.clone_from(&position.currency);
+            exposure.market_value += market_value;
+            exposure.cost += cost;
+            exposure.unrealized_pnl += unrealized_pnl;
+
+            PositionSummary {
+                account_channel,
+                symbol: position.symbol,
+                symbol_name: position.symbol_name,
+                currency: position.currency,
+                quantity: position.quantity,
+                cost_price: position.cost_price,
+                last_done,
+                market_value,
+                unrealized_pnl,
+                unrealized_pnl_rate: (!cost.is_zero()).then(|| unrealized_pnl / cost.abs()),
+            }
+        })
+        .collect();
+
+    Ok(PortfolioSummary {
+        balances,
+        positions,
+        exposures: exposures.into_values().collect(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SymbolOverview {
+    static_info: SecurityStaticInfo,
+    quote: SecurityQuote,
+    /// `None` if the indexes are not available for the security
+    calc_indexes: Option<SecurityCalcIndex>,
+    /// The latest capital flow of the day, `None` if it is not available for
+    /// the security
+    capital_flow: Option<CapitalFlowLine>,
+    /// `None` if it is not available for the security
+    capital_distribution: Option<CapitalDistributionResponse>,
+    candlesticks: Vec<Candlestick>,
+}
+
+/// Returns the static info, quote, indexes, capital flow, capital
+/// distribution and recent daily candlesticks of the security
+pub(crate) async fn symbol_overview(
+    quote_context: &QuoteContext,
+    symbol: String,
+    candlestick_count: usize,
+) -> Result<SymbolOverview, Error> {
+    let (static_info, quote, indexes, flow, distribution, candlesticks) = tokio::join!(
+        quote_context.static_info([symbol.clone()]),
+        quote_context.quote([symbol.clone()]),
+        quote_context.calc_indexes([symbol.clone()], OVERVIEW_CALC_INDEXES),
+        quote_context.capital_flow(symbol.clone()),
+        quote_context.capital_distribution(symbol.clone()),
+        quote_context.candlesticks(
+            symbol.clone(),
+            Period::Day,
+            candlestick_count,
+            AdjustType::ForwardAdjust,
+            TradeSessions::Intraday,
+        ),
+    );
+    let invalid_symbol = || Error::InvalidSecuritySymbol {
+        symbol: symbol.clone(),
+    };
+
+    Ok(SymbolOverview {
+        static_info: static_info?.into_iter().next().ok_or_else(invalid_symbol)?,
+        quote: quote?.into_iter().next().ok_or_else(invalid_symbol)?,
+        calc_indexes: indexes.ok().and_then(|indexes| indexes.into_iter().next()),
+        capital_flow: flow.ok().and_then(|lines| lines.into_iter().last()),
+        capital_distribution: distribution.ok(),
+        candlesticks: candlesticks?,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SessionStatus {
+    trade_session: TradeSession,
+    is_open: bool,
+    /// RFC3339, in the timezone of the market
+    next_open: Option<String>,
+    /// RFC3339, in the timezone of the market
+    next_close: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MarketOverview {
+    market: String,
+    temperature: MarketTemperature,
+    sessions: Vec<SessionStatus>,
+}
+
+/// Returns the market temperature and the status of each trade session of the
+/// market
+pub(crate) async fn market_overview(
+    quote_context: &QuoteContext,
+    calendar: &MarketCalendar,
+    market: Market,
+) -> Result<MarketOverview, Error> {
+    let format = |time: Option<OffsetDateTime>| time.and_then(|time| time.format(&Rfc3339).ok());
+    let temperature = quote_context.market_temperature(market).await?;
+
+    let mut sessions = Vec::new();
+    for trade_session in [
+        TradeSession::Pre,
+        TradeSession::Intraday,
+        TradeSession::Post,
+        TradeSession::Overnight,
+    ] {
+        let is_open = match calendar.is_open(market, trade_session).await {
+            Ok(is_open) => is_open,
+            Err(Error::NoTradeSession { .. }) => continue,
+            Err(err) => return Err(err),
+        };
+        sessions.push(SessionStatus {
+            trade_session,
+            is_open,
+            next_open: format(calendar.next_open(market, trade_session).await?),
+            next_close: format(calendar.next_close(market, trade_session).await?),
+        });
+    }
+
+    Ok(MarketOverview {
+        market: market.to_string(),
+        temperature,
+        sessions,
+    })
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+use crate::auth::Scope;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum PromptError {
+    #[error("unknown prompt: {name}")]
+    UnknownPrompt { name: String },
+    #[error("missing argument `{argument}` of prompt `{name}`")]
+    MissingArgument {
+        name: &'static str,
+        argument: &'static str,
+    },
+}
+
+struct PromptArgument {
+    name: &'static str,
+    description: &'static str,
+    required: bool,
+}
+
+/// A prompt template, `{argument}` in the template is replaced with the
+/// argument
+struct Prompt {
+    name: &'static str,
+    description: &'static str,
+    scope: Scope,
+    arguments: &'static [PromptArgument],
+    template: &'static str,
+}
+
+const PROMPTS: &[Prompt] = &[
+    Prompt {
+        name: "portfolio_review",
+        description: "Review the portfolio: the P&L, the currency exposure and the concentration of the positions",
+        scope: Scope::Account,
+        arguments: &[],
+        template: "Review my portfolio. Call `portfolio_summary` once to get the positions, \
+            the quotes, the P&L and the currency exposure, then summarize the total market \
+            value and unrealized P&L, the exposure to each currency, the largest positions and \
+            the positions with the largest gains and losses. Point out any concentration risk.",
+    },
+    Prompt {
+        name: "symbol_analysis",
+        description: "Analyze a security: the quote, the capital flow, the indexes and the recent candlesticks",
+        scope: Scope::Quote,
+        arguments: &[PromptArgument {
+            name: "symbol",
+            description: "Security symbol, e.g. `700.HK`",
+            required: true,
+        }],
+        template: "Analyze {symbol}. Call `symbol_overview` once with the symbol to get the \
+            static info, the quote, the capital flow, the capital distribution, the calculated \
+            indexes and the recent candlesticks, then summarize the price action, the valuation, \
+            the money flow and the trend of the recent candlesticks.",
+    },
+    Prompt {
+        name: "market_briefing",
+        description: "Brief the state of a market: the trading session and the market temperature",
+        scope: Scope::Quote,
+        arguments: &[PromptArgument {
+            name: "market",
+            description: "Market code, e.g. `HK`, `US`, `CN` or `SG`",
+            required: true,
+        }],
+        template: "Give me a briefing of the {market} market. Call `market_overview` once with \
+            the market to get the trading session status and the market temperature, then tell \
+            me whether the market is open, the current or next trading session, and the market \
+            sentiment.",
+    },
+];
+
+/// Returns the result of `prompts/list` with the prompts usable with the
+/// scopes
+pub(crate) fn list_prompts(allowed: impl Fn(Scope) -> bool) -> Value {
+    let prompts = PROMPTS
+        .iter()
+        .filter(|prompt| allowed(prompt.scope))
+        .map(|prompt| {
+            json!({
+                "name": prompt.name,
+                "description": prompt.description,
+                "arguments": prompt.arguments.iter().map(|argument| json!({
+                    "name": argument.name,
+                    "description": argument.description,
+                    "required": argument.required,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "prompts": prompts })
+}
+
+/// Returns the scope required to get the prompt, or `None` if the prompt is
+/// unknown
+pub(crate) fn scope_of_prompt(name: &str) -> Option<Scope> {
+    find(name).map(|prompt| prompt.scope)
+}
+
+/// Returns the result of `prompts/get`
+pub(crate) fn get_prompt(
+    name: &str,
+    arguments: &HashMap<String, String>,
+) -> Result<Value, PromptError> {
+    let prompt = find(name).ok_or_else(|| PromptError::UnknownPrompt {
+        name: name.to_string(),
+    })?;
+    let mut text = prompt.template.to_string();
+    for argument in prompt.arguments {
+        let value = match arguments.get(argument.name) {
+            Some(value) => value.as_str(),
+            None if argument.required => {
+                return Err(PromptError::MissingArgument {
+                    name: prompt.name,
+                    argument: argument.name,
+                });
+            }
+            None => "",
+        };
+        text = text.replace(&format!("{{{}}}", argument.name), value);
+    }
+    Ok(json!({
+        "description": prompt.description,
+        "messages": [
+            {
+                "role": "user",
+                "content": { "type": "text", "text": text },
+            }
+        ],
+    }))
+}
+
+fn find(name: &str) -> Option<&'static Prompt> {
+    PROMPTS.iter().find(|prompt| prompt.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_prompt() {
+        let arguments = HashMap::from([("symbol".to_string(), "700.HK".to_string())]);
+        let prompt = get_prompt("symbol_analysis", &arguments).unwrap();
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.starts_with("Analyze 700.HK."));
+        assert!(text.contains("`symbol_overview`"));
+
+        assert!(matches!(
+            get_prompt("symbol_analysis", &HashMap::new()),
+            Err(PromptError::MissingArgument {
+                argument: "symbol",
+                ..
+            })
+        ));
+        assert!(matches!(
+            get_prompt("unknown", &HashMap::new()),
+            Err(PromptError::UnknownPrompt { .. })
+        ));
+    }
+
+    #[test]
+    fn test_list_prompts() {
+        let prompts = list_prompts(|scope| scope == Scope::Quote);
+        let names = prompts["prompts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|prompt| prompt["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["symbol_analysis", "market_briefing"]);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use longport::{
+    QuoteContext, TradeContext,
+    quote::{PushEvent as QuotePushEvent, SubFlags},
+    trade::{OrderStatus, PushEvent as TradePushEvent, TopicType},
+};
+use serde_json::{Value, json};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::auth::Scope;
+
+const QUOTE_PREFIX: &str = "longport://quote/";
+const POSITIONS: &str = "longport://positions";
+const TODAY_ORDERS: &str = "longport://orders/today";
+
+/// The capacity of the channel of the updated resources, the slow sessions
+/// miss the oldest updates
+const UPDATES_CAPACITY: usize = 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ResourceError {
+    #[error(transparent)]
+    Longport(#[from] longport::Error),
+    #[error("unknown resource: {uri}")]
+    UnknownResource { uri: String },
+}
+
+/// A resource of the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Resource {
+    Quote(String),
+    Positions,
+    TodayOrders,
+}
+
+impl Resource {
+    fn parse(uri: &str) -> Result<Self, ResourceError> {
+        match uri {
+            POSITIONS => Ok(Resource::Positions),
+            TODAY_ORDERS => Ok(Resource::TodayOrders),
+            _ => match uri.strip_prefix(QUOTE_PREFIX) {
+                Some(symbol) if !symbol.is_empty() => Ok(Resource::Quote(symbol.to_string())),
+                _ => Err(ResourceError::UnknownResource {
+                    uri: uri.to_string(),
+                }),
+            },
+        }
+    }
+
+    fn scope(&self) -> Scope {
+        match self {
+            Resource::Quote(_) => Scope::Quote,
+            Resource::Positions | Resource::TodayOrders => Scope::Account,
+        }
+    }
+}
+
+/// Returns the scope required to read the resource, or `None` if the resource
+/// is unknown
+pub(crate) fn scope_of_resource(uri: &str) -> Option<Scope> {
+    Resource::parse(uri).ok().map(|resource| resource.scope())
+}
+
+/// Returns the result of `resources/list` with the resources readable with
+/// the scopes
+pub(crate) fn list_resources(allowed: impl Fn(Scope) -> bool) -> Value {
+    let resources = if allowed(Scope::Account) {
+        json!([
+            {
+                "uri": POSITIONS,
+                "name": "positions",
+                "description": "The stock positions of the account",
+                "mimeType": "application/json",
+            },
+            {
+                "uri": TODAY_ORDERS,
+                "name": "today_orders",
+                "description": "The orders of the account for the day",
+                "mimeType": "application/json",
+            },
+        ])
+    } else {
+        json!([])
+    };
+    json!({ "resources": resources })
+}
+
+/// Returns the result of `resources/templates/list` with the templates
+/// readable with the scopes
+pub(crate) fn list_templates(allowed: impl Fn(Scope) -> bool) -> Value {
+    let templates = if allowed(Scope::Quote) {
+        json!([
+            {
+                "uriTemplate": format!("{QUOTE_PREFIX}{{symbol}}"),
+                "name": "quote",
+                "description": "The real-time quote of a security, e.g. `longport://quote/700.HK`",
+                "mimeType": "application/json",
+            },
+        ])
+    } else {
+        json!([])
+    };
+    json!({ "resourceTemplates": templates })
+}
+
+/// The resources shared by the sessions, the updates are driven by the push
+/// events of the quote and trade contexts
+pub(crate) struct ResourceHub {
+    quote_context: QuoteContext,
+    trade_context: TradeContext,
+    updates: broadcast::Sender<String>,
+    /// The number of the sessions subscribing each resource
+    subscriptions: Mutex<HashMap<String, usize>>,
+}
+
+impl ResourceHub {
+    pub(crate) fn new(
+        quote_context: QuoteContext,
+        trade_context: TradeContext,
+        mut quote_receiver: mpsc::UnboundedReceiver<QuotePushEvent>,
+        mut trade_receiver: mpsc::UnboundedReceiver<TradePushEvent>,
+    ) -> Arc<Self> {
+        let (updates, _) = broadcast::channel(UPDATES_CAPACITY);
+
+        tokio::spawn({
+            let updates = updates.clone();
+            async move {
+                while let Some(event) = quote_receiver.recv().await {
+                    let _ = updates.send(format!("{QUOTE_PREFIX}{}", event.symbol));
+                }
+            }
+        });
+        tokio::spawn({
+            let updates = updates.clone();
+            async move {
+                while let Some(TradePushEvent::OrderChanged(order)) = trade_receiver.recv().await {
+                    let _ = updates.send(TODAY_ORDERS.to_string());
+                    if matches!(
+                        order.status,
+                        OrderStatus::Filled | OrderStatus::PartialFilled
+                    ) {
+                        let _ = updates.send(POSITIONS.to_string());
+                    }
+                }
+            }
+        });
+
+        Arc::new(Self {
+            quote_context,
+            trade_context,
+            updates,
+            subscriptions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the receiver of the URIs of the updated resources
+    pub(crate) fn updates(&self) -> broadcast::Receiver<String> {
+        self.updates.subscribe()
+    }
+
+    /// Returns the result of `resources/read`
+    pub(crate) async fn read(&self, uri: &str) -> Result<Value, ResourceError> {
+        let data = match Resource::parse(uri)? {
+            Resource::Quote(symbol) => {
+                let quote = self
+                    .quote_context
+                    .quote([symbol.as_str()])
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| ResourceError::UnknownResource {
+                        uri: uri.to_string(),
+                    })?;
+                serde_json::to_string(&quote)
+            }
+            Resource::Positions => {
+                serde_json::to_string(&self.trade_context.stock_positions(None).await?)
+            }
+            Resource::TodayOrders => {
+                serde_json::to_string(&self.trade_context.today_orders(None).await?)
+            }
+        };
+        Ok(json!({
+            "contents": [
+                {
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": data.expect("serialize resource"),
+                }
+            ]
+        }))
+    }
+
+    /// Counts a subscription of a session, subscribes the push events of the
+    /// resource for the first one
+    pub(crate) async fn subscribe(&self, uri: &str) -> Result<(), ResourceError> {
+        let resource = Resource::parse(uri)?;
+        let first_account = !self.has_account_subscriptions();
+        let first = {
+            let mut subscriptions = self.subscriptions.lock().unwrap();
+            let count = subscriptions.entry(uri.to_string()).or_default();
+            *count += 1;
+            *count == 1
+        };
+
+        let res = match &resource {
+            Resource::Quote(symbol) if first => {
+                self.quote_context
+                    .subscribe([symbol], SubFlags::QUOTE, false)
+                    .await
+            }
+            Resource::Positions | Resource::TodayOrders if first && first_account => {
+                self.trade_context.subscribe([TopicType::Private]).await
+            }
+            _ => Ok(()),
+        };
+        if res.is_err() {
+            self.release(uri);
+        }
+        Ok(res?)
+    }
+
+    /// Releases a subscription of a session, unsubscribes the push events of
+    /// the resource for the last one
+    pub(crate) async fn unsubscribe(&self, uri: &str) {
+        let Ok(resource) = Resource::parse(uri) else {
+            return;
+        };
+        if !self.release(uri) {
+            return;
+        }
+
+        let res = match &resource {
+            Resource::Quote(symbol) => {
+                self.quote_context
+                    .unsubscribe([symbol], SubFlags::QUOTE)
+                    .await
+            }
+            Resource::Positions | Resource::TodayOrders if !self.has_account_subscriptions() => {
+                self.trade_context.unsubscribe([TopicType::Private]).await
+            }
+            _ => Ok(()),
+        };
+        if let Err(err) = res {
+            tracing::warn!(uri = uri, error = %err, "failed to unsubscribe the resource");
+        }
+    }
+
+    fn has_account_subscriptions(&self) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|uri| scope_of_resource(uri) == Some(Scope::Account))
+    }
+
+    /// Decrements the subscriptions of the resource, returns `true` if it was
+    /// the last one
+    fn release(&self, uri: &str) -> bool {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let Some(count) = subscriptions.get_mut(uri) else {
+            return false;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return false;
+        }
+        subscriptions.remove(uri);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resource() {
+        assert_eq!(
+            Resource::parse("longport://quote/700.HK").unwrap(),
+            Resource::Quote("700.HK".to_string())
+        );
+        assert_eq!(
+            Resource::parse("longport://positions").unwrap(),
+            Resource::Positions
+        );
+        assert_eq!(
+            Resource::parse("longport://orders/today").unwrap(),
+            Resource::TodayOrders
+        );
+        assert!(Resource::parse("longport://quote/").is_err());
+        assert!(Resource::parse("longport://orders").is_err());
+
+        assert_eq!(
+            scope_of_resource("longport://quote/AAPL.US"),
+            Some(Scope::Quote)
+        );
+        assert_eq!(
+            scope_of_resource("longport://positions"),
+            Some(Scope::Account)
+        );
+        assert_eq!(scope_of_resource("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_list_resources() {
+        assert_eq!(
+            list_resources(|_| true)["resources"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(
+            list_resources(|scope| scope == Scope::Quote)["resources"]
+                .as_array()
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            list_templates(|scope| scope == Scope::Quote)["resourceTemplates"][0]["uriTemplate"],
+            "longport://quote/{symbol}"
+        );
+    }
+}
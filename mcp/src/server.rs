@@ -1,33 +1,400 @@
+use std::{fmt::Display, str::FromStr};
+
 use longport::{
     Decimal, Error, Market, QuoteContext, TradeContext,
-    quote::{AdjustType, Period, TradeSessions},
+    quote::{
+        AdjustType, CalcIndex, MarketCalendar, Period, RequestCreateWatchlistGroup,
+        RequestUpdateWatchlistGroup, SecuritiesUpdateMode, SecurityListCategory, SortOrderType,
+        TradeSessions, WarrantSortBy, WarrantType,
+    },
     trade::{
-        GetHistoryOrdersOptions, OrderSide, OrderType, OutsideRTH, SubmitOrderOptions,
-        TimeInForceType,
+        BalanceType, EstimateMaxPurchaseQuantityOptions, GetCashFlowOptions,
+        GetHistoryExecutionsOptions, GetHistoryOrdersOptions, GetTodayExecutionsOptions,
+        OrderDetail, OrderSide, OrderType, OutsideRTH, ReplaceOrderOptions, SubmitOrderOptions,
+        SubmitOrderResponse, TimeInForceType,
     },
 };
 use poem_mcpserver::{
     Tools,
     content::{IntoContent, IntoContents, Json, Text},
 };
+use serde::Serialize;
 use time::{
     Date, OffsetDateTime, format_description::BorrowedFormatItem, macros::format_description,
 };
 
+use crate::{
+    orders::{
+        CONFIRMATION_TOKEN_TIMEOUT, Notional, OrderError, OrderGuard, OrderLimits, OrderRequest,
+        Usage,
+    },
+    overview,
+};
+
 const DATE_FORMAT: &[BorrowedFormatItem] = format_description!("[year]-[month]-[day]");
 
+/// The tools which read the quotes
+pub(crate) const QUOTE_TOOLS: [&str; 23] = [
+    "now",
+    "static_info",
+    "quote",
+    "option_quote",
+    "depth",
+    "trades",
+    "candlesticks",
+    "trading_days",
+    "broker_queue",
+    "broker_info",
+    "option_chain_list",
+    "option_chain_info",
+    "capital_flow",
+    "capital_distribution",
+    "symbol_overview",
+    "market_overview",
+    "current_market_temperature",
+    "history_market_temperature",
+    "intraday",
+    "calc_indexes",
+    "warrant_quote",
+    "warrant_list",
+    "security_list",
+];
+
+/// The tools which read the account, positions, orders and watchlist
+pub(crate) const ACCOUNT_TOOLS: [&str; 15] = [
+    "watchlist",
+    "account_balance",
+    "stock_positions",
+    "portfolio_summary",
+    "fund_positions",
+    "magin_ratio",
+    "cash_flow",
+    "today_executions",
+    "history_executions",
+    "estimate_max_purchase_quantity",
+    "preview_order",
+    "preview_replace_order",
+    "order_detail",
+    "today_orders",
+    "history_orders",
+];
+
+/// The tools which modify the orders or the watchlist, they are disabled in
+/// read-only mode
+pub(crate) const WRITE_TOOLS: [&str; 7] = [
+    "submit_order",
+    "confirm_order",
+    "replace_order",
+    "cancel_order",
+    "create_watchlist_group",
+    "update_watchlist_group",
+    "delete_watchlist_group",
+];
+
+fn parse_field<T>(name: &'static str, value: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse().map_err(|err: T::Err| Error::ParseField {
+        name,
+        error: err.to_string(),
+    })
+}
+
+fn parse_datetime(name: &'static str, value: &str) -> Result<OffsetDateTime, Error> {
+    OffsetDateTime::parse(value, &time::format_description::well_known::Rfc3339).map_err(|err| {
+        Error::ParseField {
+            name,
+            error: err.to_string(),
+        }
+    })
+}
+
+fn parse_trade_sessions(value: Option<&str>) -> Result<TradeSessions, Error> {
+    match value {
+        None | Some("intraday") => Ok(TradeSessions::Intraday),
+        Some("all") => Ok(TradeSessions::All),
+        Some(_) => Err(Error::ParseField {
+            name: "trade_sessions",
+            error: "invalid trade_sessions".to_string(),
+        }),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn submit_order_options(
+    symbol: String,
+    order_type: String,
+    submitted_price: Option<Decimal>,
+    submitted_quantity: Decimal,
+    trigger_price: Option<Decimal>,
+    limit_offset: Option<Decimal>,
+    trailing_amount: Option<Decimal>,
+    trailing_percent: Option<Decimal>,
+    expire_date: Option<String>,
+    side: String,
+    outside_rth: Option<String>,
+    time_in_force: String,
+) -> Result<SubmitOrderOptions, Error> {
+    let mut opts = SubmitOrderOptions::new(
+        symbol,
+        order_type
+            .parse::<OrderType>()
+            .map_err(|err| Error::ParseField {
+                name: "order_type",
+                error: err.to_string(),
+            })?,
+        side.parse::<OrderSide>().map_err(|err| Error::ParseField {
+            name: "side",
+            error: err.to_string(),
+        })?,
+        submitted_quantity,
+        time_in_force
+            .parse::<TimeInForceType>()
+            .map_err(|err| Error::ParseField {
+                name: "time_in_force",
+                error: err.to_string(),
+            })?,
+    );
+
+    if let Some(submitted_price) = submitted_price {
+        opts = opts.submitted_price(submitted_price);
+    }
+    if let Some(trigger_price) = trigger_price {
+        opts = opts.trigger_price(trigger_price);
+    }
+    if let Some(limit_offset) = limit_offset {
+        opts = opts.limit_offset(limit_offset);
+    }
+    if let Some(trailing_amount) = trailing_amount {
+        opts = opts.trailing_amount(trailing_amount);
+    }
+    if let Some(trailing_percent) = trailing_percent {
+        opts = opts.trailing_percent(trailing_percent);
+    }
+
+    if let Some(expire_date) = expire_date {
+        opts = opts.expire_date(
+            Date::parse(&expire_date, format_description!("[year]-[month]-[day]")).map_err(
+                |err| Error::ParseField {
+                    name: "expire_date",
+                    error: err.to_string(),
+                },
+            )?,
+        );
+    }
+
+    if let Some(outside_rth) = outside_rth {
+        opts = opts.outside_rth(outside_rth.parse::<OutsideRTH>().map_err(|err| {
+            Error::ParseField {
+                name: "outside_rth",
+                error: err.to_string(),
+            }
+        })?);
+    }
+
+    Ok(opts)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn replace_order_options(
+    order_id: String,
+    quantity: Decimal,
+    price: Option<Decimal>,
+    trigger_price: Option<Decimal>,
+    limit_offset: Option<Decimal>,
+    trailing_amount: Option<Decimal>,
+    trailing_percent: Option<Decimal>,
+    remark: Option<String>,
+) -> ReplaceOrderOptions {
+    let mut opts = ReplaceOrderOptions::new(order_id, quantity);
+    if let Some(price) = price {
+        opts = opts.price(price);
+    }
+    if let Some(trigger_price) = trigger_price {
+        opts = opts.trigger_price(trigger_price);
+    }
+    if let Some(limit_offset) = limit_offset {
+        opts = opts.limit_offset(limit_offset);
+    }
+    if let Some(trailing_amount) = trailing_amount {
+        opts = opts.trailing_amount(trailing_amount);
+    }
+    if let Some(trailing_percent) = trailing_percent {
+        opts = opts.trailing_percent(trailing_percent);
+    }
+    if let Some(remark) = remark {
+        opts = opts.remark(remark);
+    }
+    opts
+}
+
+/// The ticket of a previewed order
+#[derive(Debug, Serialize)]
+struct OrderTicket {
+    token: String,
+    expires_at: String,
+    symbol: String,
+    order_type: String,
+    side: String,
+    quantity: Decimal,
+    price: Option<Decimal>,
+    last_done: Option<Decimal>,
+    currency: String,
+    lot_size: i32,
+    estimated_notional: Decimal,
+    cash_max_qty: Decimal,
+    margin_max_qty: Decimal,
+    warnings: Vec<String>,
+}
+
+/// The ticket of a previewed replacement of an order
+#[derive(Debug, Serialize)]
+struct ReplaceTicket {
+    token: String,
+    expires_at: String,
+    order_id: String,
+    symbol: String,
+    previous_quantity: Decimal,
+    previous_price: Option<Decimal>,
+    quantity: Decimal,
+    price: Option<Decimal>,
+    currency: String,
+    previous_notional: Option<Decimal>,
+    estimated_notional: Option<Decimal>,
+    warnings: Vec<String>,
+}
+
+/// The result of a submitted or replaced order
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum OrderResult {
+    Submitted(SubmitOrderResponse),
+    Replaced { order_id: String },
+}
+
 pub(crate) struct Longport {
     quote_context: QuoteContext,
     trade_context: TradeContext,
+    calendar: MarketCalendar,
+    orders: OrderGuard,
 }
 
 impl Longport {
     #[inline]
-    pub(crate) fn new(quote_context: QuoteContext, trade_context: TradeContext) -> Self {
+    pub(crate) fn new(
+        quote_context: QuoteContext,
+        trade_context: TradeContext,
+        order_limits: OrderLimits,
+    ) -> Self {
         Self {
+            calendar: MarketCalendar::new(quote_context.clone()),
             quote_context,
             trade_context,
+            orders: OrderGuard::new(order_limits),
+        }
+    }
+
+    async fn last_done(&self, symbol: &str) -> Result<Option<Decimal>, Error> {
+        Ok(self
+            .quote_context
+            .quote([symbol])
+            .await?
+            .into_iter()
+            .next()
+            .map(|quote| quote.last_done))
+    }
+
+    async fn currency(&self, symbol: &str) -> Result<String, Error> {
+        self.quote_context
+            .static_info([symbol])
+            .await?
+            .into_iter()
+            .next()
+            .map(|info| info.currency)
+            .ok_or_else(|| Error::InvalidSecuritySymbol {
+                symbol: symbol.to_string(),
+            })
+    }
+
+    /// Returns the price, or the last done price if it is `None`
+    async fn price_or_last_done(
+        &self,
+        symbol: &str,
+        price: Option<Decimal>,
+    ) -> Result<Decimal, OrderError> {
+        let price = match price {
+            Some(price) => Some(price),
+            None => self.last_done(symbol).await?,
+        };
+        price.ok_or_else(|| OrderError::UnknownPrice {
+            symbol: symbol.to_string(),
+        })
+    }
+
+    /// Returns the usage of the limits by a new order
+    async fn submit_usage(
+        &self,
+        symbol: &str,
+        price: Option<Decimal>,
+        quantity: Decimal,
+    ) -> Result<Usage, OrderError> {
+        let notional = match self.orders.limits().max_notional {
+            Some(_) => Some(Notional {
+                currency: self.currency(symbol).await?,
+                amount: self.price_or_last_done(symbol, price).await? * quantity,
+            }),
+            None => None,
+        };
+        Ok(Usage {
+            orders: 1,
+            notional,
+        })
+    }
+
+    /// Returns the usage of the limits by replacing an order, the increase of
+    /// the notional is counted
+    async fn replace_usage(
+        &self,
+        order: &OrderDetail,
+        price: Option<Decimal>,
+        quantity: Decimal,
+    ) -> Result<Usage, OrderError> {
+        if self.orders.limits().max_notional.is_none() {
+            return Ok(Usage::default());
         }
+        let previous_price = self
+            .price_or_last_done(&order.symbol, order.price.or(order.trigger_price))
+            .await?;
+        let amount = price.unwrap_or(previous_price) * quantity - previous_price * order.quantity;
+        Ok(Usage {
+            orders: 0,
+            notional: Some(Notional {
+                currency: order.currency.clone(),
+                amount: amount.max(Decimal::ZERO),
+            }),
+        })
+    }
+
+    /// Sends the order request counted in the limits, releases it if it fails
+    async fn send(&self, request: OrderRequest, usage: Usage) -> Result<OrderResult, OrderError> {
+        let res = match request {
+            OrderRequest::Submit(opts) => self
+                .trade_context
+                .submit_order(opts)
+                .await
+                .map(OrderResult::Submitted),
+            OrderRequest::Replace { order_id, opts } => self
+                .trade_context
+                .replace_order(opts)
+                .await
+                .map(|()| OrderResult::Replaced { order_id }),
+        };
+        res.map_err(|err| {
+            self.orders.release(&usage);
+            err.into()
+        })
     }
 }
 
@@ -260,6 +627,37 @@ impl Longport {
         Ok(Json(self.quote_context.capital_distribution(symbol).await?))
     }
 
+    /// Returns an overview of the security, including the static info, the
+    /// quote, the main indexes (change rate, volume, turnover, market value,
+    /// PE, PB, dividend ratio and period change rates), the latest capital
+    /// flow, the capital distribution and the recent daily candlesticks.
+    async fn symbol_overview(
+        &self,
+        /// Security symbol. (e.g. "700.HK")
+        symbol: String,
+        /// The number of the daily candlesticks, 30 by default
+        candlestick_count: Option<usize>,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            overview::symbol_overview(&self.quote_context, symbol, candlestick_count.unwrap_or(30))
+                .await?,
+        ))
+    }
+
+    /// Returns an overview of the market, including the market temperature
+    /// and whether each trade session (Pre, Intraday, Post, Overnight) is
+    /// open, with the next open and close time.
+    async fn market_overview(
+        &self,
+        /// Market code. (e.g. "HK", "US", "CN", "SG")
+        market: String,
+    ) -> Result<impl IntoContents, Error> {
+        let market = parse_field::<Market>("market", &market)?;
+        Ok(Json(
+            overview::market_overview(&self.quote_context, &self.calendar, market).await?,
+        ))
+    }
+
     /// Returns the market temperature of the specified market.
     async fn current_market_temperature(
         &self,
@@ -304,6 +702,214 @@ impl Longport {
         ))
     }
 
+    /// Get the intraday lines of the security.
+    async fn intraday(
+        &self,
+        symbol: String,
+        /// - intraday: regular trading hours (default)
+        /// - all: all trading hours (intraday, pre, post, overnight)
+        trade_sessions: Option<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let trade_sessions = parse_trade_sessions(trade_sessions.as_deref())?;
+        Ok(Json(
+            self.quote_context.intraday(symbol, trade_sessions).await?,
+        ))
+    }
+
+    /// Get the calculated indexes of the securities.
+    async fn calc_indexes(
+        &self,
+        /// A list of security symbols. (e.g. ["700.HK", "AAPL.US"])
+        symbols: Vec<String>,
+        /// A list of indexes. (e.g. ["LastDone", "ChangeRate", "PeTtmRatio"])
+        /// LastDone, ChangeValue, ChangeRate, Volume, Turnover, YtdChangeRate,
+        /// TurnoverRate, TotalMarketValue, CapitalFlow, Amplitude, VolumeRatio,
+        /// PeTtmRatio, PbRatio, DividendRatioTtm, FiveDayChangeRate,
+        /// TenDayChangeRate, HalfYearChangeRate, FiveMinutesChangeRate,
+        /// ExpiryDate, StrikePrice, UpperStrikePrice, LowerStrikePrice,
+        /// OutstandingQty, OutstandingRatio, Premium, ItmOtm,
+        /// ImpliedVolatility, WarrantDelta, CallPrice, ToCallPrice,
+        /// EffectiveLeverage, LeverageRatio, ConversionRatio, BalancePoint,
+        /// OpenInterest, Delta, Gamma, Theta, Vega, Rho
+        indexes: Vec<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let indexes = indexes
+            .iter()
+            .map(|index| parse_field::<CalcIndex>("indexes", index))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self
+            .quote_context
+            .calc_indexes(symbols, indexes)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the latest price of warrant securities.
+    async fn warrant_quote(
+        &self,
+        /// A list of warrant symbols. (e.g. ["21125.HK"])
+        symbols: Vec<String>,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .warrant_quote(symbols)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the warrants of the underlying security.
+    async fn warrant_list(
+        &self,
+        /// The underlying security symbol. (e.g. "700.HK")
+        symbol: String,
+        /// LastDone, ChangeRate, ChangeValue, Volume, Turnover, ExpiryDate,
+        /// StrikePrice, UpperStrikePrice, LowerStrikePrice,
+        /// OutstandingQuantity, OutstandingRatio, Premium, ItmOtm,
+        /// ImpliedVolatility, Delta, CallPrice, ToCallPrice, EffectiveLeverage,
+        /// LeverageRatio, ConversionRatio, BalancePoint, Status
+        sort_by: String,
+        /// asc or desc
+        sort_order: String,
+        /// Filter by the warrant types, if not provided, default to all types.
+        /// (Call, Put, Bull, Bear, Inline)
+        warrant_types: Option<Vec<String>>,
+    ) -> Result<impl IntoContents, Error> {
+        let sort_by = parse_field::<WarrantSortBy>("sort_by", &sort_by)?;
+        let sort_order = match sort_order.as_str() {
+            "asc" => SortOrderType::Ascending,
+            "desc" => SortOrderType::Descending,
+            _ => {
+                return Err(Error::ParseField {
+                    name: "sort_order",
+                    error: "invalid sort_order".to_string(),
+                });
+            }
+        };
+        let warrant_types = warrant_types
+            .map(|types| {
+                types
+                    .iter()
+                    .map(|ty| parse_field::<WarrantType>("warrant_types", ty))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        Ok(self
+            .quote_context
+            .warrant_list(
+                symbol,
+                sort_by,
+                sort_order,
+                warrant_types.as_deref(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the securities of the market in the category.
+    async fn security_list(
+        &self,
+        /// Market code, only "US" is supported.
+        market: String,
+        /// Overnight: the securities which can be traded overnight
+        category: String,
+    ) -> Result<impl IntoContents, Error> {
+        let market = parse_field::<Market>("market", &market)?;
+        let category = parse_field::<SecurityListCategory>("category", &category)?;
+        Ok(self
+            .quote_context
+            .security_list(market, category)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the watchlist groups and their securities.
+    async fn watchlist(&self) -> Result<impl IntoContents, Error> {
+        Ok(self
+            .quote_context
+            .watchlist()
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Create a watchlist group, returns the id of the group.
+    async fn create_watchlist_group(
+        &self,
+        /// Group name
+        name: String,
+        /// A list of security symbols. (e.g. ["700.HK", "AAPL.US"])
+        securities: Option<Vec<String>>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut req = RequestCreateWatchlistGroup::new(name);
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        Ok(Json(self.quote_context.create_watchlist_group(req).await?))
+    }
+
+    /// Update a watchlist group.
+    async fn update_watchlist_group(
+        &self,
+        /// Group id
+        id: i64,
+        /// New group name
+        name: Option<String>,
+        /// A list of security symbols. (e.g. ["700.HK", "AAPL.US"])
+        securities: Option<Vec<String>>,
+        /// How to update the securities
+        /// - add: add the securities to the group
+        /// - remove: remove the securities from the group
+        /// - replace: replace the securities of the group (default)
+        mode: Option<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut req = RequestUpdateWatchlistGroup::new(id);
+        if let Some(name) = name {
+            req = req.name(name);
+        }
+        if let Some(securities) = securities {
+            req = req.securities(securities);
+        }
+        match mode.as_deref() {
+            None | Some("replace") => {}
+            Some("add") => req = req.mode(SecuritiesUpdateMode::Add),
+            Some("remove") => req = req.mode(SecuritiesUpdateMode::Remove),
+            Some(_) => {
+                return Err(Error::ParseField {
+                    name: "mode",
+                    error: "invalid mode".to_string(),
+                });
+            }
+        }
+        Ok(Json(self.quote_context.update_watchlist_group(req).await?))
+    }
+
+    /// Delete a watchlist group.
+    async fn delete_watchlist_group(
+        &self,
+        /// Group id
+        id: i64,
+        /// Whether to remove the securities of the group from the other
+        /// groups
+        purge: bool,
+    ) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            self.quote_context.delete_watchlist_group(id, purge).await?,
+        ))
+    }
+
     /// Get the account balance.
     async fn account_balance(&self) -> Result<impl IntoContents, Error> {
         Ok(self
@@ -327,6 +933,16 @@ impl Longport {
             .collect::<Vec<_>>())
     }
 
+    /// Returns a summary of the portfolio, including the account balances,
+    /// the stock positions valued at the latest prices with the unrealized
+    /// P&L, and the market value, cost and unrealized P&L of the positions in
+    /// each currency.
+    async fn portfolio_summary(&self) -> Result<impl IntoContents, Error> {
+        Ok(Json(
+            overview::portfolio_summary(&self.quote_context, &self.trade_context).await?,
+        ))
+    }
+
     /// Returns the fund positions.
     async fn fund_positions(&self) -> Result<impl IntoContents, Error> {
         Ok(self
@@ -345,6 +961,153 @@ impl Longport {
         Ok(Json(self.trade_context.margin_ratio(symbol).await?))
     }
 
+    /// Get the cash flows of the account.
+    async fn cash_flow(
+        &self,
+        /// format: RFC3339
+        start_at: String,
+        /// format: RFC3339
+        end_at: String,
+        /// Filter by the business type, if not provided, default to all types.
+        /// (cash, stock, fund)
+        business_type: Option<String>,
+        /// Filter by the security symbol
+        symbol: Option<String>,
+        /// Page number, start from 1
+        page: Option<usize>,
+        /// Page size (max: 10000)
+        size: Option<usize>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetCashFlowOptions::new(
+            parse_datetime("start_at", &start_at)?,
+            parse_datetime("end_at", &end_at)?,
+        );
+        if let Some(business_type) = business_type {
+            opts = opts.business_type(match business_type.as_str() {
+                "cash" => BalanceType::Cash,
+                "stock" => BalanceType::Stock,
+                "fund" => BalanceType::Fund,
+                _ => {
+                    return Err(Error::ParseField {
+                        name: "business_type",
+                        error: "invalid business_type".to_string(),
+                    });
+                }
+            });
+        }
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(page) = page {
+            opts = opts.page(page);
+        }
+        if let Some(size) = size {
+            opts = opts.size(size);
+        }
+        Ok(self
+            .trade_context
+            .cash_flow(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the executions of the current account for the day.
+    async fn today_executions(
+        &self,
+        /// if not provided, default to all symbols
+        symbol: Option<String>,
+        /// if not provided, default to all orders
+        order_id: Option<String>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetTodayExecutionsOptions::new();
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+        Ok(self
+            .trade_context
+            .today_executions(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Get the historical executions of the current account.
+    ///
+    /// does not include today's executions
+    async fn history_executions(
+        &self,
+        /// if not provided, default to all symbols
+        symbol: Option<String>,
+        /// format: RFC3339
+        start_at: String,
+        /// format: RFC3339
+        end_at: String,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = GetHistoryExecutionsOptions::new()
+            .start_at(parse_datetime("start_at", &start_at)?)
+            .end_at(parse_datetime("end_at", &end_at)?);
+        if let Some(symbol) = symbol {
+            opts = opts.symbol(symbol);
+        }
+        Ok(self
+            .trade_context
+            .history_executions(opts)
+            .await?
+            .into_iter()
+            .map(Json)
+            .collect::<Vec<_>>())
+    }
+
+    /// Estimate the maximum purchase quantity of the security, with cash and
+    /// with margin.
+    #[allow(clippy::too_many_arguments)]
+    async fn estimate_max_purchase_quantity(
+        &self,
+        symbol: String,
+        /// Order type (e.g. LO, ELO, MO, AO, ALO)
+        order_type: String,
+        /// Side of the order (Buy or Sell)
+        side: String,
+        /// Estimated order price, required for the limit orders
+        price: Option<Decimal>,
+        /// Settlement currency
+        currency: Option<String>,
+        /// The order id, required when estimating the quantity to replace an
+        /// order
+        order_id: Option<String>,
+        /// Whether to estimate with fractional shares
+        fractional_shares: Option<bool>,
+    ) -> Result<impl IntoContents, Error> {
+        let mut opts = EstimateMaxPurchaseQuantityOptions::new(
+            symbol,
+            parse_field::<OrderType>("order_type", &order_type)?,
+            parse_field::<OrderSide>("side", &side)?,
+        );
+        if let Some(price) = price {
+            opts = opts.price(price);
+        }
+        if let Some(currency) = currency {
+            opts = opts.currency(currency);
+        }
+        if let Some(order_id) = order_id {
+            opts = opts.order_id(order_id);
+        }
+        if fractional_shares == Some(true) {
+            opts = opts.fractional_shares();
+        }
+        Ok(Json(
+            self.trade_context
+                .estimate_max_purchase_quantity(opts)
+                .await?,
+        ))
+    }
+
     /// Submit an order.
     #[allow(clippy::too_many_arguments)]
     async fn submit_order(
@@ -386,67 +1149,310 @@ impl Longport {
         /// - GTC: Good Till Cancel
         /// - GTD: Good Till Date
         time_in_force: String,
-    ) -> Result<impl IntoContents, Error> {
-        let mut opts = SubmitOrderOptions::new(
-            symbol,
-            order_type
-                .parse::<OrderType>()
-                .map_err(|err| Error::ParseField {
-                    name: "order_type",
-                    error: err.to_string(),
-                })?,
-            side.parse::<OrderSide>().map_err(|err| Error::ParseField {
-                name: "side",
-                error: err.to_string(),
-            })?,
+    ) -> Result<impl IntoContents, OrderError> {
+        let opts = submit_order_options(
+            symbol.clone(),
+            order_type,
+            submitted_price,
             submitted_quantity,
-            time_in_force
-                .parse::<TimeInForceType>()
-                .map_err(|err| Error::ParseField {
-                    name: "time_in_force",
-                    error: err.to_string(),
-                })?,
-        );
+            trigger_price,
+            limit_offset,
+            trailing_amount,
+            trailing_percent,
+            expire_date,
+            side,
+            outside_rth,
+            time_in_force,
+        )?;
+        let usage = self
+            .submit_usage(
+                &symbol,
+                submitted_price.or(trigger_price),
+                submitted_quantity,
+            )
+            .await?;
+        self.orders.reserve(&usage)?;
+        self.send(OrderRequest::Submit(opts), usage).await.map(Json)
+    }
 
-        if let Some(submitted_price) = submitted_price {
-            opts = opts.submitted_price(submitted_price);
-        }
-        if let Some(trigger_price) = trigger_price {
-            opts = opts.trigger_price(trigger_price);
+    /// Preview an order without submitting it.
+    ///
+    /// Returns a ticket with the estimated notional, the current price, the
+    /// lot size, the estimated max purchase quantity, the warnings and a
+    /// confirmation token. Show the ticket to the user, and submit the order
+    /// with `confirm_order` after the user confirms it. The token expires in
+    /// 2 minutes.
+    #[allow(clippy::too_many_arguments)]
+    async fn preview_order(
+        &self,
+        symbol: String,
+        /// Order type
+        /// LO:	Limit Order
+        /// ELO: Enhanced Limit Order
+        /// MO: Market Order
+        /// AO: At-auction Order
+        /// ALO: At-auction Limit Order
+        /// ODD: Odd Lots Order
+        /// LIT: Limit If Touched
+        /// MIT: Market If Touched
+        /// TSLPAMT: Trailing Limit If Touched (Trailing Amount)
+        /// TSLPPCT: Trailing Limit If Touched (Trailing Percent)
+        /// SLO: Special Limit Order. Not Support Replace Order.
+        order_type: String,
+        /// for LO, ELO, ALO, ODD, LIT
+        submitted_price: Option<Decimal>,
+        submitted_quantity: Decimal,
+        /// for LIT, MIT
+        trigger_price: Option<Decimal>,
+        /// for TSLPAMT, TSLPPCT
+        limit_offset: Option<Decimal>,
+        /// for TSLPAMT
+        trailing_amount: Option<Decimal>,
+        /// for TSLPPCT (0-1)
+        trailing_percent: Option<Decimal>,
+        /// format: "yyyy-mm-dd"
+        expire_date: Option<String>,
+        /// Side of the order (Buy or Sell)
+        side: String,
+        /// - RTH_ONLY: regular trading hour only
+        /// - ANY_TIME: any time
+        /// - OVERNIGHT: overnight
+        outside_rth: Option<String>,
+        /// - Day: Day Order
+        /// - GTC: Good Till Cancel
+        /// - GTD: Good Till Date
+        time_in_force: String,
+    ) -> Result<impl IntoContents, OrderError> {
+        let parsed_order_type = parse_field::<OrderType>("order_type", &order_type)?;
+        let parsed_side = parse_field::<OrderSide>("side", &side)?;
+        let opts = submit_order_options(
+            symbol.clone(),
+            order_type.clone(),
+            submitted_price,
+            submitted_quantity,
+            trigger_price,
+            limit_offset,
+            trailing_amount,
+            trailing_percent,
+            expire_date,
+            side.clone(),
+            outside_rth,
+            time_in_force,
+        )?;
+
+        let static_info = self
+            .quote_context
+            .static_info([symbol.clone()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::InvalidSecuritySymbol {
+                symbol: symbol.clone(),
+            })?;
+        let last_done = self.last_done(&symbol).await?;
+        let price = submitted_price.or(trigger_price).or(last_done);
+
+        let mut estimate_opts =
+            EstimateMaxPurchaseQuantityOptions::new(symbol.clone(), parsed_order_type, parsed_side);
+        if let Some(price) = price {
+            estimate_opts = estimate_opts.price(price);
         }
-        if let Some(limit_offset) = limit_offset {
-            opts = opts.limit_offset(limit_offset);
+        let estimate = self
+            .trade_context
+            .estimate_max_purchase_quantity(estimate_opts)
+            .await?;
+
+        let notional = price.unwrap_or_default() * submitted_quantity;
+        let mut warnings = Vec::new();
+        if price.is_none() {
+            warnings.push("the price is unknown, the estimated notional is 0".to_string());
         }
-        if let Some(trailing_amount) = trailing_amount {
-            opts = opts.trailing_amount(trailing_amount);
+        let lot_size = Decimal::from(static_info.lot_size);
+        if parsed_order_type != OrderType::ODD
+            && !lot_size.is_zero()
+            && !(submitted_quantity % lot_size).is_zero()
+        {
+            warnings.push(format!(
+                "the quantity is not a multiple of the lot size {lot_size}"
+            ));
         }
-        if let Some(trailing_percent) = trailing_percent {
-            opts = opts.trailing_percent(trailing_percent);
+        if parsed_side == OrderSide::Buy
+            && submitted_quantity > estimate.cash_max_qty.max(estimate.margin_max_qty)
+        {
+            warnings.push(format!(
+                "the quantity exceeds the estimated max purchase quantity, {} with cash and {} with margin",
+                estimate.cash_max_qty, estimate.margin_max_qty
+            ));
         }
 
-        if let Some(expire_date) = expire_date {
-            opts = opts.expire_date(
-                Date::parse(&expire_date, format_description!("[year]-[month]-[day]")).map_err(
-                    |err| Error::ParseField {
-                        name: "expire_date",
-                        error: err.to_string(),
-                    },
-                )?,
-            );
-        }
+        let usage = Usage {
+            orders: 1,
+            notional: match (self.orders.limits().max_notional, price) {
+                (Some(_), Some(_)) => Some(Notional {
+                    currency: static_info.currency.clone(),
+                    amount: notional,
+                }),
+                (Some(_), None) => return Err(OrderError::UnknownPrice { symbol }),
+                (None, _) => None,
+            },
+        };
+        self.orders.check_limits(&usage)?;
+        let token = self.orders.add_pending(OrderRequest::Submit(opts), usage);
+        Ok(Json(OrderTicket {
+            token,
+            expires_at: (OffsetDateTime::now_utc() + CONFIRMATION_TOKEN_TIMEOUT)
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap(),
+            symbol,
+            order_type,
+            side,
+            quantity: submitted_quantity,
+            price,
+            last_done,
+            currency: static_info.currency,
+            lot_size: static_info.lot_size,
+            estimated_notional: notional,
+            cash_max_qty: estimate.cash_max_qty,
+            margin_max_qty: estimate.margin_max_qty,
+            warnings,
+        }))
+    }
 
-        if let Some(outside_rth) = outside_rth {
-            opts = opts.outside_rth(outside_rth.parse::<OutsideRTH>().map_err(|err| {
-                Error::ParseField {
-                    name: "outside_rth",
-                    error: err.to_string(),
-                }
-            })?);
+    /// Submit an order previewed with `preview_order`, or replace an order
+    /// previewed with `preview_replace_order`.
+    async fn confirm_order(
+        &self,
+        /// The confirmation token of the order ticket
+        token: String,
+    ) -> Result<impl IntoContents, OrderError> {
+        let (request, usage) = self.orders.take_pending(&token)?;
+        self.send(request, usage).await.map(Json)
+    }
+
+    /// Replace the quantity or the prices of an order.
+    #[allow(clippy::too_many_arguments)]
+    async fn replace_order(
+        &self,
+        order_id: String,
+        /// Replaced quantity
+        quantity: Decimal,
+        /// Replaced price, for LO, ELO, ALO, ODD, LIT
+        price: Option<Decimal>,
+        /// for LIT, MIT
+        trigger_price: Option<Decimal>,
+        /// for TSLPAMT, TSLPPCT
+        limit_offset: Option<Decimal>,
+        /// for TSLPAMT
+        trailing_amount: Option<Decimal>,
+        /// for TSLPPCT (0-1)
+        trailing_percent: Option<Decimal>,
+        /// Remark (max 64 characters)
+        remark: Option<String>,
+    ) -> Result<impl IntoContents, OrderError> {
+        let usage = match self.orders.limits().max_notional {
+            Some(_) => {
+                let order = self.trade_context.order_detail(order_id.clone()).await?;
+                self.replace_usage(&order, price.or(trigger_price), quantity)
+                    .await?
+            }
+            None => Usage::default(),
+        };
+        let opts = replace_order_options(
+            order_id.clone(),
+            quantity,
+            price,
+            trigger_price,
+            limit_offset,
+            trailing_amount,
+            trailing_percent,
+            remark,
+        );
+        self.orders.reserve(&usage)?;
+        self.send(OrderRequest::Replace { order_id, opts }, usage)
+            .await
+            .map(Json)
+    }
+
+    /// Preview the replacement of an order without sending it.
+    ///
+    /// Returns a ticket with the previous and the estimated notional, the
+    /// warnings and a confirmation token. Show the ticket to the user, and
+    /// replace the order with `confirm_order` after the user confirms it. The
+    /// token expires in 2 minutes.
+    #[allow(clippy::too_many_arguments)]
+    async fn preview_replace_order(
+        &self,
+        order_id: String,
+        /// Replaced quantity
+        quantity: Decimal,
+        /// Replaced price, for LO, ELO, ALO, ODD, LIT
+        price: Option<Decimal>,
+        /// for LIT, MIT
+        trigger_price: Option<Decimal>,
+        /// for TSLPAMT, TSLPPCT
+        limit_offset: Option<Decimal>,
+        /// for TSLPAMT
+        trailing_amount: Option<Decimal>,
+        /// for TSLPPCT (0-1)
+        trailing_percent: Option<Decimal>,
+        /// Remark (max 64 characters)
+        remark: Option<String>,
+    ) -> Result<impl IntoContents, OrderError> {
+        let order = self.trade_context.order_detail(order_id.clone()).await?;
+        let previous_price = order.price.or(order.trigger_price);
+        let new_price = price.or(trigger_price).or(previous_price);
+
+        let mut warnings = Vec::new();
+        if new_price.is_none() {
+            warnings.push("the price is unknown, the notional is not estimated".to_string());
+        }
+        if quantity < order.executed_quantity {
+            warnings.push(format!(
+                "the quantity is less than the executed quantity {}",
+                order.executed_quantity
+            ));
         }
 
-        self.trade_context.submit_order(opts).await.map(Json)
+        let usage = self
+            .replace_usage(&order, price.or(trigger_price), quantity)
+            .await?;
+        self.orders.check_limits(&usage)?;
+        let opts = replace_order_options(
+            order_id.clone(),
+            quantity,
+            price,
+            trigger_price,
+            limit_offset,
+            trailing_amount,
+            trailing_percent,
+            remark,
+        );
+        let token = self.orders.add_pending(
+            OrderRequest::Replace {
+                order_id: order_id.clone(),
+                opts,
+            },
+            usage,
+        );
+        Ok(Json(ReplaceTicket {
+            token,
+            expires_at: (OffsetDateTime::now_utc() + CONFIRMATION_TOKEN_TIMEOUT)
+                .format(&time::format_description::well_known::Rfc3339)
+                .unwrap(),
+            order_id,
+            symbol: order.symbol,
+            previous_quantity: order.quantity,
+            previous_price,
+            quantity,
+            price: new_price,
+            currency: order.currency,
+            previous_notional: previous_price.map(|price| price * order.quantity),
+            estimated_notional: new_price.map(|price| price * quantity),
+            warnings,
+        }))
     }
 
+    /// Cancel an order.
     async fn cancel_order(&self, order_id: String) -> Result<impl IntoContents, Error> {
         Ok(Json(self.trade_context.cancel_order(order_id).await?))
     }
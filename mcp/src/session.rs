@@ -0,0 +1,287 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use futures_util::Stream;
+use poem_mcpserver::{
+    McpServer,
+    protocol::{
+        JSON_RPC_VERSION,
+        rpc::{Request, RpcError},
+    },
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::{broadcast::error::RecvError, watch};
+
+use crate::{
+    auth::{Client, Scope},
+    prompts,
+    resources::{self, ResourceError, ResourceHub},
+    server::Longport,
+};
+
+/// The JSON-RPC error code of an unknown resource
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+#[derive(Debug, Deserialize)]
+struct ResourceParams {
+    uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptParams {
+    name: String,
+    #[serde(default)]
+    arguments: HashMap<String, String>,
+}
+
+/// A MCP session, handles the resources and prompts requests and forwards the
+/// other requests to the server
+pub(crate) struct Session {
+    server: McpServer<Longport>,
+    hub: Arc<ResourceHub>,
+    /// The client authenticated by the access token
+    client: Option<Client>,
+    /// The subscribed resources
+    subscriptions: Arc<Mutex<HashSet<String>>>,
+    /// Closes the notification streams when the session is dropped
+    closed: watch::Sender<()>,
+}
+
+impl Session {
+    pub(crate) fn new(
+        server: McpServer<Longport>,
+        hub: Arc<ResourceHub>,
+        client: Option<Client>,
+    ) -> Self {
+        Self {
+            server,
+            hub,
+            client,
+            subscriptions: Default::default(),
+            closed: watch::channel(()).0,
+        }
+    }
+
+    /// Handles a request or a batch of requests, returns `None` if there is
+    /// nothing to respond
+    pub(crate) async fn handle(&mut self, request: Value) -> Option<Value> {
+        match request {
+            Value::Array(requests) => {
+                let mut resps = vec![];
+                for request in requests {
+                    resps.extend(self.handle_request(request).await);
+                }
+                (!resps.is_empty()).then_some(Value::Array(resps))
+            }
+            request => self.handle_request(request).await,
+        }
+    }
+
+    /// Returns the notifier of the subscribed resources
+    pub(crate) fn notifier(&self) -> Notifier {
+        Notifier {
+            hub: self.hub.clone(),
+            subscriptions: self.subscriptions.clone(),
+            closed: self.closed.subscribe(),
+        }
+    }
+
+    async fn handle_request(&mut self, request: Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        if request.get("jsonrpc").and_then(Value::as_str) != Some(JSON_RPC_VERSION) {
+            return Some(response(
+                id,
+                Err(RpcError::invalid_request(
+                    "invalid JSON-RPC version, expected `2.0`",
+                )),
+            ));
+        }
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let res = match method.as_str() {
+            "resources/list" => Ok(resources::list_resources(|scope| self.can_read(scope))),
+            "resources/templates/list" => {
+                Ok(resources::list_templates(|scope| self.can_read(scope)))
+            }
+            "resources/read" => self.read(params).await,
+            "resources/subscribe" => self.subscribe(params).await,
+            "resources/unsubscribe" => self.unsubscribe(params).await,
+            "prompts/list" => Ok(prompts::list_prompts(|scope| self.can_read(scope))),
+            "prompts/get" => self.get_prompt(params),
+            _ => return self.forward(id, request).await,
+        };
+        id.map(|id| response(Some(id), res))
+    }
+
+    /// Forwards the request to the server
+    async fn forward(&mut self, id: Option<Value>, request: Value) -> Option<Value> {
+        let is_initialize = request.get("method").and_then(Value::as_str) == Some("initialize");
+        let request = match serde_json::from_value::<Request>(request) {
+            Ok(request) => request,
+            Err(err) => {
+                tracing::error!(error = %err, "failed to parse request");
+                return id.map(|id| {
+                    response(
+                        Some(id),
+                        Err(RpcError::method_not_found(format!(
+                            "unknown method or invalid params: {err}"
+                        ))),
+                    )
+                });
+            }
+        };
+        let mut resp = serde_json::to_value(self.server.handle_request(request).await?)
+            .expect("serialize response");
+        if is_initialize && let Some(capabilities) = resp.pointer_mut("/result/capabilities") {
+            capabilities["resources"] = json!({ "subscribe": true, "listChanged": false });
+            capabilities["prompts"] = json!({ "listChanged": false });
+        }
+        Some(resp)
+    }
+
+    fn can_read(&self, scope: Scope) -> bool {
+        self.client
+            .as_ref()
+            .is_none_or(|client| client.has_scope(scope))
+    }
+
+    /// Returns the URI of the resource if the client can read it
+    fn resource_uri(&self, params: Value) -> Result<String, RpcError> {
+        let ResourceParams { uri } = serde_json::from_value(params)
+            .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+        let Some(scope) = resources::scope_of_resource(&uri) else {
+            return Err(RpcError::new(
+                RESOURCE_NOT_FOUND,
+                format!("unknown resource: {uri}"),
+            ));
+        };
+        if !self.can_read(scope) {
+            return Err(RpcError::invalid_request(format!(
+                "the client is not allowed to read {uri}"
+            )));
+        }
+        Ok(uri)
+    }
+
+    fn get_prompt(&self, params: Value) -> Result<Value, RpcError> {
+        let PromptParams { name, arguments } = serde_json::from_value(params)
+            .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+        if let Some(scope) = prompts::scope_of_prompt(&name)
+            && !self.can_read(scope)
+        {
+            return Err(RpcError::invalid_request(format!(
+                "the client is not allowed to get {name}"
+            )));
+        }
+        prompts::get_prompt(&name, &arguments)
+            .map_err(|err| RpcError::invalid_params(err.to_string()))
+    }
+
+    async fn read(&self, params: Value) -> Result<Value, RpcError> {
+        let uri = self.resource_uri(params)?;
+        self.hub.read(&uri).await.map_err(rpc_error)
+    }
+
+    async fn subscribe(&self, params: Value) -> Result<Value, RpcError> {
+        let uri = self.resource_uri(params)?;
+        if self.subscriptions.lock().unwrap().contains(&uri) {
+            return Ok(json!({}));
+        }
+        self.hub.subscribe(&uri).await.map_err(rpc_error)?;
+        self.subscriptions.lock().unwrap().insert(uri);
+        Ok(json!({}))
+    }
+
+    async fn unsubscribe(&self, params: Value) -> Result<Value, RpcError> {
+        let uri = self.resource_uri(params)?;
+        if self.subscriptions.lock().unwrap().remove(&uri) {
+            self.hub.unsubscribe(&uri).await;
+        }
+        Ok(json!({}))
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let uris = std::mem::take(&mut *self.subscriptions.lock().unwrap());
+        if uris.is_empty() {
+            return;
+        }
+        let hub = self.hub.clone();
+        tokio::spawn(async move {
+            for uri in uris {
+                hub.unsubscribe(&uri).await;
+            }
+        });
+    }
+}
+
+/// Sends the notifications of the resources subscribed in a session
+#[derive(Clone)]
+pub(crate) struct Notifier {
+    hub: Arc<ResourceHub>,
+    subscriptions: Arc<Mutex<HashSet<String>>>,
+    closed: watch::Receiver<()>,
+}
+
+impl Notifier {
+    /// Returns the stream of the notifications, the stream ends when the
+    /// session is dropped
+    pub(crate) fn notifications(&self) -> impl Stream<Item = Value> + Send + 'static {
+        let subscriptions = self.subscriptions.clone();
+        futures_util::stream::unfold(
+            (self.hub.updates(), self.closed.clone()),
+            move |(mut updates, mut closed)| {
+                let subscriptions = subscriptions.clone();
+                async move {
+                    loop {
+                        tokio::select! {
+                            _ = closed.changed() => return None,
+                            res = updates.recv() => match res {
+                                Ok(uri) if subscriptions.lock().unwrap().contains(&uri) => {
+                                    return Some((updated(&uri), (updates, closed)));
+                                }
+                                Ok(_) => {}
+                                Err(RecvError::Lagged(count)) => {
+                                    tracing::warn!(count = count, "missed resource updates");
+                                }
+                                Err(RecvError::Closed) => return None,
+                            },
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+fn rpc_error(err: ResourceError) -> RpcError {
+    match err {
+        ResourceError::UnknownResource { .. } => RpcError::new(RESOURCE_NOT_FOUND, err.to_string()),
+        ResourceError::Longport(err) => RpcError::internal_error(err.to_string()),
+    }
+}
+
+fn response(id: Option<Value>, res: Result<Value, RpcError>) -> Value {
+    match res {
+        Ok(result) => json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": JSON_RPC_VERSION, "id": id, "error": error }),
+    }
+}
+
+/// Returns the notification of an updated resource
+fn updated(uri: &str) -> Value {
+    json!({
+        "jsonrpc": JSON_RPC_VERSION,
+        "method": "notifications/resources/updated",
+        "params": { "uri": uri },
+    })
+}
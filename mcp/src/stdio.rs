@@ -0,0 +1,44 @@
+use std::pin::pin;
+
+use futures_util::StreamExt;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::session::Session;
+
+fn print_message(message: &Value) {
+    println!("{message}");
+}
+
+/// Runs the session using the standard input and output, the notifications of
+/// the subscribed resources are written between the responses
+pub(crate) async fn stdio(mut session: Session) -> std::io::Result<()> {
+    let mut input = BufReader::new(tokio::io::stdin()).lines();
+    let mut notifications = pin!(session.notifier().notifications());
+
+    tracing::info!("stdio server started");
+
+    loop {
+        tokio::select! {
+            line = input.next_line() => {
+                let Some(line) = line? else {
+                    break;
+                };
+                tracing::info!(request = &line, "received request");
+
+                let Ok(request) = serde_json::from_str::<Value>(&line).inspect_err(|err| {
+                    tracing::error!(error = ?err, "failed to parse request");
+                }) else {
+                    continue;
+                };
+                if let Some(resp) = session.handle(request).await {
+                    tracing::info!(response = %resp, "sending response");
+                    print_message(&resp);
+                }
+            }
+            Some(notification) = notifications.next() => print_message(&notification),
+        }
+    }
+
+    Ok(())
+}
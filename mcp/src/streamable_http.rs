@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use poem::{
+    EndpointExt, IntoEndpoint, IntoResponse, Request, Response, handler,
+    http::{HeaderMap, StatusCode},
+    post,
+    web::{
+        Accept, Data, Json,
+        sse::{Event, SSE},
+    },
+};
+use serde_json::Value;
+use tokio::time::Instant;
+
+use crate::{
+    auth::Client,
+    session::{Notifier, Session},
+};
+
+const SESSION_TIMEOUT: Duration = Duration::from_secs(60 * 5);
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+type SessionFactoryFn = Box<dyn Fn(&Request) -> Session + Send + Sync>;
+
+struct SessionEntry {
+    session: Arc<tokio::sync::Mutex<Session>>,
+    notifier: Notifier,
+    /// The client which created the session, the requests of the other
+    /// clients are rejected
+    client: Option<Client>,
+    last_active: Instant,
+    /// Counts the open notification streams, the sessions with an open stream
+    /// don't expire
+    streams: Arc<()>,
+}
+
+impl SessionEntry {
+    fn is_alive(&self, now: Instant) -> bool {
+        Arc::strong_count(&self.streams) > 1 || now - self.last_active < SESSION_TIMEOUT
+    }
+
+    fn is_owned_by(&self, client: Option<&Client>) -> bool {
+        match (&self.client, client) {
+            (Some(owner), Some(client)) => owner.is_same(client),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+struct State {
+    session_factory: SessionFactoryFn,
+    sessions: Mutex<HashMap<String, SessionEntry>>,
+}
+
+fn session_id(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn client(request: &Request) -> Option<&Client> {
+    request.extensions().get::<Client>()
+}
+
+fn is_initialize(request: &Value) -> bool {
+    request.get("method").and_then(Value::as_str) == Some("initialize")
+}
+
+#[handler]
+async fn post_handler(
+    data: Data<&Arc<State>>,
+    request: &Request,
+    Json(body): Json<Value>,
+    accept: Accept,
+) -> Response {
+    let Some(accept) = accept.0.first() else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+
+    if is_initialize(&body) && session_id(request.headers()).is_none() {
+        let session_id = format!("{:016x}", rand::random::<u128>());
+        let mut session = (data.0.session_factory)(request);
+        let resp = session.handle(body).await;
+        data.0.sessions.lock().unwrap().insert(
+            session_id.clone(),
+            SessionEntry {
+                notifier: session.notifier(),
+                client: client(request).cloned(),
+                session: Arc::new(tokio::sync::Mutex::new(session)),
+                last_active: Instant::now(),
+                streams: Arc::new(()),
+            },
+        );
+
+        tracing::info!(session_id = session_id, "created new session");
+        return Json(resp)
+            .with_header(SESSION_ID_HEADER, session_id)
+            .into_response();
+    }
+
+    let Some(session_id) = session_id(request.headers()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let session = {
+        let mut sessions = data.0.sessions.lock().unwrap();
+        let Some(entry) = sessions.get_mut(session_id) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+        if !entry.is_owned_by(client(request)) {
+            return StatusCode::FORBIDDEN.into_response();
+        }
+        entry.last_active = Instant::now();
+        entry.session.clone()
+    };
+
+    tracing::info!(session_id = session_id, request = %body, "received request");
+    let Some(resp) = session.lock().await.handle(body).await else {
+        return StatusCode::ACCEPTED.into_response();
+    };
+    tracing::info!(session_id = session_id, response = %resp, "sending response");
+
+    match accept.essence_str() {
+        "application/json" => Json(resp).into_response(),
+        "text/event-stream" => {
+            let resps = match resp {
+                Value::Array(resps) => resps,
+                resp => vec![resp],
+            };
+            SSE::new(
+                futures_util::stream::iter(resps)
+                    .map(|resp| Event::message(resp.to_string()).event_type("message")),
+            )
+            .into_response()
+        }
+        _ => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+/// Opens the stream of the notifications of the subscribed resources
+#[handler]
+fn get_handler(data: Data<&Arc<State>>, request: &Request) -> Response {
+    let Some(session_id) = session_id(request.headers()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let sessions = data.0.sessions.lock().unwrap();
+    let Some(entry) = sessions.get(session_id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if !entry.is_owned_by(client(request)) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    tracing::info!(session_id = session_id, "opened notification stream");
+    let stream = entry.streams.clone();
+    SSE::new(entry.notifier.notifications().map(move |notification| {
+        let _stream = &stream;
+        Event::message(notification.to_string()).event_type("message")
+    }))
+    .keep_alive(Duration::from_secs(15))
+    .into_response()
+}
+
+#[handler]
+fn delete_handler(data: Data<&Arc<State>>, request: &Request) -> StatusCode {
+    let Some(session_id) = session_id(request.headers()) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    let mut sessions = data.sessions.lock().unwrap();
+    let Some(entry) = sessions.get(session_id) else {
+        return StatusCode::NOT_FOUND;
+    };
+    if !entry.is_owned_by(client(request)) {
+        return StatusCode::FORBIDDEN;
+    }
+    sessions.remove(session_id);
+
+    tracing::info!(session_id = session_id, "deleted session");
+    StatusCode::ACCEPTED
+}
+
+/// A Streamable-HTTP endpoint handling the MCP requests, the notifications of
+/// the subscribed resources are sent in the stream opened with `GET`
+pub(crate) fn endpoint<F>(session_factory: F) -> impl IntoEndpoint
+where
+    F: Fn(&Request) -> Session + Send + Sync + 'static,
+{
+    let state = Arc::new(State {
+        session_factory: Box::new(session_factory),
+        sessions: Default::default(),
+    });
+
+    tokio::spawn({
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+            loop {
+                let now = interval.tick().await;
+                let mut sessions = state.sessions.lock().unwrap();
+                sessions.retain(|_, entry| entry.is_alive(now));
+            }
+        }
+    });
+
+    post(post_handler)
+        .get(get_handler)
+        .delete(delete_handler)
+        .data(state)
+}
@@ -40,6 +40,15 @@ export declare class Brokers {
   get brokerIds(): Array<number>
 }
 
+/** A token to cancel the calls of the contexts returned by `withCallOptions` */
+export declare class CancellationToken {
+  constructor()
+  /** Cancels the calls, the pending and the following calls fail */
+  cancel(): void
+  /** Returns `true` if the token is cancelled */
+  isCancelled(): boolean
+}
+
 /** Candlestick */
 export declare class Candlestick {
   toString(): string
@@ -162,6 +171,20 @@ export declare class Config {
    *   `true` or `false` (Default: `true`)
    */
   static fromEnv(): Config
+  /**
+   * Create a new `Config` from a TOML file, or a YAML file if the
+   * extension is `.yaml` or `.yml`
+   *
+   * The keys of the file are the names of the environment variables
+   * without the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+   * `push_candlestick_mode`.
+   *
+   * The options of `profile` in the `profiles` table override the top level
+   * options, if `profile` is not specified, the `LONGPORT_PROFILE`
+   * environment variable is used. The environment variables override the
+   * options of the file.
+   */
+  static fromFile(path: string, profile?: string | undefined | null): Config
   /**
    * Gets a new `access_token`
    *
@@ -973,6 +996,13 @@ export declare class PushTradesEvent {
 /** Quote context */
 export declare class QuoteContext {
   static new(config: Config): Promise<QuoteContext>
+  /**
+   * Returns a `QuoteContext` sharing the connection and the callbacks with this
+   * one, whose calls fail if they are not completed within `timeout`
+   * milliseconds or before `deadline`, or when `cancellationToken` is
+   * cancelled.
+   */
+  withCallOptions(timeout?: number | undefined | null, deadline?: Date | undefined | null, cancellationToken?: CancellationToken | undefined | null): QuoteContext
   /** Returns the member ID */
   memberId(): number
   /** Returns the quote level */
@@ -1949,6 +1979,13 @@ export declare class Trade {
 /** Trade context */
 export declare class TradeContext {
   static new(config: Config): Promise<TradeContext>
+  /**
+   * Returns a `TradeContext` sharing the connection and the callbacks with this
+   * one, whose calls fail if they are not completed within `timeout`
+   * milliseconds or before `deadline`, or when `cancellationToken` is
+   * cancelled.
+   */
+  withCallOptions(timeout?: number | undefined | null, deadline?: Date | undefined | null, cancellationToken?: CancellationToken | undefined | null): TradeContext
   /**
    * Set order changed callback, after receiving the order changed event, it
    * will call back to this function.
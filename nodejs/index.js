@@ -395,6 +395,7 @@ if (!nativeBinding) {
 module.exports = nativeBinding
 module.exports.AccountBalance = nativeBinding.AccountBalance
 module.exports.Brokers = nativeBinding.Brokers
+module.exports.CancellationToken = nativeBinding.CancellationToken
 module.exports.Candlestick = nativeBinding.Candlestick
 module.exports.CapitalDistribution = nativeBinding.CapitalDistribution
 module.exports.CapitalDistributionResponse = nativeBinding.CapitalDistributionResponse
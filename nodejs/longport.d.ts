@@ -0,0 +1,23 @@
+export * from './index'
+
+/** The kind of an OpenAPI error */
+export type ErrorKind =
+  | 'InsufficientBuyingPower'
+  | 'MarketClosed'
+  | 'InvalidPriceTick'
+  | 'RateLimited'
+  | 'TokenExpired'
+  | 'PermissionDenied'
+  | 'Other'
+
+/** The error thrown by the SDK */
+export interface OpenApiError extends Error {
+  /** The kind of the error, `Other` if it is not a known OpenAPI error */
+  kind: ErrorKind
+  /** Whether the request may succeed when it is sent again */
+  retryable: boolean
+  /** Whether the error is caused by the credentials */
+  auth: boolean
+  /** The trace id of the failed HTTP request */
+  traceId?: string
+}
@@ -0,0 +1,59 @@
+// The entry of the package, it re-exports the native binding and moves the
+// details of the OpenAPI errors to the properties of the thrown errors
+
+const binding = require('./index.js')
+
+const DETAILS_PREFIX = 'longport:details:'
+
+function decorateError(err) {
+  const cause = err instanceof Error ? err.cause : undefined
+  if (cause instanceof Error && cause.message.startsWith(DETAILS_PREFIX)) {
+    const details = JSON.parse(cause.message.slice(DETAILS_PREFIX.length))
+    delete err.cause
+    err.kind = details.kind
+    err.retryable = details.retryable
+    err.auth = details.auth
+    err.traceId = details.traceId ?? undefined
+  }
+  return err
+}
+
+function wrap(func) {
+  return function (...args) {
+    let result
+    try {
+      result = func.apply(this, args)
+    } catch (err) {
+      throw decorateError(err)
+    }
+    if (result instanceof Promise) {
+      return result.catch((err) => {
+        throw decorateError(err)
+      })
+    }
+    return result
+  }
+}
+
+function wrapMethods(target) {
+  for (const name of Object.getOwnPropertyNames(target)) {
+    if (name === 'constructor' || name === 'prototype') {
+      continue
+    }
+    const descriptor = Object.getOwnPropertyDescriptor(target, name)
+    if (descriptor && typeof descriptor.value === 'function' && descriptor.writable) {
+      Object.defineProperty(target, name, { ...descriptor, value: wrap(descriptor.value) })
+    }
+  }
+}
+
+for (const value of Object.values(binding)) {
+  if (typeof value === 'function') {
+    wrapMethods(value)
+    if (value.prototype) {
+      wrapMethods(value.prototype)
+    }
+  }
+}
+
+module.exports = binding
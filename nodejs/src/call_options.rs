@@ -0,0 +1,48 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use longport::CallOptions;
+
+/// A token to cancel the calls of the contexts returned by `withCallOptions`
+#[derive(Clone, Default)]
+#[napi_derive::napi]
+pub struct CancellationToken(pub(crate) longport::CancellationToken);
+
+#[napi_derive::napi]
+impl CancellationToken {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the calls, the pending and the following calls fail
+    #[napi]
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    /// Returns `true` if the token is cancelled
+    #[napi]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+pub(crate) fn call_options(
+    timeout: Option<u32>,
+    deadline: Option<DateTime<Utc>>,
+    cancellation_token: Option<&CancellationToken>,
+) -> CallOptions {
+    let mut options = CallOptions::new();
+    if let Some(timeout) = timeout {
+        options = options.timeout(Duration::from_millis(timeout as u64));
+    }
+    if let Some(deadline) = deadline {
+        let remaining = (deadline - Utc::now()).to_std().unwrap_or_default();
+        options = options.deadline(Instant::now() + remaining);
+    }
+    if let Some(cancellation_token) = cancellation_token {
+        options = options.cancellation_token(cancellation_token.0.clone());
+    }
+    options
+}
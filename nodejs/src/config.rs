@@ -110,6 +110,24 @@ impl Config {
         Ok(Self(longport::Config::from_env().map_err(ErrorNewType)?))
     }
 
+    /// Create a new `Config` from a TOML file, or a YAML file if the
+    /// extension is `.yaml` or `.yml`
+    ///
+    /// The keys of the file are the names of the environment variables
+    /// without the `LONGPORT_` prefix in lowercase, e.g. `app_key` or
+    /// `push_candlestick_mode`.
+    ///
+    /// The options of `profile` in the `profiles` table override the top level
+    /// options, if `profile` is not specified, the `LONGPORT_PROFILE`
+    /// environment variable is used. The environment variables override the
+    /// options of the file.
+    #[napi(factory)]
+    pub fn from_file(path: String, profile: Option<String>) -> Result<Self> {
+        Ok(Self(
+            longport::Config::from_file(path, profile.as_deref()).map_err(ErrorNewType)?,
+        ))
+    }
+
     /// Gets a new `access_token`
     ///
     /// `expired_at` - The expiration time of the access token, defaults to `90`
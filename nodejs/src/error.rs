@@ -1,8 +1,24 @@
+/// The prefix of the message of the cause carrying the details of an OpenAPI
+/// error, `longport.js` moves the details to the thrown error and removes the
+/// cause
+const DETAILS_PREFIX: &str = "longport:details:";
+
 pub(crate) struct ErrorNewType(pub(crate) longport::Error);
 
 impl std::convert::From<ErrorNewType> for napi::Error {
     #[inline]
     fn from(err: ErrorNewType) -> napi::Error {
-        napi::Error::from_reason(err.0.to_string())
+        let err = err.0;
+        let details = serde_json::json!({
+            "kind": format!("{:?}", err.kind()),
+            "retryable": err.is_retryable(),
+            "auth": err.is_auth(),
+            "traceId": err.trace_id(),
+        });
+        let mut napi_err = napi::Error::from_reason(err.to_string());
+        napi_err.cause = Some(Box::new(napi::Error::from_reason(format!(
+            "{DETAILS_PREFIX}{details}"
+        ))));
+        napi_err
     }
 }
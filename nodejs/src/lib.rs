@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+mod call_options;
 mod config;
 mod decimal;
 mod error;
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use longport::quote::PushEventDetail;
 use napi::{Result, bindgen_prelude::*, threadsafe_function::ThreadsafeFunctionCallMode};
 use parking_lot::Mutex;
 
 use crate::{
+    call_options::{CancellationToken, call_options},
     config::Config,
     error::ErrorNewType,
     quote::{
@@ -134,6 +136,25 @@ impl QuoteContext {
         Ok(QuoteContext { ctx, callbacks })
     }
 
+    /// Returns a `QuoteContext` sharing the connection and the callbacks with this
+    /// one, whose calls fail if they are not completed within `timeout`
+    /// milliseconds or before `deadline`, or when `cancellationToken` is
+    /// cancelled.
+    #[napi]
+    pub fn with_call_options(
+        &self,
+        timeout: Option<u32>,
+        deadline: Option<DateTime<Utc>>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> QuoteContext {
+        QuoteContext {
+            ctx: self
+                .ctx
+                .with_call_options(call_options(timeout, deadline, cancellation_token)),
+            callbacks: self.callbacks.clone(),
+        }
+    }
+
     /// Returns the member ID
     #[napi]
     pub fn member_id(&self) -> i64 {
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use longport::trade::{GetFundPositionsOptions, GetStockPositionsOptions, PushEvent};
 use napi::{Result, bindgen_prelude::*, threadsafe_function::ThreadsafeFunctionCallMode};
 use parking_lot::Mutex;
 
 use crate::{
+    call_options::{CancellationToken, call_options},
     config::Config,
     error::ErrorNewType,
     trade::{
@@ -67,6 +69,25 @@ impl TradeContext {
         Ok(TradeContext { ctx, callbacks })
     }
 
+    /// Returns a `TradeContext` sharing the connection and the callbacks with this
+    /// one, whose calls fail if they are not completed within `timeout`
+    /// milliseconds or before `deadline`, or when `cancellationToken` is
+    /// cancelled.
+    #[napi]
+    pub fn with_call_options(
+        &self,
+        timeout: Option<u32>,
+        deadline: Option<DateTime<Utc>>,
+        cancellation_token: Option<&CancellationToken>,
+    ) -> TradeContext {
+        TradeContext {
+            ctx: self
+                .ctx
+                .with_call_options(call_options(timeout, deadline, cancellation_token)),
+            callbacks: self.callbacks.clone(),
+        }
+    }
+
     /// Set order changed callback, after receiving the order changed event, it
     /// will call back to this function.
     #[napi(ts_args_type = "callback: (err: null | Error, event: PushOrderChanged) => void")]
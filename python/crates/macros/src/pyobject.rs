@@ -28,6 +28,9 @@ struct ObjectArgs {
     data: Data<Ignored, ObjectField>,
 
     remote: TypePath,
+    /// Generates `From<Self>` for the remote type
+    #[darling(default)]
+    into: bool,
 }
 
 pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
@@ -35,6 +38,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         ident,
         data,
         remote,
+        into,
     } = ObjectArgs::from_derive_input(&args)?;
 
     let s = match data {
@@ -46,6 +50,7 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
     let mut getters = Vec::new();
     let mut from_fields = Vec::new();
     let mut set_dictitem = Vec::new();
+    let mut into_fields = Vec::new();
 
     for field in &s.fields {
         let field_ident = field.ident.as_ref().unwrap();
@@ -64,6 +69,29 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
             }
         });
 
+        if into && (field.sub_types || field.derivative_types) {
+            return Err(Error::new_spanned(
+                field_ident,
+                "`sub_types` and `derivative_types` cannot be converted into the remote type",
+            )
+            .into());
+        } else if field.array {
+            into_fields.push(quote! {
+                #field_ident: value.#field_ident
+                    .into_iter()
+                    .map(::std::convert::Into::into)
+                    .collect(),
+            });
+        } else if field.opt {
+            into_fields.push(quote! {
+                #field_ident: value.#field_ident.map(::std::convert::Into::into),
+            });
+        } else {
+            into_fields.push(quote! {
+                #field_ident: value.#field_ident.into(),
+            });
+        }
+
         if field.sub_types {
             from_fields.push(quote! {
                 #field_ident: crate::quote::types::SubTypes::from(#field_ident).0,
@@ -93,7 +121,23 @@ pub(crate) fn generate(args: DeriveInput) -> GeneratorResult<TokenStream> {
         }
     }
 
+    let impl_into = if into {
+        Some(quote! {
+            impl ::std::convert::From<#ident> for #remote {
+                fn from(value: #ident) -> #remote {
+                    #remote {
+                        #(#into_fields)*
+                    }
+                }
+            }
+        })
+    } else {
+        None
+    };
+
     let expanded = quote! {
+        #impl_into
+
         #[::pyo3::pymethods]
         impl #ident {
             fn __repr__(&self) -> String {
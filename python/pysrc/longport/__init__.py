@@ -6,10 +6,19 @@ sys.modules['longport.openapi'] = openapi
 
 
 class OpenApiException(Exception):
-    def __init__(self, code: int, trace_id: str, message: str):
+    def __init__(self, code: int, trace_id: str, message: str, kind=None, retryable: bool = False, auth: bool = False):
         self.code = code
         self.trace_id = trace_id
         self.message = message
+        self.kind = kind if kind is not None else openapi.ErrorKind.Other
+        self._retryable = retryable
+        self._auth = auth
+
+    def is_retryable(self) -> bool:
+        return self._retryable
+
+    def is_auth(self) -> bool:
+        return self._auth
 
     def __str__(self):
         if self.code != None:
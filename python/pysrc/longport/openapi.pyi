@@ -13,14 +13,75 @@ class OpenApiException(Exception):
     Error code
     """
 
+    trace_id: Optional[str]
+    """
+    Trace id
+    """
+
     message: str
     """
     Error message
     """
 
-    def __init__(self, code: int, message: str) -> None:
+    kind: Type[ErrorKind]
+    """
+    Error kind, mapped from the known error codes
+    """
+
+    def __init__(self, code: Optional[int], trace_id: Optional[str], message: str, kind: Optional[Type[ErrorKind]] = None, retryable: bool = False, auth: bool = False) -> None:
         ...
 
+    def is_retryable(self) -> bool:
+        """
+        Returns `True` if the request may succeed when it is sent again, e.g. it is rate limited, timed out or the connection is lost
+        """
+
+    def is_auth(self) -> bool:
+        """
+        Returns `True` if the credentials are invalid or expired, or have no permission for the request
+        """
+
+
+class ErrorKind:
+    """
+    Error kind
+    """
+
+    class InsufficientBuyingPower(ErrorKind):
+        """
+        The buying power is insufficient for the order
+        """
+
+    class MarketClosed(ErrorKind):
+        """
+        The market is closed
+        """
+
+    class InvalidPriceTick(ErrorKind):
+        """
+        The price does not match the tick size of the security
+        """
+
+    class RateLimited(ErrorKind):
+        """
+        Too many requests
+        """
+
+    class TokenExpired(ErrorKind):
+        """
+        The access token is expired
+        """
+
+    class PermissionDenied(ErrorKind):
+        """
+        The account has no permission for the request
+        """
+
+    class Other(ErrorKind):
+        """
+        Other errors
+        """
+
 
 class HttpClient:
     """
@@ -89,6 +150,24 @@ class PushCandlestickMode:
         """
 
 
+class CancellationToken:
+    """
+    A token to cancel the calls of the contexts returned by `with_call_options`
+    """
+
+    def __init__(self) -> None: ...
+
+    def cancel(self) -> None:
+        """
+        Cancels the calls, the pending and the following calls fail
+        """
+
+    def is_cancelled(self) -> bool:
+        """
+        Returns `True` if the token is cancelled
+        """
+
+
 class Config:
     """
     Configuration options for LongPort sdk
@@ -144,6 +223,27 @@ class Config:
         - `LONGPORT_LOG_PATH` - Set the path of the log files (Default: `no logs`)
         """
 
+    @classmethod
+    def from_file(cls: Type[Config], path: str, profile: Optional[str] = None) -> Config:
+        """
+        Create a new `Config` from a TOML file, or a YAML file if the extension is `.yaml` or `.yml`
+
+        The keys of the file are the names of the environment variables without the `LONGPORT_` prefix in lowercase, e.g. `app_key` or `push_candlestick_mode`.
+
+        The options of `profile` in the `profiles` table override the top level options, if `profile` is `None`, the `LONGPORT_PROFILE` environment variable is used. The environment variables override the options of the file.
+
+        Args:
+            path: The path of the config file
+            profile: The name of the profile
+
+        Examples:
+            ::
+
+                from longport.openapi import Config
+
+                config = Config.from_file("longport.toml", profile="paper")
+        """
+
     def refresh_access_token(self, expired_at: Optional[datetime] = None) -> str:
         """
         Gets a new `access_token`
@@ -2681,6 +2781,25 @@ class QuoteContext:
 
     def __init__(self, config: Config) -> None: ...
 
+    def with_call_options(self, timeout: Optional[float] = None, deadline: Optional[datetime] = None, cancellation_token: Optional[CancellationToken] = None) -> "QuoteContext":
+        """
+        Returns a context sharing the connection and the callbacks with this one, whose calls fail if they are not completed within `timeout` seconds or before `deadline`, or when `cancellation_token` is cancelled
+
+        Args:
+            timeout: Timeout of the calls in seconds
+            deadline: Deadline of the calls, naive datetimes are local times
+            cancellation_token: Token to cancel the calls
+
+        Examples:
+            ::
+
+                from longport.openapi import QuoteContext, Config
+
+                config = Config.from_env()
+                ctx = QuoteContext(config)
+                fast_ctx = ctx.with_call_options(timeout=0.5)
+        """
+
     def member_id(self) -> int:
         """
         Returns the member ID
@@ -4965,6 +5084,25 @@ class TradeContext:
 
     def __init__(self, config: Config) -> None: ...
 
+    def with_call_options(self, timeout: Optional[float] = None, deadline: Optional[datetime] = None, cancellation_token: Optional[CancellationToken] = None) -> "TradeContext":
+        """
+        Returns a context sharing the connection and the callbacks with this one, whose calls fail if they are not completed within `timeout` seconds or before `deadline`, or when `cancellation_token` is cancelled
+
+        Args:
+            timeout: Timeout of the calls in seconds
+            deadline: Deadline of the calls, naive datetimes are local times
+            cancellation_token: Token to cancel the calls
+
+        Examples:
+            ::
+
+                from longport.openapi import TradeContext, Config
+
+                config = Config.from_env()
+                ctx = TradeContext(config)
+                fast_ctx = ctx.with_call_options(timeout=0.5)
+        """
+
     def set_on_order_changed(self, callback: Callable[[PushOrderChanged], None]) -> None:
         """
         Set order changed callback, after receiving the order changed event, it will call back to this function.
@@ -5395,3 +5533,78 @@ class TradeContext:
                 )
                 print(resp)
         """
+
+
+def to_arrow(items: List[Any]) -> Any:
+    """
+    Convert a list of `Candlestick`, `Trade`, `IntradayLine`, `SecurityQuote`, `Order` or `Execution` to a `pyarrow.Table`
+
+    The conversion runs in Rust, the decimals are `decimal128(38, 9)` and the timestamps are `timestamp("ns", "UTC")`. Requires `pyarrow>=14`.
+
+    Args:
+        items: Items of the same type
+
+    Returns:
+        `pyarrow.Table`
+
+    Examples:
+        ::
+
+            from longport.openapi import QuoteContext, Config, Period, AdjustType, to_arrow
+
+            config = Config.from_env()
+            ctx = QuoteContext(config)
+
+            table = to_arrow(ctx.candlesticks("700.HK", Period.Day, 1000, AdjustType.NoAdjust))
+            print(table.schema)
+    """
+
+
+def to_pandas(items: List[Any]) -> Any:
+    """
+    Convert a list of `Candlestick`, `Trade`, `IntradayLine`, `SecurityQuote`, `Order` or `Execution` to a `pandas.DataFrame`
+
+    Requires `pyarrow>=14` and `pandas`.
+
+    Args:
+        items: Items of the same type
+
+    Returns:
+        `pandas.DataFrame`
+
+    Examples:
+        ::
+
+            from longport.openapi import QuoteContext, Config, Period, AdjustType, to_pandas
+
+            config = Config.from_env()
+            ctx = QuoteContext(config)
+
+            df = to_pandas(ctx.candlesticks("700.HK", Period.Day, 1000, AdjustType.NoAdjust))
+            print(df)
+    """
+
+
+def to_polars(items: List[Any]) -> Any:
+    """
+    Convert a list of `Candlestick`, `Trade`, `IntradayLine`, `SecurityQuote`, `Order` or `Execution` to a `polars.DataFrame`
+
+    Requires `pyarrow>=14` and `polars`.
+
+    Args:
+        items: Items of the same type
+
+    Returns:
+        `polars.DataFrame`
+
+    Examples:
+        ::
+
+            from longport.openapi import QuoteContext, Config, Period, AdjustType, to_polars
+
+            config = Config.from_env()
+            ctx = QuoteContext(config)
+
+            df = to_polars(ctx.candlesticks("700.HK", Period.Day, 1000, AdjustType.NoAdjust))
+            print(df)
+    """
@@ -0,0 +1,96 @@
+use std::{ffi::CString, sync::Arc};
+
+use arrow_array::{RecordBatchIterator, ffi_stream::FFI_ArrowArrayStream};
+use arrow_schema::Schema;
+use longport::arrow::{ArrowRecord, RecordBatch};
+use pyo3::{
+    PyClass, exceptions::PyTypeError, prelude::*, pyclass::boolean_struct::False, types::PyCapsule,
+};
+
+use crate::{error::ErrorNewType, quote::types as quote, trade::types as trade};
+
+/// The record batch of a result list, exported with the Arrow PyCapsule
+/// interface
+#[pyclass]
+struct ArrowStream(RecordBatch);
+
+#[pymethods]
+impl ArrowStream {
+    /// The requested schema is ignored, the schema of the batch is always used
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let _ = requested_schema;
+        let reader = RecordBatchIterator::new([Ok(self.0.clone())], self.0.schema());
+        let stream = FFI_ArrowArrayStream::new(Box::new(reader));
+        PyCapsule::new(py, stream, Some(CString::new("arrow_array_stream")?))
+    }
+}
+
+fn to_record_batch<T, R>(items: &[Bound<PyAny>]) -> PyResult<RecordBatch>
+where
+    T: PyClass<Frozen = False> + Clone,
+    R: From<T> + ArrowRecord,
+{
+    let rows = items
+        .iter()
+        .map(|item| Ok(item.downcast::<T>()?.borrow().clone().into()))
+        .collect::<PyResult<Vec<R>>>()?;
+    Ok(R::to_record_batch(&rows).map_err(ErrorNewType)?)
+}
+
+/// Converts a result list to a record batch, the items must be of the same
+/// type
+fn record_batch(items: &Bound<PyAny>) -> PyResult<RecordBatch> {
+    let items = items.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+    let Some(first) = items.first() else {
+        return Ok(RecordBatch::new_empty(Arc::new(Schema::empty())));
+    };
+
+    if first.is_instance_of::<quote::Candlestick>() {
+        to_record_batch::<quote::Candlestick, longport::quote::Candlestick>(&items)
+    } else if first.is_instance_of::<quote::Trade>() {
+        to_record_batch::<quote::Trade, longport::quote::Trade>(&items)
+    } else if first.is_instance_of::<quote::IntradayLine>() {
+        to_record_batch::<quote::IntradayLine, longport::quote::IntradayLine>(&items)
+    } else if first.is_instance_of::<quote::SecurityQuote>() {
+        to_record_batch::<quote::SecurityQuote, longport::quote::SecurityQuote>(&items)
+    } else if first.is_instance_of::<trade::Order>() {
+        to_record_batch::<trade::Order, longport::trade::Order>(&items)
+    } else if first.is_instance_of::<trade::Execution>() {
+        to_record_batch::<trade::Execution, longport::trade::Execution>(&items)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot convert `{}` to arrow, expected Candlestick, Trade, IntradayLine, SecurityQuote, Order or Execution",
+            first.get_type().name()?
+        )))
+    }
+}
+
+/// Converts a list of candlesticks, trades, intraday lines, quotes, orders or
+/// executions to a `pyarrow.Table`
+#[pyfunction]
+pub(crate) fn to_arrow<'py>(items: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = items.py();
+    let stream = Bound::new(py, ArrowStream(record_batch(items)?))?;
+    py.import("pyarrow")?.call_method1("table", (stream,))
+}
+
+/// Converts a list of candlesticks, trades, intraday lines, quotes, orders or
+/// executions to a `pandas.DataFrame`
+#[pyfunction]
+pub(crate) fn to_pandas<'py>(items: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    to_arrow(items)?.call_method0("to_pandas")
+}
+
+/// Converts a list of candlesticks, trades, intraday lines, quotes, orders or
+/// executions to a `polars.DataFrame`
+#[pyfunction]
+pub(crate) fn to_polars<'py>(items: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = items.py();
+    py.import("polars")?
+        .call_method1("from_arrow", (to_arrow(items)?,))
+}
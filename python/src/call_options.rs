@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use longport::CallOptions;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// A token to cancel the calls of the contexts returned by `with_call_options`
+#[pyclass]
+#[derive(Clone, Default)]
+pub(crate) struct CancellationToken(longport::CancellationToken);
+
+#[pymethods]
+impl CancellationToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the calls, the pending and the following calls fail
+    fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    /// Returns `True` if the token is cancelled
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+}
+
+pub(crate) fn call_options(
+    timeout: Option<f64>,
+    deadline: Option<Bound<PyAny>>,
+    cancellation_token: Option<CancellationToken>,
+) -> PyResult<CallOptions> {
+    let mut options = CallOptions::new();
+    if let Some(timeout) = timeout {
+        options = options.timeout(
+            Duration::try_from_secs_f64(timeout)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?,
+        );
+    }
+    if let Some(deadline) = deadline {
+        // `datetime.timestamp()` treats the naive datetimes as local times
+        let timestamp = deadline.call_method0("timestamp")?.extract::<f64>()?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let remaining = Duration::try_from_secs_f64((timestamp - now).max(0.0))
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        options = options.deadline(Instant::now() + remaining);
+    }
+    if let Some(cancellation_token) = cancellation_token {
+        options = options.cancellation_token(cancellation_token.0);
+    }
+    Ok(options)
+}
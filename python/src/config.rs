@@ -74,6 +74,14 @@ impl Config {
         Ok(Self(longport::Config::from_env().map_err(ErrorNewType)?))
     }
 
+    #[classmethod]
+    #[pyo3(signature = (path, profile = None))]
+    fn from_file(_cls: Bound<PyType>, path: String, profile: Option<String>) -> PyResult<Self> {
+        Ok(Self(
+            longport::Config::from_file(path, profile.as_deref()).map_err(ErrorNewType)?,
+        ))
+    }
+
     /// Gets a new `access_token`.
     ///
     /// `expired_at` - The expiration time of the access token, defaults to `90`
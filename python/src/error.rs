@@ -1,5 +1,7 @@
 use pyo3::PyErr;
 
+use crate::types::ErrorKind;
+
 pyo3::import_exception!(longport.openapi, OpenApiException);
 
 pub(crate) struct ErrorNewType(pub(crate) longport::Error);
@@ -7,11 +9,17 @@ pub(crate) struct ErrorNewType(pub(crate) longport::Error);
 impl std::convert::From<ErrorNewType> for PyErr {
     #[inline]
     fn from(err: ErrorNewType) -> PyErr {
+        let kind: ErrorKind = err.0.kind().into();
+        let retryable = err.0.is_retryable();
+        let auth = err.0.is_auth();
         let err = err.0.into_simple_error();
         OpenApiException::new_err((
             err.code(),
             err.trace_id().map(ToString::to_string),
             err.message().to_string(),
+            kind,
+            retryable,
+            auth,
         ))
     }
 }
@@ -1,3 +1,5 @@
+mod arrow;
+mod call_options;
 mod config;
 mod decimal;
 mod error;
@@ -16,10 +18,15 @@ fn longport(py: Python<'_>, m: Bound<PyModule>) -> PyResult<()> {
     openapi.add_class::<config::Config>()?;
     openapi.add_class::<types::Language>()?;
     openapi.add_class::<types::Market>()?;
+    openapi.add_class::<types::ErrorKind>()?;
     openapi.add_class::<types::PushCandlestickMode>()?;
     openapi.add_class::<http_client::HttpClient>()?;
+    openapi.add_class::<call_options::CancellationToken>()?;
     quote::register_types(&openapi)?;
     trade::register_types(&openapi)?;
+    openapi.add_function(wrap_pyfunction!(arrow::to_arrow, &openapi)?)?;
+    openapi.add_function(wrap_pyfunction!(arrow::to_pandas, &openapi)?)?;
+    openapi.add_function(wrap_pyfunction!(arrow::to_polars, &openapi)?)?;
 
     m.add_submodule(&openapi)?;
     Ok(())
@@ -9,6 +9,7 @@ use pyo3::prelude::*;
 use time::PrimitiveDateTime;
 
 use crate::{
+    call_options::{CancellationToken, call_options},
     config::Config,
     error::ErrorNewType,
     quote::{
@@ -59,6 +60,25 @@ impl QuoteContext {
         Ok(Self { ctx, callbacks })
     }
 
+    /// Returns a `QuoteContext` sharing the connection and the callbacks with this
+    /// one, whose calls fail if they are not completed within `timeout`
+    /// seconds or before `deadline`, or when `cancellation_token` is
+    /// cancelled.
+    #[pyo3(signature = (timeout = None, deadline = None, cancellation_token = None))]
+    fn with_call_options(
+        &self,
+        timeout: Option<f64>,
+        deadline: Option<Bound<PyAny>>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            ctx: self
+                .ctx
+                .with_call_options(call_options(timeout, deadline, cancellation_token)?),
+            callbacks: self.callbacks.clone(),
+        })
+    }
+
     /// Returns the member ID
     fn member_id(&self) -> PyResult<i64> {
         Ok(self.ctx.member_id().map_err(ErrorNewType)?)
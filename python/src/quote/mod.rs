@@ -1,6 +1,6 @@
 mod context;
 mod push;
-mod types;
+pub(crate) mod types;
 
 use pyo3::prelude::*;
 
@@ -373,7 +373,7 @@ pub(crate) struct SecurityStaticInfo {
 /// Quote of US pre/post market
 #[pyclass]
 #[derive(Debug, PyObject, Copy, Clone)]
-#[py(remote = "longport::quote::PrePostQuote")]
+#[py(remote = "longport::quote::PrePostQuote", into)]
 pub(crate) struct PrePostQuote {
     /// Latest price
     last_done: PyDecimal,
@@ -393,8 +393,8 @@ pub(crate) struct PrePostQuote {
 
 /// Quote of securitity
 #[pyclass]
-#[derive(Debug, PyObject)]
-#[py(remote = "longport::quote::SecurityQuote")]
+#[derive(Debug, PyObject, Clone)]
+#[py(remote = "longport::quote::SecurityQuote", into)]
 pub(crate) struct SecurityQuote {
     /// Security code
     symbol: String,
@@ -596,7 +596,7 @@ pub(crate) struct ParticipantInfo {
 /// Trade
 #[pyclass]
 #[derive(Debug, PyObject, Clone)]
-#[py(remote = "longport::quote::Trade")]
+#[py(remote = "longport::quote::Trade", into)]
 pub(crate) struct Trade {
     /// Price
     price: PyDecimal,
@@ -614,8 +614,8 @@ pub(crate) struct Trade {
 
 /// Intraday line
 #[pyclass]
-#[derive(Debug, PyObject)]
-#[py(remote = "longport::quote::IntradayLine")]
+#[derive(Debug, PyObject, Clone)]
+#[py(remote = "longport::quote::IntradayLine", into)]
 pub(crate) struct IntradayLine {
     /// Close price of the minute
     price: PyDecimal,
@@ -632,7 +632,7 @@ pub(crate) struct IntradayLine {
 /// Candlestick
 #[pyclass]
 #[derive(Debug, PyObject, Clone)]
-#[py(remote = "longport::quote::Candlestick")]
+#[py(remote = "longport::quote::Candlestick", into)]
 pub(crate) struct Candlestick {
     /// Close price
     close: PyDecimal,
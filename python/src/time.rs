@@ -44,6 +44,13 @@ impl From<OffsetDateTime> for PyOffsetDateTimeWrapper {
     }
 }
 
+impl From<PyOffsetDateTimeWrapper> for OffsetDateTime {
+    #[inline]
+    fn from(value: PyOffsetDateTimeWrapper) -> Self {
+        value.0
+    }
+}
+
 impl<'py> IntoPyObject<'py> for PyOffsetDateTimeWrapper {
     type Target = PyDateTime;
     type Output = Bound<'py, Self::Target>;
@@ -9,9 +9,10 @@ use longport::{
     },
 };
 use parking_lot::Mutex;
-use pyo3::{PyObject, PyResult, Python, pyclass, pymethods};
+use pyo3::{Bound, PyAny, PyObject, PyResult, Python, pyclass, pymethods};
 
 use crate::{
+    call_options::{CancellationToken, call_options},
     config::Config,
     decimal::PyDecimal,
     error::ErrorNewType,
@@ -54,6 +55,25 @@ impl TradeContext {
         Ok(Self { ctx, callbacks })
     }
 
+    /// Returns a `TradeContext` sharing the connection and the callbacks with this
+    /// one, whose calls fail if they are not completed within `timeout`
+    /// seconds or before `deadline`, or when `cancellation_token` is
+    /// cancelled.
+    #[pyo3(signature = (timeout = None, deadline = None, cancellation_token = None))]
+    fn with_call_options(
+        &self,
+        timeout: Option<f64>,
+        deadline: Option<Bound<PyAny>>,
+        cancellation_token: Option<CancellationToken>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            ctx: self
+                .ctx
+                .with_call_options(call_options(timeout, deadline, cancellation_token)?),
+            callbacks: self.callbacks.clone(),
+        })
+    }
+
     /// Set order changed callback, after receiving the order changed event, it
     /// will call back to this function.
     fn set_on_order_changed(&self, py: Python<'_>, callback: PyObject) {
@@ -18,8 +18,8 @@ pub(crate) enum TopicType {
 
 /// Trade
 #[pyclass]
-#[derive(Debug, PyObject)]
-#[py(remote = "longport::trade::Execution")]
+#[derive(Debug, PyObject, Clone)]
+#[py(remote = "longport::trade::Execution", into)]
 pub(crate) struct Execution {
     /// Order ID
     order_id: String,
@@ -198,8 +198,8 @@ pub(crate) enum OutsideRTH {
 
 /// Order
 #[pyclass]
-#[derive(Debug, PyObject)]
-#[py(remote = "longport::trade::Order")]
+#[derive(Debug, PyObject, Clone)]
+#[py(remote = "longport::trade::Order", into)]
 pub(crate) struct Order {
     /// Order ID
     order_id: String,
@@ -19,6 +19,26 @@ pub(crate) enum Market {
     Crypto,
 }
 
+#[pyclass(eq, eq_int)]
+#[derive(Debug, PyEnum, Copy, Clone, Hash, Eq, PartialEq)]
+#[py(remote = "longport::ErrorKind")]
+pub(crate) enum ErrorKind {
+    /// The buying power is insufficient for the order
+    InsufficientBuyingPower,
+    /// The market is closed
+    MarketClosed,
+    /// The price does not match the tick size of the security
+    InvalidPriceTick,
+    /// Too many requests
+    RateLimited,
+    /// The access token is expired
+    TokenExpired,
+    /// The account has no permission for the request
+    PermissionDenied,
+    /// Other errors
+    Other,
+}
+
 #[pyclass(eq, eq_int)]
 #[derive(Debug, PyEnum, Copy, Clone, Hash, Eq, PartialEq)]
 #[allow(non_camel_case_types)]
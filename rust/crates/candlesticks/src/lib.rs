@@ -7,7 +7,8 @@ mod types;
 
 pub use candlestick::{CandlestickComponents, CandlestickType};
 pub use market::{
-    Days, Market, TRADE_SESSION_INTRADAY, TRADE_SESSION_OVERNIGHT, TRADE_SESSION_POST,
-    TRADE_SESSION_PRE, TradeSession, TradeSessionKind, TradeSessionType, UpdateAction,
+    Days, Market, SessionTable, TRADE_SESSION_INTRADAY, TRADE_SESSION_OVERNIGHT,
+    TRADE_SESSION_POST, TRADE_SESSION_PRE, TradeSession, TradeSessionKind, TradeSessionType,
+    UpdateAction,
 };
 pub use types::{Period, QuoteType, TradeType, UpdateFields};
@@ -82,10 +82,32 @@ pub const TRADE_SESSION_PRE: TradeSessionKind = TradeSessionKind(1);
 pub const TRADE_SESSION_POST: TradeSessionKind = TradeSessionKind(2);
 pub const TRADE_SESSION_OVERNIGHT: TradeSessionKind = TradeSessionKind(3);
 
+/// The trade sessions of a market, indexed by [`TradeSessionKind`]
+pub trait SessionTable {
+    /// Returns the sessions of each trade session kind
+    fn kinds(&self) -> impl Iterator<Item = &[TradeSession]>;
+}
+
+impl SessionTable for &[&[TradeSession]] {
+    #[inline]
+    fn kinds(&self) -> impl Iterator<Item = &[TradeSession]> {
+        self.iter().copied()
+    }
+}
+
+impl SessionTable for Vec<Vec<TradeSession>> {
+    #[inline]
+    fn kinds(&self) -> impl Iterator<Item = &[TradeSession]> {
+        self.iter().map(Vec::as_slice)
+    }
+}
+
+/// A market, the compiled-in markets of [`crate::markets`] have static trade
+/// session tables, the tables loaded at runtime are owned
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub struct Market {
+pub struct Market<S = &'static [&'static [TradeSession]]> {
     pub timezone: &'static Tz,
-    pub trade_sessions: &'static [&'static [TradeSession]],
+    pub trade_sessions: S,
     pub half_trade_sessions: &'static [&'static [TradeSession]],
     pub lot_size: i32,
 }
@@ -115,7 +137,89 @@ impl Days for &HashSet<Date> {
     }
 }
 
-impl Market {
+impl<S: SessionTable> Market<S> {
+    /// Returns the sessions of the trade session type, or the sessions of the
+    /// half trading days if `half_day` is `true`
+    #[inline]
+    pub fn sessions<TS>(&self, ts: TS, half_day: bool) -> Option<&[TradeSession]>
+    where
+        TS: TradeSessionType,
+    {
+        if !half_day {
+            self.trade_sessions.kinds().nth(ts.kind().0)
+        } else {
+            self.half_trade_sessions.get(ts.kind().0).copied()
+        }
+    }
+
+    /// Returns the market with the trade session table replaced
+    pub fn with_trade_sessions<T: SessionTable>(&self, trade_sessions: T) -> Market<T> {
+        Market {
+            timezone: self.timezone,
+            trade_sessions,
+            half_trade_sessions: self.half_trade_sessions,
+            lot_size: self.lot_size,
+        }
+    }
+
+    /// Returns a copy of the market with an owned trade session table
+    pub fn to_owned_sessions(&self) -> Market<Vec<Vec<TradeSession>>> {
+        self.with_trade_sessions(
+            self.trade_sessions
+                .kinds()
+                .map(<[TradeSession]>::to_vec)
+                .collect(),
+        )
+    }
+
+    /// Builds the trade session table from the `(trade session type, start,
+    /// end)` periods, e.g. loaded from the server or a file
+    ///
+    /// The sessions of the trade session types without periods are kept. A
+    /// period ending before it starts crosses midnight and is split in two. A
+    /// session ending at the same time as a session of the same type in this
+    /// table keeps its timeout and `inclusive` flag.
+    pub fn build_trade_sessions<TS, I>(&self, periods: I) -> Vec<Vec<TradeSession>>
+    where
+        TS: TradeSessionType,
+        I: IntoIterator<Item = (TS, Time, Time)>,
+    {
+        let mut table = self.to_owned_sessions().trade_sessions;
+        let mut replaced = vec![false; table.len()];
+
+        for (ts, start, end) in periods {
+            let idx = ts.kind().0;
+            if idx >= table.len() {
+                table.resize(idx + 1, Vec::new());
+                replaced.resize(idx + 1, false);
+            }
+            if !replaced[idx] {
+                table[idx].clear();
+                replaced[idx] = true;
+            }
+
+            let current = self.sessions(ts, false).unwrap_or_default();
+            let session =
+                |start: Time, end: Time| match current.iter().find(|session| session.end == end) {
+                    Some(session) => TradeSession { start, ..*session },
+                    None => TradeSession::new(start, end),
+                };
+            if end > start {
+                table[idx].push(session(start, end));
+            } else {
+                table[idx].push(session(start, Time::MAX).with_inclusive());
+                if end > Time::MIDNIGHT {
+                    table[idx].push(session(Time::MIDNIGHT, end));
+                }
+            }
+        }
+
+        for sessions in &mut table {
+            sessions.sort_by_key(|session| session.start);
+        }
+        table
+    }
+
     pub fn candlestick_time<H, TS>(
         &self,
         ts: TS,
@@ -133,15 +237,9 @@ impl Market {
             return None;
         }
 
-        let ts = ts.kind();
-
         let t = t.to_timezone(self.timezone);
         let time = t.time();
-        let trade_sessions = if !half_days.contains(t.date()) {
-            self.trade_sessions.get(ts.0)?
-        } else {
-            self.half_trade_sessions.get(ts.0)?
-        };
+        let trade_sessions = self.sessions(ts, half_days.contains(t.date()))?;
         let res = trade_sessions.find_session(time);
         let (time, n) = match res {
             FindSessionResult::BeforeFirst => return None,
@@ -374,7 +472,7 @@ impl Market {
 
     pub fn trade_session(&self, candlestick_time: OffsetDateTime) -> Option<TradeSessionKind> {
         let candlestick_time = candlestick_time.to_timezone(self.timezone);
-        for (idx, trade_sessions) in self.trade_sessions.iter().enumerate() {
+        for (idx, trade_sessions) in self.trade_sessions.kinds().enumerate() {
             for TradeSession {
                 start,
                 end,
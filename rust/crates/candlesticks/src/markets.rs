@@ -91,3 +91,40 @@ pub const US_OPTION: Market = Market {
     ],
     lot_size: 1,
 };
+
+pub const JP: Market = Market {
+    timezone: db::asia::TOKYO,
+    trade_sessions: &[
+        // Intraday
+        &[
+            TradeSession::new(time!(9:00:00), time!(11:30:00)),
+            TradeSession::new(time!(12:30:00), time!(15:30:00)).with_timeout(Duration::minutes(5)),
+        ],
+    ],
+    half_trade_sessions: &[],
+    lot_size: 1,
+};
+
+pub const AU: Market = Market {
+    timezone: db::australia::SYDNEY,
+    trade_sessions: &[
+        // Intraday
+        &[TradeSession::new(time!(10:00:00), time!(16:00:00)).with_timeout(Duration::minutes(12))],
+    ],
+    half_trade_sessions: &[
+        // Intraday
+        &[TradeSession::new(time!(10:00:00), time!(14:10:00)).with_timeout(Duration::minutes(2))],
+    ],
+    lot_size: 1,
+};
+
+/// The crypto market trades 24/7, the bars are aligned to UTC
+pub const CRYPTO: Market = Market {
+    timezone: db::UTC,
+    trade_sessions: &[
+        // Intraday
+        &[TradeSession::new(time!(0:00:00), time!(23:59:59.999_999_999)).with_inclusive()],
+    ],
+    half_trade_sessions: &[],
+    lot_size: 1,
+};
@@ -0,0 +1,48 @@
+use longport_candlesticks::{
+    Period, TRADE_SESSION_INTRADAY, markets::AU, testutil::TestCandlestickTime,
+};
+use time::macros::{datetime, time};
+
+#[test]
+fn au_min1() {
+    let t = TestCandlestickTime::new(&AU, Period::Min_1);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:59:59), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(10:00:00), time!(10:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(10:00:30), time!(10:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:59:59), time!(15:59:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(16:00:00), time!(16:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(16:11:59), time!(16:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(16:12:00), None);
+}
+
+#[test]
+fn au_min60() {
+    let t = TestCandlestickTime::new(&AU, Period::Min_60);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(10:59:59), time!(10:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:30:00), time!(15:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(16:05:00), time!(16:00:00));
+}
+
+#[test]
+fn au_half_day() {
+    let date = time::Date::from_calendar_date(2024, time::Month::December, 24).unwrap();
+    let half_days = [date].into_iter().collect::<std::collections::HashSet<_>>();
+    assert_eq!(
+        AU.candlestick_time(
+            TRADE_SESSION_INTRADAY,
+            &half_days,
+            Period::Min_1,
+            datetime!(2024-12-24 14:09:30 +11),
+        ),
+        Some(datetime!(2024-12-24 14:09:00 +11))
+    );
+    assert_eq!(
+        AU.candlestick_time(
+            TRADE_SESSION_INTRADAY,
+            &half_days,
+            Period::Min_1,
+            datetime!(2024-12-24 14:30:00 +11),
+        ),
+        None
+    );
+}
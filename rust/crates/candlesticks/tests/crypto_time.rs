@@ -0,0 +1,60 @@
+use longport_candlesticks::{
+    Period, TRADE_SESSION_INTRADAY, TRADE_SESSION_PRE, markets::CRYPTO,
+    testutil::TestCandlestickTime,
+};
+use time::macros::{datetime, time};
+
+#[test]
+fn crypto_min1() {
+    let t = TestCandlestickTime::new(&CRYPTO, Period::Min_1);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(00:00:00), time!(00:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(00:00:59), time!(00:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:34:42), time!(09:34:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(23:59:00), time!(23:59:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(23:59:59), time!(23:59:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(23:59:59.999), time!(23:59:00));
+    t.check_time(TRADE_SESSION_PRE, time!(09:34:42), None);
+}
+
+#[test]
+fn crypto_min60() {
+    let t = TestCandlestickTime::new(&CRYPTO, Period::Min_60);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(00:00:00), time!(00:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(00:59:59), time!(00:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(13:30:00), time!(13:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(23:59:59), time!(23:00:00));
+}
+
+#[test]
+fn crypto_min240() {
+    let t = TestCandlestickTime::new(&CRYPTO, Period::Min_240);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(03:59:59), time!(00:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(04:00:00), time!(04:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(23:59:59), time!(20:00:00));
+}
+
+#[test]
+fn crypto_day() {
+    let t = TestCandlestickTime::new(&CRYPTO, Period::Day);
+    t.check_datetime(
+        TRADE_SESSION_INTRADAY,
+        datetime!(2024-1-6 23:59:59 UTC),
+        datetime!(2024-1-6 0:00:00 UTC),
+    );
+    // aligned to UTC
+    t.check_datetime(
+        TRADE_SESSION_INTRADAY,
+        datetime!(2024-1-7 07:30:00 +8),
+        datetime!(2024-1-6 0:00:00 UTC),
+    );
+}
+
+#[test]
+fn crypto_week() {
+    let t = TestCandlestickTime::new(&CRYPTO, Period::Week);
+    t.check_datetime(
+        TRADE_SESSION_INTRADAY,
+        datetime!(2024-1-7 12:00:00 UTC),
+        datetime!(2024-1-1 0:00:00 UTC),
+    );
+}
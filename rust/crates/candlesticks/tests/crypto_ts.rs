@@ -0,0 +1,16 @@
+use longport_candlesticks::{TRADE_SESSION_INTRADAY, markets::CRYPTO};
+use time::macros::datetime;
+
+#[test]
+fn crypto_trade_session() {
+    let market = CRYPTO;
+
+    assert_eq!(
+        market.trade_session(datetime!(2024-1-1 00:00:00 UTC)),
+        Some(TRADE_SESSION_INTRADAY)
+    );
+    assert_eq!(
+        market.trade_session(datetime!(2024-1-6 23:59:00 UTC)),
+        Some(TRADE_SESSION_INTRADAY)
+    );
+}
@@ -0,0 +1,31 @@
+use longport_candlesticks::{
+    Period, TRADE_SESSION_INTRADAY, markets::JP, testutil::TestCandlestickTime,
+};
+use time::macros::time;
+
+#[test]
+fn jp_min1() {
+    let t = TestCandlestickTime::new(&JP, Period::Min_1);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(08:59:59), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:00:00), time!(09:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:00:30), time!(09:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:29:59), time!(11:29:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:30:00), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(12:29:59), None);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(12:30:00), time!(12:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:29:59), time!(15:29:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:30:00), time!(15:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:34:59), time!(15:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:35:00), None);
+}
+
+#[test]
+fn jp_min30() {
+    let t = TestCandlestickTime::new(&JP, Period::Min_30);
+    t.check_time(TRADE_SESSION_INTRADAY, time!(09:29:59), time!(09:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(11:29:59), time!(11:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(12:30:00), time!(12:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(12:59:59), time!(12:30:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:29:59), time!(15:00:00));
+    t.check_time(TRADE_SESSION_INTRADAY, time!(15:30:00), time!(15:30:00));
+}
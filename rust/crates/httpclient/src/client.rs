@@ -6,11 +6,24 @@ use reqwest::{
 };
 use serde::Deserialize;
 
-use crate::{HttpClientConfig, HttpClientError, HttpClientResult, Json, RequestBuilder};
+use crate::{
+    HttpClientConfig, HttpClientError, HttpClientResult, Json, RateLimitBudget, RequestBuilder,
+};
+
+/// Create a `reqwest` client which sends all requests through `proxy`
+pub(crate) fn create_client(proxy: Option<&str>) -> Result<Client, String> {
+    let Some(proxy) = proxy else {
+        return Ok(Client::new());
+    };
+    Client::builder()
+        .proxy(reqwest::Proxy::all(proxy).map_err(|err| err.to_string())?)
+        .build()
+        .map_err(|err| err.to_string())
+}
 
 /// LongPort HTTP client
 pub struct HttpClient {
-    pub(crate) http_cli: Client,
+    pub(crate) http_cli: Result<Client, String>,
     pub(crate) config: Arc<HttpClientConfig>,
     pub(crate) default_headers: HeaderMap,
 }
@@ -19,7 +32,7 @@ impl HttpClient {
     /// Create a new `HttpClient`
     pub fn new(config: HttpClientConfig) -> Self {
         Self {
-            http_cli: Client::new(),
+            http_cli: create_client(config.proxy.as_deref()),
             config: Arc::new(config),
             default_headers: HeaderMap::new(),
         }
@@ -33,6 +46,7 @@ impl HttpClient {
     /// - LONGPORT_APP_SECRET
     /// - LONGPORT_ACCESS_TOKEN
     /// - LONGPORT_HTTP_URL
+    /// - LONGPORT_PROXY
     pub fn from_env() -> Result<Self, HttpClientError> {
         Ok(Self::new(HttpClientConfig::from_env()?))
     }
@@ -51,6 +65,15 @@ impl HttpClient {
         self
     }
 
+    /// Returns the client-side rate limit budget of the endpoint, or `None`
+    /// if the endpoint is not rate limited.
+    ///
+    /// The budget is shared by all clients created from the same
+    /// [`HttpClientConfig`].
+    pub fn rate_limit_budget(&self, method: &Method, path: &str) -> Option<RateLimitBudget> {
+        self.config.rate_limiter.budget(method, path)
+    }
+
     /// Create a new request builder
     #[inline]
     pub fn request(
@@ -26,6 +26,10 @@ pub enum HttpClientError {
         name: &'static str,
     },
 
+    /// Invalid proxy url
+    #[error("invalid proxy: {0}")]
+    InvalidProxy(String),
+
     /// Unexpected response
     #[error("unexpected response")]
     UnexpectedResponse,
@@ -65,6 +69,10 @@ pub enum HttpClientError {
     #[error(transparent)]
     Http(#[from] HttpError),
 
+    /// The circuit breaker is open, the request is not sent
+    #[error("circuit breaker is open")]
+    CircuitOpen,
+
     /// Connection limit exceeded
     #[error("connections limitation is hit, limit = {limit}, online = {online}")]
     ConnectionLimitExceeded {
@@ -75,6 +83,22 @@ pub enum HttpClientError {
     },
 }
 
+impl HttpClientError {
+    /// Returns `true` if the request failed because the server is
+    /// unreachable, i.e. it timed out, the connection failed or the server
+    /// responded with a `5xx` status
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            HttpClientError::RequestTimeout => true,
+            HttpClientError::BadStatus(status) => status.is_server_error(),
+            HttpClientError::Http(err) => {
+                err.0.is_timeout() || err.0.is_connect() || err.0.is_request()
+            }
+            _ => false,
+        }
+    }
+}
+
 /// Represents an HTTP error
 #[derive(Debug)]
 pub struct HttpError(pub reqwest::Error);
@@ -1,45 +1,251 @@
-use std::{cell::RefCell, time::Duration};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    time::Duration,
+};
 
-// because we may call `is_cn` multi times in a short time, we cache the result
-thread_local! {
-    static REGION: RefCell<Option<String>> = const { RefCell::new(None) };
+use tokio::sync::OnceCell;
+
+use crate::client::create_client;
+
+const PING_URL: &str = "https://api.lbkrs.com/_ping";
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+// the region is detected at most once per process, so that all threads and
+// runtimes (e.g. the blocking contexts) agree on the same endpoints
+static AUTO_REGION: OnceCell<RegionResolution> = OnceCell::const_new();
+
+/// The OpenAPI endpoints of a region
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Endpoints {
+    /// HTTP API url
+    pub http_url: &'static str,
+    /// Quote websocket url
+    pub quote_ws_url: &'static str,
+    /// Trade websocket url
+    pub trade_ws_url: &'static str,
+}
+
+const GLOBAL_ENDPOINTS: Endpoints = Endpoints {
+    http_url: "https://openapi.longportapp.com",
+    quote_ws_url: "wss://openapi-quote.longportapp.com/v2",
+    trade_ws_url: "wss://openapi-trade.longportapp.com/v2",
+};
+
+const CN_ENDPOINTS: Endpoints = Endpoints {
+    http_url: "https://openapi.longportapp.cn",
+    quote_ws_url: "wss://openapi-quote.longportapp.cn/v2",
+    trade_ws_url: "wss://openapi-trade.longportapp.cn/v2",
+};
+
+/// The region of the OpenAPI endpoints
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Region {
+    /// Detect the region from the `LONGPORT_REGION` environment variable, or
+    /// by pinging the OpenAPI server if it is not set
+    #[default]
+    Auto,
+    /// Global
+    Global,
+    /// China Mainland
+    CN,
+}
+
+impl Region {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Region::Auto => "auto",
+            Region::Global => "global",
+            Region::CN => "cn",
+        }
+    }
 }
 
-async fn region() -> Option<String> {
+impl Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse region error
+#[derive(Debug, thiserror::Error)]
+#[error("invalid region: {0}")]
+pub struct ParseRegionError(String);
+
+impl FromStr for Region {
+    type Err = ParseRegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Region::Auto),
+            "global" => Ok(Region::Global),
+            "cn" => Ok(Region::CN),
+            _ => Err(ParseRegionError(s.to_string())),
+        }
+    }
+}
+
+/// The reason why a region was chosen
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RegionReason {
+    /// Specified in the config
+    Config,
+    /// Specified by the `LONGPORT_REGION` environment variable
+    EnvVar,
+    /// Detected by pinging the OpenAPI server, `ip_region` is the region of
+    /// the client ip reported by the server
+    Ping {
+        /// The region of the client ip
+        ip_region: String,
+    },
+    /// Failed to ping the OpenAPI server, fallback to the global region
+    PingFailed {
+        /// The reason of the failure
+        error: String,
+    },
+}
+
+impl Display for RegionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionReason::Config => f.write_str("specified in the config"),
+            RegionReason::EnvVar => f.write_str("specified by LONGPORT_REGION"),
+            RegionReason::Ping { ip_region } => write!(f, "client ip region is {ip_region}"),
+            RegionReason::PingFailed { error } => {
+                write!(
+                    f,
+                    "failed to detect the region ({error}), fallback to global"
+                )
+            }
+        }
+    }
+}
+
+/// The region chosen for the OpenAPI endpoints and the reason
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RegionResolution {
+    /// The chosen region, either [`Region::Global`] or [`Region::CN`]
+    pub region: Region,
+    /// The reason why the region was chosen
+    pub reason: RegionReason,
+}
+
+impl RegionResolution {
+    /// Returns the endpoints of the chosen region
+    pub fn endpoints(&self) -> &'static Endpoints {
+        match self.region {
+            Region::CN => &CN_ENDPOINTS,
+            Region::Global | Region::Auto => &GLOBAL_ENDPOINTS,
+        }
+    }
+}
+
+/// Resolve [`Region::Auto`] to a concrete region, the other regions are
+/// returned as is
+///
+/// The automatic detection runs at most once per process, and all the
+/// subsequent calls return the same result.
+pub async fn resolve_region(region: Region, proxy: Option<&str>) -> RegionResolution {
+    match region {
+        Region::Global | Region::CN => RegionResolution {
+            region,
+            reason: RegionReason::Config,
+        },
+        Region::Auto => AUTO_REGION
+            .get_or_init(|| async {
+                let resolution = detect_region(proxy).await;
+                tracing::info!(region = %resolution.region, reason = %resolution.reason, "region resolved");
+                resolution
+            })
+            .await
+            .clone(),
+    }
+}
+
+async fn detect_region(proxy: Option<&str>) -> RegionResolution {
     // check user defined REGION
     if let Ok(region) = std::env::var("LONGPORT_REGION") {
-        return Some(region);
+        return RegionResolution {
+            region: if region.eq_ignore_ascii_case("CN") {
+                Region::CN
+            } else {
+                Region::Global
+            },
+            reason: RegionReason::EnvVar,
+        };
     }
 
     // check network connectivity
-    // make sure block_on doesn't block the outer tokio runtime
-    ping().await
-}
-
-async fn ping() -> Option<String> {
-    if let Some(region) = REGION.with_borrow(Clone::clone) {
-        return Some(region.clone());
+    match ping(proxy).await {
+        Ok(ip_region) => RegionResolution {
+            region: if ip_region.eq_ignore_ascii_case("CN") {
+                Region::CN
+            } else {
+                Region::Global
+            },
+            reason: RegionReason::Ping { ip_region },
+        },
+        Err(error) => RegionResolution {
+            region: Region::Global,
+            reason: RegionReason::PingFailed { error },
+        },
     }
+}
 
-    let Ok(resp) = reqwest::Client::new()
-        .get("https://api.lbkrs.com/_ping")
-        .timeout(Duration::from_secs(1))
+async fn ping(proxy: Option<&str>) -> Result<String, String> {
+    let resp = create_client(proxy)?
+        .get(PING_URL)
+        .timeout(PING_TIMEOUT)
         .send()
         .await
-    else {
-        return None;
-    };
-    let region = resp
-        .headers()
+        .map_err(|err| err.to_string())?;
+    resp.headers()
         .get("X-Ip-Region")
-        .and_then(|v| v.to_str().ok())?;
-    REGION.set(Some(region.to_string()));
-    Some(region.to_string())
+        .and_then(|v| v.to_str().ok())
+        .map(ToString::to_string)
+        .ok_or_else(|| "missing X-Ip-Region header".to_string())
 }
 
 /// do the best to guess whether the access point is in China Mainland or not
+///
+/// The region is detected through the proxy specified by the `LONGPORT_PROXY`
+/// environment variable.
 pub async fn is_cn() -> bool {
-    region()
-        .await
-        .is_some_and(|region| region.eq_ignore_ascii_case("CN"))
+    is_cn_with_proxy(std::env::var("LONGPORT_PROXY").ok().as_deref()).await
+}
+
+/// Same as [`is_cn`], but the region is detected through `proxy`
+pub async fn is_cn_with_proxy(proxy: Option<&str>) -> bool {
+    resolve_region(Region::Auto, proxy).await.region == Region::CN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_explicit_region() {
+        let resolution = resolve_region(Region::CN, None).await;
+        assert_eq!(resolution.region, Region::CN);
+        assert_eq!(resolution.reason, RegionReason::Config);
+        assert_eq!(
+            resolution.endpoints().http_url,
+            "https://openapi.longportapp.cn"
+        );
+
+        let resolution = resolve_region(Region::Global, None).await;
+        assert_eq!(resolution.region, Region::Global);
+        assert_eq!(
+            resolution.endpoints().quote_ws_url,
+            "wss://openapi-quote.longportapp.com/v2"
+        );
+    }
+
+    #[test]
+    fn test_parse_region() {
+        assert_eq!("CN".parse::<Region>().unwrap(), Region::CN);
+        assert_eq!("global".parse::<Region>().unwrap(), Region::Global);
+        assert_eq!("Auto".parse::<Region>().unwrap(), Region::Auto);
+        assert!("hk".parse::<Region>().is_err());
+    }
 }
@@ -9,15 +9,24 @@ mod client;
 mod config;
 mod error;
 mod geo;
+pub mod metrics;
 mod qs;
+mod rate_limit;
+mod redact;
 mod request;
+mod retry;
 mod signature;
 mod timestamp;
 
 pub use client::HttpClient;
-pub use config::HttpClientConfig;
+pub use config::{Credentials, HttpClientConfig};
 pub use error::{HttpClientError, HttpClientResult, HttpError};
-pub use geo::is_cn;
+pub use geo::{
+    Endpoints, ParseRegionError, Region, RegionReason, RegionResolution, is_cn, is_cn_with_proxy,
+    resolve_region,
+};
 pub use qs::QsError;
+pub use rate_limit::{RateLimit, RateLimitBudget};
 pub use request::{FromPayload, Json, RequestBuilder, ToPayload};
 pub use reqwest::Method;
+pub use retry::{CircuitBreaker, CircuitBreakerPolicy, RetryPolicy};
@@ -0,0 +1,63 @@
+//! Metrics of the HTTP client
+//!
+//! The metrics are recorded through the [`metrics`](https://docs.rs/metrics)
+//! facade, they are no-op until a recorder is installed.
+
+use std::time::Duration;
+
+use metrics::{
+    Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+use reqwest::Method;
+
+/// The number of HTTP requests, labeled by `method`, `path` and `status`
+pub const HTTP_REQUESTS_TOTAL: &str = "longport_http_requests_total";
+/// The latency of HTTP requests, labeled by `method`, `path` and `status`
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "longport_http_request_duration_seconds";
+/// The time spent waiting for the client-side rate limiter, labeled by
+/// `method` and `path`
+pub const HTTP_RATE_LIMIT_WAIT_SECONDS: &str = "longport_http_rate_limit_wait_seconds";
+/// The number of HTTP requests waiting for the client-side rate limiter,
+/// labeled by `method` and `path`
+pub const HTTP_RATE_LIMIT_QUEUE_DEPTH: &str = "longport_http_rate_limit_queue_depth";
+
+/// Register the descriptions of the metrics with the installed recorder
+pub fn describe_metrics() {
+    describe_counter!(HTTP_REQUESTS_TOTAL, "The number of HTTP requests");
+    describe_histogram!(
+        HTTP_REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "The latency of HTTP requests"
+    );
+    describe_histogram!(
+        HTTP_RATE_LIMIT_WAIT_SECONDS,
+        Unit::Seconds,
+        "The time spent waiting for the client-side rate limiter"
+    );
+    describe_gauge!(
+        HTTP_RATE_LIMIT_QUEUE_DEPTH,
+        "The number of HTTP requests waiting for the client-side rate limiter"
+    );
+}
+
+/// `status` is the HTTP status code, or `timeout` and `error` if no response
+/// was received
+pub(crate) fn record_request(method: &Method, path: &str, status: &str, duration: Duration) {
+    let labels = [
+        ("method", method.to_string()),
+        ("path", path.to_string()),
+        ("status", status.to_string()),
+    ];
+    counter!(HTTP_REQUESTS_TOTAL, &labels).increment(1);
+    histogram!(HTTP_REQUEST_DURATION_SECONDS, &labels).record(duration);
+}
+
+pub(crate) fn record_rate_limit_wait(method: &Method, path: &str, waited: Duration) {
+    histogram!(HTTP_RATE_LIMIT_WAIT_SECONDS, "method" => method.to_string(), "path" => path.to_string())
+        .record(waited);
+}
+
+pub(crate) fn set_rate_limit_queue_depth(method: &Method, path: &str, depth: usize) {
+    gauge!(HTTP_RATE_LIMIT_QUEUE_DEPTH, "method" => method.to_string(), "path" => path.to_string())
+        .set(depth as f64);
+}
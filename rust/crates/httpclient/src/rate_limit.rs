@@ -0,0 +1,301 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use parking_lot::Mutex;
+use reqwest::Method;
+use tokio::time::Instant;
+
+use crate::metrics;
+
+/// Rate limiter config
+///
+/// Tokens are added in batches of `refill` every `interval`, up to `max`,
+/// and every request takes one token.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RateLimit {
+    /// The time duration between which we add refill number to the bucket
+    pub interval: Duration,
+    /// The initial number of tokens
+    pub initial: usize,
+    /// The max number of tokens to use
+    pub max: usize,
+    /// The number of tokens to add at each interval interval
+    pub refill: usize,
+    /// The minimum time between two requests
+    pub min_interval: Duration,
+}
+
+impl RateLimit {
+    /// Create a new `RateLimit` which allows `max` requests in every
+    /// `interval`
+    pub const fn new(max: usize, interval: Duration) -> Self {
+        Self {
+            interval,
+            initial: max,
+            max,
+            refill: max,
+            min_interval: Duration::ZERO,
+        }
+    }
+
+    /// Specifies the minimum time between two requests
+    #[must_use]
+    pub const fn with_min_interval(self, min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            ..self
+        }
+    }
+}
+
+/// The budget of a rate limited endpoint
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RateLimitBudget {
+    /// The number of requests that can be sent without waiting
+    pub remaining: usize,
+    /// The max number of tokens of the bucket
+    pub max: usize,
+    /// The number of requests waiting for a token
+    pub waiting: usize,
+}
+
+#[derive(Debug)]
+struct State {
+    tokens: usize,
+    last_refill: Instant,
+    last_acquire: Option<Instant>,
+}
+
+impl State {
+    fn refill(&mut self, config: &RateLimit, now: Instant) {
+        if config.interval.is_zero() {
+            self.tokens = config.max;
+            self.last_refill = now;
+            return;
+        }
+
+        let n = ((now - self.last_refill).as_nanos() / config.interval.as_nanos()) as u32;
+        if n > 0 {
+            self.tokens = self
+                .tokens
+                .saturating_add(config.refill.saturating_mul(n as usize))
+                .min(config.max);
+            self.last_refill += config.interval * n;
+        }
+    }
+
+    /// Takes a token, or returns the time to wait before the next attempt
+    fn try_take(&mut self, config: &RateLimit, now: Instant) -> Option<Duration> {
+        self.refill(config, now);
+
+        let mut wait = self
+            .last_acquire
+            .map(|last_acquire| (last_acquire + config.min_interval).saturating_duration_since(now))
+            .unwrap_or_default();
+        if self.tokens == 0 {
+            wait = wait.max((self.last_refill + config.interval).saturating_duration_since(now));
+        }
+
+        if !wait.is_zero() {
+            return Some(wait);
+        }
+        self.tokens -= 1;
+        self.last_acquire = Some(now);
+        None
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    config: RateLimit,
+    state: Mutex<State>,
+    // `tokio::sync::Mutex` is fair, so the requests get the tokens in FIFO order
+    queue: tokio::sync::Mutex<()>,
+    waiting: AtomicUsize,
+}
+
+struct WaitingGuard<'a> {
+    waiting: &'a AtomicUsize,
+    method: &'a Method,
+    path: &'a str,
+}
+
+impl<'a> WaitingGuard<'a> {
+    fn new(waiting: &'a AtomicUsize, method: &'a Method, path: &'a str) -> Self {
+        let depth = waiting.fetch_add(1, Ordering::Relaxed) + 1;
+        metrics::set_rate_limit_queue_depth(method, path, depth);
+        Self {
+            waiting,
+            method,
+            path,
+        }
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        let depth = self.waiting.fetch_sub(1, Ordering::Relaxed) - 1;
+        metrics::set_rate_limit_queue_depth(self.method, self.path, depth);
+    }
+}
+
+impl Bucket {
+    fn new(config: RateLimit) -> Self {
+        Self {
+            config,
+            state: Mutex::new(State {
+                tokens: config.initial.min(config.max),
+                last_refill: Instant::now(),
+                last_acquire: None,
+            }),
+            queue: tokio::sync::Mutex::new(()),
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    async fn acquire(&self, method: &Method, path: &str) -> Duration {
+        let start = Instant::now();
+        let _waiting = WaitingGuard::new(&self.waiting, method, path);
+        let _queue = self.queue.lock().await;
+
+        loop {
+            let wait = self.state.lock().try_take(&self.config, Instant::now());
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => {
+                    let waited = start.elapsed();
+                    metrics::record_rate_limit_wait(method, path, waited);
+                    return waited;
+                }
+            }
+        }
+    }
+
+    fn budget(&self) -> RateLimitBudget {
+        let mut state = self.state.lock();
+        state.refill(&self.config, Instant::now());
+        RateLimitBudget {
+            remaining: state.tokens,
+            max: self.config.max,
+            waiting: self.waiting.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Per endpoint rate limiter
+#[derive(Debug, Default, Clone)]
+pub(crate) struct RateLimiter {
+    buckets: HashMap<(Method, String), Arc<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Default limits of the trade endpoints
+    ///
+    /// Reference: <https://open.longportapp.com/en/docs/#rate-limit>
+    pub(crate) fn with_defaults() -> Self {
+        let order_limit = RateLimit::new(30, Duration::from_secs(30))
+            .with_min_interval(Duration::from_millis(20));
+        [Method::POST, Method::PUT, Method::DELETE]
+            .into_iter()
+            .fold(Self::default(), |limiter, method| {
+                limiter.with(method, "/v1/trade/order", Some(order_limit))
+            })
+    }
+
+    /// Returns a new limiter which replaces the limit of the endpoint
+    pub(crate) fn with(
+        &self,
+        method: Method,
+        path: impl Into<String>,
+        limit: Option<RateLimit>,
+    ) -> Self {
+        let mut buckets = self.buckets.clone();
+        let key = (method, path.into());
+        match limit {
+            Some(limit) => buckets.insert(key, Arc::new(Bucket::new(limit))),
+            None => buckets.remove(&key),
+        };
+        Self { buckets }
+    }
+
+    /// Waits for a token of the endpoint, and returns the waited time
+    pub(crate) async fn acquire(&self, method: &Method, path: &str) -> Duration {
+        match self.buckets.get(&(method.clone(), path.to_string())) {
+            Some(bucket) => bucket.acquire(method, path).await,
+            None => Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn budget(&self, method: &Method, path: &str) -> Option<RateLimitBudget> {
+        self.buckets
+            .get(&(method.clone(), path.to_string()))
+            .map(|bucket| bucket.budget())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit() {
+        let limiter = RateLimiter::default().with(
+            Method::POST,
+            "/test",
+            Some(RateLimit::new(2, Duration::from_secs(10))),
+        );
+        let budget = |remaining| RateLimitBudget {
+            remaining,
+            max: 2,
+            waiting: 0,
+        };
+
+        assert_eq!(limiter.budget(&Method::GET, "/test"), None);
+        assert_eq!(limiter.budget(&Method::POST, "/test"), Some(budget(2)));
+
+        assert_eq!(
+            limiter.acquire(&Method::POST, "/test").await,
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.acquire(&Method::POST, "/test").await,
+            Duration::ZERO
+        );
+        assert_eq!(limiter.budget(&Method::POST, "/test"), Some(budget(0)));
+        assert_eq!(
+            limiter.acquire(&Method::POST, "/test").await,
+            Duration::from_secs(10)
+        );
+        assert_eq!(limiter.budget(&Method::POST, "/test"), Some(budget(1)));
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(limiter.budget(&Method::POST, "/test"), Some(budget(2)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_min_interval() {
+        let limiter = RateLimiter::default().with(
+            Method::POST,
+            "/test",
+            Some(
+                RateLimit::new(10, Duration::from_secs(10))
+                    .with_min_interval(Duration::from_millis(20)),
+            ),
+        );
+
+        assert_eq!(
+            limiter.acquire(&Method::POST, "/test").await,
+            Duration::ZERO
+        );
+        assert_eq!(
+            limiter.acquire(&Method::POST, "/test").await,
+            Duration::from_millis(20)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::{self, Display};
+
+use serde_json::Value;
+
+const REDACTED: &str = "***";
+
+/// The secrets, redacted in the bodies of every endpoint
+const SECRET_KEYS: &[&str] = &["token", "access_token", "otp", "session_id"];
+
+/// The account balances and positions, redacted in the bodies of the account
+/// and position endpoints
+const ACCOUNT_KEYS: &[&str] = &[
+    // account balances
+    "total_cash",
+    "max_finance_amount",
+    "remaining_finance_amount",
+    "margin_call",
+    "net_assets",
+    "init_margin",
+    "maintenance_margin",
+    "buy_power",
+    "cash_infos",
+    "frozen_transaction_fees",
+    "withdraw_cash",
+    "available_cash",
+    "frozen_cash",
+    "settling_cash",
+    "balance",
+    "amount",
+    // positions
+    "quantity",
+    "available_quantity",
+    "init_quantity",
+    "cost_price",
+    "holding_units",
+    "current_net_asset_value",
+    "cost_net_asset_value",
+];
+
+/// The order details, redacted in the bodies of the order, execution and
+/// estimation endpoints
+const ORDER_KEYS: &[&str] = &[
+    "price",
+    "quantity",
+    "submitted_price",
+    "submitted_quantity",
+    "executed_price",
+    "executed_quantity",
+    "trigger_price",
+    "last_done",
+    "limit_offset",
+    "trailing_amount",
+    "trailing_percent",
+    "free_amount",
+    "charge_detail",
+    "history",
+    "cash_max_qty",
+    "margin_max_qty",
+];
+
+/// The keys redacted in the bodies of the endpoints whose path starts with
+/// the prefix, e.g. `/v1/asset/account` or `/v1/trade/order/today`, the
+/// bodies of the other endpoints, e.g. the quotes, only have their secrets
+/// redacted
+const PATH_KEYS: &[(&str, &[&str])] = &[("/v1/asset/", ACCOUNT_KEYS), ("/v1/trade/", ORDER_KEYS)];
+
+/// Returns the JSON body of the endpoint with the values of the sensitive keys
+/// replaced, the text is returned as is if it is not a JSON
+pub(crate) fn redact_json(path: &str, text: &str) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut value) => {
+            let keys = PATH_KEYS
+                .iter()
+                .find(|(prefix, _)| path.starts_with(prefix))
+                .map(|(_, keys)| *keys)
+                .unwrap_or_default();
+            redact_value(&mut value, keys);
+            value.to_string()
+        }
+        Err(_) => text.to_string(),
+    }
+}
+
+/// A request or response body to log, the sensitive values are redacted when
+/// it is formatted if `redact` is `true`
+pub(crate) struct LogBody<'a> {
+    path: &'a str,
+    text: &'a str,
+    redact: bool,
+}
+
+impl<'a> LogBody<'a> {
+    pub(crate) fn new(path: &'a str, text: &'a str, redact: bool) -> Self {
+        Self { path, text, redact }
+    }
+}
+
+impl Display for LogBody<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.redact {
+            f.write_str(&redact_json(self.path, self.text))
+        } else {
+            f.write_str(self.text)
+        }
+    }
+}
+
+fn redact_value(value: &mut Value, keys: &[&str]) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if SECRET_KEYS.contains(&key.as_str()) || keys.contains(&key.as_str()) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value, keys);
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| redact_value(value, keys)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn redact(path: &str, text: &str) -> Value {
+        serde_json::from_str(&redact_json(path, text)).unwrap()
+    }
+
+    #[test]
+    fn test_redact_json() {
+        assert_eq!(
+            redact(
+                "/v1/asset/account",
+                r#"{"code":0,"data":{"list":[{"currency":"HKD","total_cash":"1000","cash_infos":[{"available_cash":"10"}]}]}}"#
+            ),
+            json!({"code": 0, "data": {"list": [{"currency": "HKD", "total_cash": "***", "cash_infos": "***"}]}})
+        );
+        assert_eq!(
+            redact(
+                "/v1/trade/order",
+                r#"{"order_id":"1","symbol":"700.HK","submitted_price":"50","quantity":"100"}"#
+            ),
+            json!({"order_id": "1", "symbol": "700.HK", "submitted_price": "***", "quantity": "***"})
+        );
+        assert_eq!(
+            redact(
+                "/v1/trade/execution/today",
+                r#"{"code":0,"data":{"trades":[{"order_id":"1","symbol":"700.HK","price":"50","quantity":"100"}]}}"#
+            ),
+            json!({"code": 0, "data": {"trades": [{"order_id": "1", "symbol": "700.HK", "price": "***", "quantity": "***"}]}})
+        );
+        assert_eq!(
+            redact(
+                "/v1/quote/market_temperature",
+                r#"{"code":0,"data":{"temperature":50,"price":"10"}}"#
+            ),
+            json!({"code": 0, "data": {"temperature": 50, "price": "10"}})
+        );
+        assert_eq!(
+            redact(
+                "/v1/token/refresh",
+                r#"{"code":0,"data":{"token":"secret","expired_at":"2025-01-01T00:00:00Z"}}"#
+            ),
+            json!({"code": 0, "data": {"token": "***", "expired_at": "2025-01-01T00:00:00Z"}})
+        );
+        assert_eq!(redact_json("/v1/asset/account", "not json"), "not json");
+    }
+}
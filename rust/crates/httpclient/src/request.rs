@@ -11,21 +11,18 @@ use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue},
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tracing::Span;
 
 use crate::{
-    HttpClient, HttpClientError, HttpClientResult, is_cn,
+    HttpClient, HttpClientError, HttpClientResult, metrics,
+    redact::LogBody,
+    resolve_region,
     signature::{SignatureParams, signature},
     timestamp::Timestamp,
 };
 
-const HTTP_URL: &str = "https://openapi.longportapp.com";
-const HTTP_URL_CN: &str = "https://openapi.longportapp.cn";
-
 const USER_AGENT: &str = "openapi-sdk";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
-const RETRY_COUNT: usize = 5;
-const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
-const RETRY_FACTOR: f32 = 2.0;
 
 /// A JSON payload
 #[derive(Debug)]
@@ -124,6 +121,7 @@ pub struct RequestBuilder<'a, T, Q, R> {
     headers: HeaderMap,
     body: Option<T>,
     query_params: Option<Q>,
+    idempotent: Option<bool>,
     mark_resp: PhantomData<R>,
 }
 
@@ -136,6 +134,7 @@ impl<'a> RequestBuilder<'a, (), (), ()> {
             headers: Default::default(),
             body: None,
             query_params: None,
+            idempotent: None,
             mark_resp: PhantomData,
         }
     }
@@ -155,6 +154,7 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: Some(body),
             query_params: self.query_params,
+            idempotent: self.idempotent,
             mark_resp: self.mark_resp,
         }
     }
@@ -187,10 +187,24 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: self.body,
             query_params: Some(params),
+            idempotent: self.idempotent,
             mark_resp: self.mark_resp,
         }
     }
 
+    /// Specifies whether the request can be sent more than once without
+    /// changing the result, idempotent requests are retried when they time
+    /// out, fail to connect or are responded with a `5xx` status.
+    ///
+    /// Default: `true` for `GET` requests, otherwise `false`
+    #[must_use]
+    pub fn idempotent(self, idempotent: bool) -> Self {
+        Self {
+            idempotent: Some(idempotent),
+            ..self
+        }
+    }
+
     /// Set the response body type
     #[must_use]
     pub fn response<R2>(self) -> RequestBuilder<'a, T, Q, R2>
@@ -204,6 +218,7 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: self.body,
             query_params: self.query_params,
+            idempotent: self.idempotent,
             mark_resp: PhantomData,
         }
     }
@@ -220,7 +235,11 @@ where
             return url;
         }
 
-        if is_cn().await { HTTP_URL_CN } else { HTTP_URL }
+        let config = &self.client.config;
+        resolve_region(config.region, config.proxy.as_deref())
+            .await
+            .endpoints()
+            .http_url
     }
 
     async fn do_send(&self) -> HttpClientResult<R> {
@@ -235,10 +254,19 @@ where
             .and_then(|value| value.to_str().ok())
             .and_then(|value| value.parse().ok())
             .unwrap_or_else(Timestamp::now);
-        let app_key_value =
-            HeaderValue::from_str(&config.app_key).map_err(|_| HttpClientError::InvalidApiKey)?;
-        let access_token_value = HeaderValue::from_str(&config.access_token)
+        let credentials = config.credentials();
+        let app_key_value = HeaderValue::from_str(&credentials.app_key)
+            .map_err(|_| HttpClientError::InvalidApiKey)?;
+        let access_token_value = HeaderValue::from_str(&credentials.access_token)
             .map_err(|_| HttpClientError::InvalidAccessToken)?;
+        let http_cli = http_cli
+            .as_ref()
+            .map_err(|err| HttpClientError::InvalidProxy(err.clone()))?;
+
+        let waited = config.rate_limiter.acquire(&self.method, &self.path).await;
+        if !waited.is_zero() {
+            tracing::info!(method = %self.method, path = %self.path, waited = ?waited, "rate limited");
+        }
 
         let url = self.http_url().await;
         let mut request_builder = http_cli
@@ -252,10 +280,12 @@ where
             .header("Content-Type", "application/json; charset=utf-8");
 
         // set the request body
+        let mut body_text = None;
         if let Some(body) = &self.body {
             let body = body
                 .to_bytes()
                 .map_err(|err| HttpClientError::SerializeRequestBody(err.to_string()))?;
+            body_text = Some(String::from_utf8_lossy(&body).into_owned());
             request_builder = request_builder.body(body);
         }
 
@@ -270,9 +300,9 @@ where
         // signature the request
         let sign = signature(SignatureParams {
             request: &request,
-            app_key: &config.app_key,
-            access_token: Some(&config.access_token),
-            app_secret: &config.app_secret,
+            app_key: &credentials.app_key,
+            access_token: Some(&credentials.access_token),
+            app_secret: &credentials.app_secret,
             timestamp,
         });
         request.headers_mut().insert(
@@ -280,8 +310,8 @@ where
             HeaderValue::from_maybe_shared(sign).expect("valid signature"),
         );
 
-        if let Some(body) = &self.body {
-            tracing::info!(method = %request.method(), url = %request.url(), body = ?body, "http request");
+        if let Some(body) = &body_text {
+            tracing::info!(method = %request.method(), url = %request.url(), body = %LogBody::new(&self.path, body, config.log_redaction), "http request");
         } else {
             tracing::info!(method = %request.method(), url = %request.url(), "http request");
         }
@@ -289,7 +319,7 @@ where
         let s = Instant::now();
 
         // send request
-        let (status, trace_id, text) = tokio::time::timeout(REQUEST_TIMEOUT, async move {
+        let res = tokio::time::timeout(REQUEST_TIMEOUT, async move {
             let resp = http_cli
                 .execute(request)
                 .await
@@ -308,9 +338,25 @@ where
             Ok::<_, HttpClientError>((status, trace_id, text))
         })
         .await
-        .map_err(|_| HttpClientError::RequestTimeout)??;
+        .map_err(|_| HttpClientError::RequestTimeout)
+        .and_then(std::convert::identity);
+        let duration = s.elapsed();
+        metrics::record_request(
+            &self.method,
+            &self.path,
+            match &res {
+                Ok((status, _, _)) => status.as_str(),
+                Err(HttpClientError::RequestTimeout) => "timeout",
+                Err(_) => "error",
+            },
+            duration,
+        );
+        let (status, trace_id, text) = res?;
+        let span = Span::current();
+        span.record("status", status.as_u16());
+        span.record("trace_id", trace_id.as_str());
 
-        tracing::info!(duration = ?s.elapsed(), body = %text.as_str(), "http response");
+        tracing::info!(duration = ?duration, body = %LogBody::new(&self.path, &text, config.log_redaction), "http response");
 
         let resp = match serde_json::from_str::<OpenApiResponse>(&text) {
             Ok(resp) if resp.code == 0 => resp.data.ok_or(HttpClientError::UnexpectedResponse),
@@ -330,29 +376,66 @@ where
     }
 
     /// Send request and get the response
+    ///
+    /// The request is sent within a `http request` span, the `status` and the
+    /// `trace_id` of the response and the `retry_count` are recorded to it.
+    #[tracing::instrument(
+        name = "http request",
+        skip(self),
+        fields(
+            otel.kind = "client",
+            method = %self.method,
+            path = %self.path,
+            status = tracing::field::Empty,
+            trace_id = tracing::field::Empty,
+            retry_count = 0,
+            otel.status_code = tracing::field::Empty,
+        )
+    )]
     pub async fn send(self) -> HttpClientResult<R> {
-        match self.do_send().await {
-            Ok(resp) => Ok(resp),
-            Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                let mut retry_delay = RETRY_INITIAL_DELAY;
-
-                for _ in 0..RETRY_COUNT {
-                    tokio::time::sleep(retry_delay).await;
-
-                    match self.do_send().await {
-                        Ok(resp) => return Ok(resp),
-                        Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                            retry_delay =
-                                Duration::from_secs_f32(retry_delay.as_secs_f32() * RETRY_FACTOR);
-                            continue;
-                        }
-                        Err(err) => return Err(err),
-                    }
+        let res = self.send_with_retry().await;
+        if res.is_err() {
+            Span::current().record("otel.status_code", "ERROR");
+        }
+        res
+    }
+
+    async fn send_with_retry(&self) -> HttpClientResult<R> {
+        let config = &self.client.config;
+        let idempotent = self.idempotent.unwrap_or(self.method == Method::GET);
+        let mut retry_count = 0;
+
+        loop {
+            if config
+                .circuit_breaker
+                .as_ref()
+                .is_some_and(|circuit_breaker| !circuit_breaker.allow())
+            {
+                return Err(HttpClientError::CircuitOpen);
+            }
+
+            let res = self.do_send().await;
+            let transient = res.as_ref().is_err_and(HttpClientError::is_transient);
+            if let Some(circuit_breaker) = &config.circuit_breaker {
+                if transient {
+                    circuit_breaker.record_failure();
+                } else {
+                    circuit_breaker.record_success();
                 }
+            }
 
-                Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS))
+            let retryable = match &res {
+                Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => true,
+                Err(_) => transient && idempotent,
+                Ok(_) => false,
+            };
+            if !retryable || retry_count >= config.retry_policy.max_retries {
+                return res;
             }
-            Err(err) => Err(err),
+
+            retry_count += 1;
+            tokio::time::sleep(config.retry_policy.delay(retry_count)).await;
+            Span::current().record("retry_count", retry_count);
         }
     }
 }
@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// Retry policy
+///
+/// The delay before the `n`th retry is `initial_delay * factor^(n-1)`, at
+/// most `max_delay`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The max number of retries, `0` disables retrying
+    pub max_retries: usize,
+    /// The delay before the first retry
+    pub initial_delay: Duration,
+    /// The factor to multiply the delay by after every retry
+    pub factor: f32,
+    /// The max delay between two attempts
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new `RetryPolicy` which retries at most `max_retries` times
+    pub const fn new(max_retries: usize) -> Self {
+        Self {
+            max_retries,
+            initial_delay: Duration::from_millis(100),
+            factor: 2.0,
+            max_delay: Duration::from_secs(5),
+        }
+    }
+
+    /// Create a `RetryPolicy` which never retries
+    pub const fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    /// Specifies the delay before the first retry
+    #[must_use]
+    pub const fn with_initial_delay(self, initial_delay: Duration) -> Self {
+        Self {
+            initial_delay,
+            ..self
+        }
+    }
+
+    /// Specifies the factor to multiply the delay by after every retry
+    #[must_use]
+    pub const fn with_factor(self, factor: f32) -> Self {
+        Self { factor, ..self }
+    }
+
+    /// Specifies the max delay between two attempts
+    #[must_use]
+    pub const fn with_max_delay(self, max_delay: Duration) -> Self {
+        Self { max_delay, ..self }
+    }
+
+    /// Returns the delay before the retry, `retry_count` starts from `1`
+    pub fn delay(&self, retry_count: usize) -> Duration {
+        let exp = retry_count.saturating_sub(1).min(i32::MAX as usize) as i32;
+        let delay = self.initial_delay.as_secs_f64() * (self.factor as f64).powi(exp);
+        Duration::try_from_secs_f64(delay)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Circuit breaker policy
+///
+/// The circuit opens after `failure_threshold` consecutive requests fail
+/// because the server is unreachable, e.g. they time out, the connection is
+/// lost or the server responds with a `5xx` status. While it is open, the
+/// requests fail immediately. After `open_duration`, one request is sent to
+/// probe the server, the circuit closes if it succeeds and opens again
+/// otherwise.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CircuitBreakerPolicy {
+    /// The number of consecutive failures to open the circuit
+    pub failure_threshold: usize,
+    /// The time the circuit stays open before probing the server
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(30))
+    }
+}
+
+impl CircuitBreakerPolicy {
+    /// Create a new `CircuitBreakerPolicy`
+    pub const fn new(failure_threshold: usize, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold,
+            open_duration,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        failures: usize,
+    },
+    Open {
+        until: Instant,
+    },
+    /// The probing request is sent, another one is allowed after `until` in
+    /// case it is never completed
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// A circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    /// Create a new `CircuitBreaker`
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    /// Returns `true` if the request can be sent
+    ///
+    /// When the circuit is open and `open_duration` has elapsed, only the
+    /// first caller is allowed to send the probing request.
+    pub fn allow(&self) -> bool {
+        let mut state = self.state.lock();
+        let now = Instant::now();
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } | State::HalfOpen { until } if now >= until => {
+                *state = State::HalfOpen {
+                    until: now + self.policy.open_duration,
+                };
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Returns `true` if the circuit is open
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.state.lock(), State::Closed { .. })
+    }
+
+    /// Records that the server responded to the request
+    pub fn record_success(&self) {
+        *self.state.lock() = State::Closed { failures: 0 };
+    }
+
+    /// Records that the request failed because the server is unreachable
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock();
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            State::Open { .. } | State::HalfOpen { .. } => self.policy.failure_threshold,
+        };
+        if failures >= self.policy.failure_threshold {
+            tracing::warn!(failures, open_duration = ?self.policy.open_duration, "circuit breaker opened");
+            *state = State::Open {
+                until: Instant::now() + self.policy.open_duration,
+            };
+        } else {
+            *state = State::Closed { failures };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::new(5).with_max_delay(Duration::from_millis(500));
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(4), Duration::from_millis(500));
+        assert_eq!(policy.delay(usize::MAX), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new(CircuitBreakerPolicy::new(2, Duration::from_secs(10)));
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert!(!breaker.is_open());
+        breaker.record_failure();
+        assert!(breaker.is_open());
+        assert!(!breaker.allow());
+
+        // probe after `open_duration`, only one request is allowed
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(breaker.allow());
+        assert!(!breaker.allow());
+        breaker.record_failure();
+        assert!(!breaker.allow());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert!(!breaker.is_open());
+        assert!(breaker.allow());
+    }
+}
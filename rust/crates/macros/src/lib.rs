@@ -0,0 +1,24 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{ItemFn, parse_macro_input};
+
+/// Runs the body of a `QuoteContext` or `TradeContext` method with
+/// `self.call`, which applies the call options and creates the span of the
+/// call named after the method
+#[proc_macro_attribute]
+pub fn call(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = parse_macro_input!(input as ItemFn);
+    let method = sig.ident.to_string();
+    quote! {
+        #(#attrs)*
+        #vis #sig {
+            self.call(#method, async move #block).await
+        }
+    }
+    .into()
+}
@@ -1,8 +1,13 @@
+#[cfg(unix)]
+use std::path::Path;
 use std::{
     collections::HashMap,
     fmt::Debug,
     str::FromStr,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
@@ -15,17 +20,22 @@ use longport_proto::control::{AuthRequest, AuthResponse, ReconnectRequest, Recon
 use num_enum::IntoPrimitive;
 use prost::Message as _;
 use tokio::{
+    io::{AsyncRead, AsyncWrite},
     net::TcpStream,
     sync::{mpsc, oneshot},
 };
 use tokio_tungstenite::{
     MaybeTlsStream, WebSocketStream,
-    tungstenite::{Message, client::IntoClientRequest, http::Uri},
+    tungstenite::{
+        Message, client::IntoClientRequest, error::UrlError, handshake::client::Request, http::Uri,
+    },
 };
+use tracing::Span;
 use url::Url;
 
 use crate::{
     WsClientError, WsClientResult, WsCloseReason, WsEvent, WsResponseErrorDetail, codec::Packet,
+    metrics, proxy::Proxy,
 };
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -63,11 +73,34 @@ pub enum Platform {
 
 enum Command {
     Request {
+        request_id: u32,
         command_code: u8,
         timeout_millis: u16,
         body: Vec<u8>,
+        span: Span,
         reply_tx: oneshot::Sender<WsClientResult<Vec<u8>>>,
     },
+    /// The caller stopped waiting for the response, e.g. the call is timed out
+    /// or cancelled
+    Cancel { request_id: u32 },
+}
+
+/// Sends [`Command::Cancel`] if the request is dropped before its response is
+/// received
+struct CancelGuard<'a> {
+    command_tx: &'a mpsc::UnboundedSender<Command>,
+    request_id: u32,
+    completed: bool,
+}
+
+impl Drop for CancelGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            let _ = self.command_tx.send(Command::Cancel {
+                request_id: self.request_id,
+            });
+        }
+    }
 }
 
 /// Rate limiter config
@@ -94,24 +127,30 @@ impl From<RateLimit> for RateLimiter {
     }
 }
 
-struct Context<'a> {
-    request_id: u32,
-    inflight_requests: HashMap<u32, oneshot::Sender<WsClientResult<Vec<u8>>>>,
-    sink: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-    stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+struct InflightRequest {
+    span: Span,
+    reply_tx: oneshot::Sender<WsClientResult<Vec<u8>>>,
+}
+
+struct Context<'a, S> {
+    inflight_requests: HashMap<u32, InflightRequest>,
+    sink: SplitSink<WebSocketStream<S>, Message>,
+    stream: SplitStream<WebSocketStream<S>>,
     command_rx: &'a mut mpsc::UnboundedReceiver<Command>,
     event_sender: &'a mut mpsc::UnboundedSender<WsEvent>,
 }
 
-impl<'a> Context<'a> {
+impl<'a, S> Context<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     fn new(
-        conn: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        conn: WebSocketStream<S>,
         command_rx: &'a mut mpsc::UnboundedReceiver<Command>,
         event_sender: &'a mut mpsc::UnboundedSender<WsEvent>,
     ) -> Self {
         let (sink, stream) = conn.split();
         Context {
-            request_id: 0,
             inflight_requests: Default::default(),
             sink,
             stream,
@@ -120,12 +159,6 @@ impl<'a> Context<'a> {
         }
     }
 
-    #[inline]
-    fn get_request_id(&mut self) -> u32 {
-        self.request_id += 1;
-        self.request_id
-    }
-
     fn send_event(&mut self, event: WsEvent) {
         let _ = self.event_sender.send(event);
     }
@@ -167,12 +200,14 @@ impl<'a> Context<'a> {
     async fn handle_command(&mut self, command: Command) -> WsClientResult<()> {
         match command {
             Command::Request {
+                request_id,
                 command_code,
                 timeout_millis: timeout,
                 body,
+                span,
                 reply_tx,
             } => {
-                let request_id = self.get_request_id();
+                span.record("request_id", request_id);
                 let msg = Message::Binary(
                     Packet::Request {
                         command_code,
@@ -184,10 +219,19 @@ impl<'a> Context<'a> {
                     .encode()
                     .into(),
                 );
-                self.inflight_requests.insert(request_id, reply_tx);
+                self.inflight_requests
+                    .insert(request_id, InflightRequest { span, reply_tx });
+                metrics::add_inflight_requests(1.0);
                 self.sink.send(msg).await?;
                 Ok(())
             }
+            Command::Cancel { request_id } => {
+                if self.inflight_requests.remove(&request_id).is_some() {
+                    tracing::debug!(request_id, "remove abandoned request");
+                    metrics::add_inflight_requests(-1.0);
+                }
+                Ok(())
+            }
         }
     }
 
@@ -203,9 +247,13 @@ impl<'a> Context<'a> {
                     body,
                     ..
                 } => {
-                    if let Some(sender) = self.inflight_requests.remove(&request_id) {
+                    if let Some(InflightRequest { span, reply_tx }) =
+                        self.inflight_requests.remove(&request_id)
+                    {
+                        metrics::add_inflight_requests(-1.0);
+                        span.record("status", status);
                         if status == 0 {
-                            let _ = sender.send(Ok(body));
+                            let _ = reply_tx.send(Ok(body));
                         } else {
                             let detail = longport_proto::Error::decode(&*body).ok().map(
                                 |longport_proto::Error { code, msg }| WsResponseErrorDetail {
@@ -214,7 +262,7 @@ impl<'a> Context<'a> {
                                 },
                             );
                             let _ =
-                                sender.send(Err(WsClientError::ResponseError { status, detail }));
+                                reply_tx.send(Err(WsClientError::ResponseError { status, detail }));
                         }
                     }
                 }
@@ -257,9 +305,36 @@ impl WsSession {
     }
 }
 
+/// Options of [`WsClient::open_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct WsOpenOptions {
+    proxy: Option<String>,
+}
+
+impl WsOpenOptions {
+    /// Create a new `WsOpenOptions`
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the proxy server to establish the connection through,
+    /// supported schemes are `http` (`CONNECT` tunnel), `socks5` and
+    /// `socks5h`.
+    ///
+    /// Default: `None`
+    #[must_use]
+    pub fn proxy(self, proxy: impl Into<String>) -> Self {
+        Self {
+            proxy: Some(proxy.into()),
+        }
+    }
+}
+
 /// LongPort Websocket client
 pub struct WsClient {
     command_tx: mpsc::UnboundedSender<Command>,
+    request_id: Arc<AtomicU32>,
     rate_limit: Arc<HashMap<u8, RateLimiter>>,
 }
 
@@ -273,18 +348,80 @@ impl WsClient {
         event_sender: mpsc::UnboundedSender<WsEvent>,
         rate_limit: Vec<(u8, RateLimit)>,
     ) -> WsClientResult<Self> {
+        Self::open_with_options(
+            request,
+            version,
+            codec,
+            platform,
+            event_sender,
+            rate_limit,
+            WsOpenOptions::default(),
+        )
+        .await
+    }
+
+    /// Connect to `url` with the options and returns a `WsClient` object
+    pub async fn open_with_options(
+        request: impl IntoClientRequest,
+        version: ProtocolVersion,
+        codec: CodecType,
+        platform: Platform,
+        event_sender: mpsc::UnboundedSender<WsEvent>,
+        rate_limit: Vec<(u8, RateLimit)>,
+        options: WsOpenOptions,
+    ) -> WsClientResult<Self> {
+        let conn = do_connect(request, version, codec, platform, options.proxy.as_deref()).await?;
+        Ok(Self::new(conn, event_sender, rate_limit))
+    }
+
+    /// Connect to the server listening on the unix domain socket `path` and
+    /// returns a `WsClient` object
+    ///
+    /// The `request` is only used for the websocket handshake, the host of the
+    /// url is ignored.
+    #[cfg(unix)]
+    pub async fn open_unix(
+        path: impl AsRef<Path>,
+        request: impl IntoClientRequest,
+        version: ProtocolVersion,
+        codec: CodecType,
+        platform: Platform,
+        event_sender: mpsc::UnboundedSender<WsEvent>,
+        rate_limit: Vec<(u8, RateLimit)>,
+    ) -> WsClientResult<Self> {
+        let request = create_request(request, version, codec, platform)?;
+        let conn = tokio::time::timeout(CONNECT_TIMEOUT, async move {
+            let stream = tokio::net::UnixStream::connect(path)
+                .await
+                .map_err(tokio_tungstenite::tungstenite::Error::Io)?;
+            let (conn, _) = tokio_tungstenite::client_async(request, stream).await?;
+            Ok::<_, WsClientError>(conn)
+        })
+        .await
+        .map_err(|_| WsClientError::ConnectTimeout)??;
+        Ok(Self::new(conn, event_sender, rate_limit))
+    }
+
+    fn new<S>(
+        conn: WebSocketStream<S>,
+        event_sender: mpsc::UnboundedSender<WsEvent>,
+        rate_limit: Vec<(u8, RateLimit)>,
+    ) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
         let (command_tx, command_rx) = mpsc::unbounded_channel();
-        let conn = do_connect(request, version, codec, platform).await?;
         tokio::spawn(client_loop(conn, command_rx, event_sender));
-        Ok(Self {
+        Self {
             command_tx,
+            request_id: Default::default(),
             rate_limit: Arc::new(
                 rate_limit
                     .into_iter()
                     .map(|(cmd, rate_limit)| (cmd, rate_limit.into()))
                     .collect(),
             ),
-        })
+        }
     }
 
     /// Set the rate limit
@@ -354,6 +491,22 @@ impl WsClient {
     }
 
     /// Send a raw request
+    ///
+    /// The request is sent within a `ws request` span, the `request_id` of
+    /// the packet and the `status` of the response are recorded to it.
+    ///
+    /// The request fails with [`WsClientError::RequestTimeout`] if no response
+    /// is received within `timeout`, default `30s`.
+    #[tracing::instrument(
+        name = "ws request",
+        skip(self, timeout, body),
+        fields(
+            otel.kind = "client",
+            request_id = tracing::field::Empty,
+            status = tracing::field::Empty,
+            otel.status_code = tracing::field::Empty,
+        )
+    )]
     pub async fn request_raw(
         &self,
         command_code: u8,
@@ -361,25 +514,46 @@ impl WsClient {
         body: Vec<u8>,
     ) -> WsClientResult<Vec<u8>> {
         if let Some(rate_limit) = self.rate_limit.get(&command_code) {
+            let start = Instant::now();
             rate_limit.acquire_one().await;
+            metrics::record_rate_limit_wait(command_code, start.elapsed());
         }
 
+        let start = Instant::now();
         let (reply_tx, reply_rx) = oneshot::channel();
+        let request_id = self
+            .request_id
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1);
         self.command_tx
             .send(Command::Request {
+                request_id,
                 command_code,
                 timeout_millis: timeout.unwrap_or(REQUEST_TIMEOUT).as_millis().min(60000) as u16,
                 body,
+                span: Span::current(),
                 reply_tx,
             })
             .map_err(|_| WsClientError::ClientClosed)?;
-        let resp = tokio::time::timeout(
-            REQUEST_TIMEOUT,
+        let mut guard = CancelGuard {
+            command_tx: &self.command_tx,
+            request_id,
+            completed: false,
+        };
+        let res = tokio::time::timeout(
+            timeout.unwrap_or(REQUEST_TIMEOUT),
             reply_rx.map_err(|_| WsClientError::ClientClosed),
         )
         .map_err(|_| WsClientError::RequestTimeout)
-        .await???;
-        Ok(resp)
+        .await
+        .and_then(std::convert::identity);
+        guard.completed = res.is_ok();
+        let res = res.and_then(std::convert::identity);
+        metrics::record_request(command_code, &res, start.elapsed());
+        if res.is_err() {
+            Span::current().record("otel.status_code", "ERROR");
+        }
+        res
     }
 
     /// Send a request `T` to get a response `R`
@@ -393,22 +567,33 @@ impl WsClient {
         T: prost::Message + Debug,
         R: prost::Message + Default + Debug,
     {
-        tracing::info!(message = ?req, "ws request");
+        // the control commands carry the token and the session id, they are
+        // logged without the message
+        let is_control = matches!(command_code, COMMAND_CODE_AUTH | COMMAND_CODE_RECONNECT);
+        if is_control {
+            tracing::info!(command_code, "ws request");
+        } else {
+            tracing::info!(message = ?req, "ws request");
+        }
         let resp = self
             .request_raw(command_code, timeout, req.encode_to_vec())
             .await?;
         let resp = R::decode(&*resp)?;
-        tracing::info!(message = ?resp, "ws response");
+        if is_control {
+            tracing::info!(command_code, "ws response");
+        } else {
+            tracing::info!(message = ?resp, "ws response");
+        }
         Ok(resp)
     }
 }
 
-async fn do_connect(
+fn create_request(
     request: impl IntoClientRequest,
     version: ProtocolVersion,
     codec: CodecType,
     platform: Platform,
-) -> WsClientResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+) -> WsClientResult<Request> {
     let mut request = request.into_client_request()?;
     let mut url_obj = Url::parse(&request.uri().to_string())?;
     url_obj.query_pairs_mut().extend_pairs(&[
@@ -417,11 +602,44 @@ async fn do_connect(
         ("platform", i32::from(platform).to_string()),
     ]);
     *request.uri_mut() = Uri::from_str(url_obj.as_ref()).expect("valid url");
+    Ok(request)
+}
 
-    let conn = match tokio::time::timeout(
-        CONNECT_TIMEOUT,
-        tokio_tungstenite::connect_async(request).map_err(WsClientError::from),
-    )
+async fn do_connect(
+    request: impl IntoClientRequest,
+    version: ProtocolVersion,
+    codec: CodecType,
+    platform: Platform,
+    proxy: Option<&str>,
+) -> WsClientResult<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+    let request = create_request(request, version, codec, platform)?;
+    let proxy = proxy.map(Proxy::parse).transpose()?;
+
+    let conn = match tokio::time::timeout(CONNECT_TIMEOUT, async move {
+        match proxy {
+            Some(proxy) => {
+                let uri = request.uri();
+                let host = uri
+                    .host()
+                    .ok_or(tokio_tungstenite::tungstenite::Error::Url(
+                        UrlError::NoHostName,
+                    ))?;
+                let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+                    Some("wss") => 443,
+                    _ => 80,
+                });
+                let stream = proxy
+                    .connect(host.trim_start_matches('[').trim_end_matches(']'), port)
+                    .await?;
+                tokio_tungstenite::client_async_tls_with_config(request, stream, None, None)
+                    .await
+                    .map_err(WsClientError::from)
+            }
+            None => tokio_tungstenite::connect_async(request)
+                .await
+                .map_err(WsClientError::from),
+        }
+    })
     .map_err(|_| WsClientError::ConnectTimeout)
     .await
     .and_then(std::convert::identity)
@@ -433,11 +651,13 @@ async fn do_connect(
     Ok(conn)
 }
 
-async fn client_loop(
-    conn: WebSocketStream<MaybeTlsStream<TcpStream>>,
+async fn client_loop<S>(
+    conn: WebSocketStream<S>,
     mut command_tx: mpsc::UnboundedReceiver<Command>,
     mut event_sender: mpsc::UnboundedSender<WsEvent>,
-) {
+) where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
     let mut ctx = Context::new(conn, &mut command_tx, &mut event_sender);
 
     let res = ctx.process_loop().await;
@@ -448,7 +668,8 @@ async fn client_loop(
         }
     };
 
-    for sender in ctx.inflight_requests.into_values() {
-        let _ = sender.send(Err(WsClientError::Cancelled));
+    metrics::add_inflight_requests(-(ctx.inflight_requests.len() as f64));
+    for InflightRequest { reply_tx, .. } in ctx.inflight_requests.into_values() {
+        let _ = reply_tx.send(Err(WsClientError::Cancelled));
     }
 }
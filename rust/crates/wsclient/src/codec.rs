@@ -99,8 +99,58 @@ impl Packet {
 
                 data
             }
-            Packet::Response { .. } => unimplemented!(),
-            Packet::Push { .. } => unimplemented!(),
+            Packet::Response {
+                command_code,
+                request_id,
+                status,
+                body,
+                signature,
+            } => {
+                let header = PacketHeader {
+                    ty: PACKAGE_TYPE_RESPONSE,
+                    verify: signature.is_some(),
+                    gzip: false,
+                }
+                .encode();
+                let mut data = vec![header, *command_code];
+
+                let _ = data.write_u32::<BE>(*request_id);
+                let _ = data.write_u8(*status);
+                let _ = data.write_u24::<BE>(body.len() as u32);
+
+                data.extend(body);
+
+                if let Some(signature) = signature {
+                    data.extend_from_slice(&signature.nonce);
+                    data.extend_from_slice(&signature.signature);
+                }
+
+                data
+            }
+            Packet::Push {
+                command_code,
+                body,
+                signature,
+            } => {
+                let header = PacketHeader {
+                    ty: PACKAGE_TYPE_PUSH,
+                    verify: signature.is_some(),
+                    gzip: false,
+                }
+                .encode();
+                let mut data = vec![header, *command_code];
+
+                let _ = data.write_u24::<BE>(body.len() as u32);
+
+                data.extend(body);
+
+                if let Some(signature) = signature {
+                    data.extend_from_slice(&signature.nonce);
+                    data.extend_from_slice(&signature.signature);
+                }
+
+                data
+            }
         }
     }
 
@@ -254,3 +304,64 @@ fn parse_push(header: &PacketHeader, data: &[u8]) -> std::io::Result<Packet> {
         signature,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let data = Packet::Request {
+            command_code: 6,
+            request_id: 42,
+            timeout_millis: 3000,
+            body: vec![1, 2, 3],
+            signature: None,
+        }
+        .encode();
+        assert!(matches!(
+            Packet::decode(&data).unwrap(),
+            Packet::Request {
+                command_code: 6,
+                request_id: 42,
+                timeout_millis: 3000,
+                body,
+                signature: None,
+            } if body == [1, 2, 3]
+        ));
+
+        let data = Packet::Response {
+            command_code: 6,
+            request_id: 42,
+            status: 3,
+            body: vec![4, 5],
+            signature: None,
+        }
+        .encode();
+        assert!(matches!(
+            Packet::decode(&data).unwrap(),
+            Packet::Response {
+                command_code: 6,
+                request_id: 42,
+                status: 3,
+                body,
+                signature: None,
+            } if body == [4, 5]
+        ));
+
+        let data = Packet::Push {
+            command_code: 101,
+            body: vec![6],
+            signature: None,
+        }
+        .encode();
+        assert!(matches!(
+            Packet::decode(&data).unwrap(),
+            Packet::Push {
+                command_code: 101,
+                body,
+                signature: None,
+            } if body == [6]
+        ));
+    }
+}
@@ -67,6 +67,14 @@ pub enum WsClientError {
     #[error(transparent)]
     InvalidUrl(#[from] url::ParseError),
 
+    /// Invalid proxy url
+    #[error("invalid proxy: {0}")]
+    InvalidProxy(String),
+
+    /// Failed to connect through the proxy server
+    #[error("proxy error: {0}")]
+    Proxy(String),
+
     /// Websocket error
     #[error(transparent)]
     Websocket(#[from] tokio_tungstenite::tungstenite::Error),
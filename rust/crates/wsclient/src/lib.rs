@@ -10,7 +10,13 @@ mod client;
 mod codec;
 mod error;
 mod event;
+pub mod metrics;
+mod proxy;
+mod server;
 
-pub use client::{CodecType, Platform, ProtocolVersion, RateLimit, WsClient, WsSession};
+pub use client::{
+    CodecType, Platform, ProtocolVersion, RateLimit, WsClient, WsOpenOptions, WsSession,
+};
 pub use error::{WsClientError, WsClientResult, WsCloseReason, WsResponseErrorDetail};
 pub use event::WsEvent;
+pub use server::{WsRequest, WsServerConn};
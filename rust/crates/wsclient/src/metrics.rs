@@ -0,0 +1,59 @@
+//! Metrics of the Websocket client
+//!
+//! The metrics are recorded through the [`metrics`](https://docs.rs/metrics)
+//! facade, they are no-op until a recorder is installed.
+
+use std::time::Duration;
+
+use metrics::{Unit, describe_gauge, describe_histogram, gauge, histogram};
+
+use crate::WsClientError;
+
+/// The latency of Websocket requests, labeled by `command` code and `status`
+pub const WS_REQUEST_DURATION_SECONDS: &str = "longport_ws_request_duration_seconds";
+/// The number of Websocket requests waiting for a response
+pub const WS_INFLIGHT_REQUESTS: &str = "longport_ws_inflight_requests";
+/// The time spent waiting for the client-side rate limiter, labeled by
+/// `command` code
+pub const WS_RATE_LIMIT_WAIT_SECONDS: &str = "longport_ws_rate_limit_wait_seconds";
+
+/// Register the descriptions of the metrics with the installed recorder
+pub fn describe_metrics() {
+    describe_histogram!(
+        WS_REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "The latency of Websocket requests"
+    );
+    describe_gauge!(
+        WS_INFLIGHT_REQUESTS,
+        "The number of Websocket requests waiting for a response"
+    );
+    describe_histogram!(
+        WS_RATE_LIMIT_WAIT_SECONDS,
+        Unit::Seconds,
+        "The time spent waiting for the client-side rate limiter"
+    );
+}
+
+pub(crate) fn record_request<T>(
+    command_code: u8,
+    res: &Result<T, WsClientError>,
+    duration: Duration,
+) {
+    let status = match res {
+        Ok(_) => "ok",
+        Err(WsClientError::RequestTimeout) => "timeout",
+        Err(WsClientError::ResponseError { .. }) => "response_error",
+        Err(_) => "error",
+    };
+    histogram!(WS_REQUEST_DURATION_SECONDS, "command" => command_code.to_string(), "status" => status)
+        .record(duration);
+}
+
+pub(crate) fn record_rate_limit_wait(command_code: u8, waited: Duration) {
+    histogram!(WS_RATE_LIMIT_WAIT_SECONDS, "command" => command_code.to_string()).record(waited);
+}
+
+pub(crate) fn add_inflight_requests(n: f64) {
+    gauge!(WS_INFLIGHT_REQUESTS).increment(n);
+}
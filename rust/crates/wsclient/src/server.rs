@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::mpsc,
+};
+use tokio_tungstenite::{WebSocketStream, tungstenite::Message};
+
+use crate::{WsClientError, WsClientResult, codec::Packet};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A request received from a websocket client
+#[derive(Debug)]
+pub struct WsRequest {
+    /// Command code
+    pub command_code: u8,
+    /// Request id
+    pub request_id: u32,
+    /// Request timeout in milliseconds
+    pub timeout_millis: u16,
+    /// Request body
+    pub body: Vec<u8>,
+}
+
+/// The server side of a LongPort websocket connection
+///
+/// It speaks the same packet format as [`WsClient`](crate::WsClient), so a
+/// `WsClient` can be connected to it.
+#[derive(Debug, Clone)]
+pub struct WsServerConn {
+    packet_tx: mpsc::UnboundedSender<Packet>,
+}
+
+impl WsServerConn {
+    /// Accept a websocket connection from `stream`
+    ///
+    /// All requests received from the client are sent to `request_tx`, the
+    /// sender is dropped when the connection is closed.
+    pub async fn accept<S>(
+        stream: S,
+        request_tx: mpsc::UnboundedSender<WsRequest>,
+    ) -> WsClientResult<Self>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let conn = tokio::time::timeout(HANDSHAKE_TIMEOUT, tokio_tungstenite::accept_async(stream))
+            .await
+            .map_err(|_| WsClientError::ConnectTimeout)??;
+        let (packet_tx, packet_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(err) = server_loop(conn, packet_rx, request_tx).await {
+                tracing::debug!(error = %err, "websocket server connection closed");
+            }
+        });
+        Ok(Self { packet_tx })
+    }
+
+    /// Send a response for the request `request_id`
+    ///
+    /// A non-zero `status` indicates that the request failed, in which case
+    /// `body` should be an encoded `longport_proto::Error`.
+    pub fn respond(
+        &self,
+        command_code: u8,
+        request_id: u32,
+        status: u8,
+        body: Vec<u8>,
+    ) -> WsClientResult<()> {
+        self.packet_tx
+            .send(Packet::Response {
+                command_code,
+                request_id,
+                status,
+                body,
+                signature: None,
+            })
+            .map_err(|_| WsClientError::ClientClosed)
+    }
+
+    /// Send a push message
+    pub fn push(&self, command_code: u8, body: Vec<u8>) -> WsClientResult<()> {
+        self.packet_tx
+            .send(Packet::Push {
+                command_code,
+                body,
+                signature: None,
+            })
+            .map_err(|_| WsClientError::ClientClosed)
+    }
+
+    /// Returns `true` if the connection is closed
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.packet_tx.is_closed()
+    }
+}
+
+async fn server_loop<S>(
+    conn: WebSocketStream<S>,
+    mut packet_rx: mpsc::UnboundedReceiver<Packet>,
+    request_tx: mpsc::UnboundedSender<WsRequest>,
+) -> WsClientResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (mut sink, mut stream) = conn.split();
+    let mut ping_interval = tokio::time::interval(PING_INTERVAL);
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                match item.transpose()? {
+                    Some(Message::Binary(data)) => match Packet::decode(&data)? {
+                        Packet::Request {
+                            command_code,
+                            request_id,
+                            timeout_millis,
+                            body,
+                            ..
+                        } => {
+                            let _ = request_tx.send(WsRequest {
+                                command_code,
+                                request_id,
+                                timeout_millis,
+                                body,
+                            });
+                        }
+                        _ => return Err(WsClientError::UnexpectedResponse),
+                    },
+                    Some(Message::Ping(data)) => sink.send(Message::Pong(data)).await?,
+                    Some(Message::Pong(_)) => {}
+                    Some(Message::Close(_)) | None => return Ok(()),
+                    Some(_) => return Err(WsClientError::UnexpectedResponse),
+                }
+            }
+            item = packet_rx.recv() => {
+                match item {
+                    Some(packet) => sink.send(Message::Binary(packet.encode().into())).await?,
+                    None => {
+                        let _ = sink.close().await;
+                        return Ok(());
+                    }
+                }
+            }
+            _ = ping_interval.tick() => sink.send(Message::Ping(Default::default())).await?,
+        }
+    }
+}
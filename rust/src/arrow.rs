@@ -0,0 +1,503 @@
+//! Apache Arrow and Parquet export of the market data
+//!
+//! The rows of [`ArrowRecord`] types are converted to an Arrow
+//! [`RecordBatch`] with one column per field:
+//!
+//! - The decimals are `Decimal128(38, 9)`, the values with more than 9 decimal
+//!   places are rounded
+//! - The timestamps are `Timestamp(Nanosecond, "UTC")`
+//! - The dates are `Date32`
+//! - The enums are strings of their `Display` form, which is the same as their
+//!   serde form
+//! - The pre-market, post-market and overnight quotes of [`SecurityQuote`]
+//!   are flattened to the `pre_market_*`, `post_market_*` and `overnight_*`
+//!   columns
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use longport::{
+//!     Config,
+//!     arrow::ArrowRecord,
+//!     quote::{AdjustType, Candlestick, Period, QuoteContext, TradeSessions},
+//! };
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let config = Arc::new(Config::from_env()?);
+//! let (ctx, _) = QuoteContext::try_new(config).await?;
+//!
+//! let candlesticks = ctx
+//!     .candlesticks(
+//!         "700.HK",
+//!         Period::Day,
+//!         1000,
+//!         AdjustType::NoAdjust,
+//!         TradeSessions::Intraday,
+//!     )
+//!     .await?;
+//! let batch = Candlestick::to_record_batch(&candlesticks)?;
+//! println!("{} rows", batch.num_rows());
+//! longport::arrow::write_parquet("700.HK.parquet", &candlesticks)?;
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! # });
+//! ```
+
+use std::{fs::File, io::Write, path::Path, sync::Arc};
+
+pub use arrow_array::RecordBatch;
+use arrow_array::{
+    ArrayRef, Date32Array, Decimal128Array, Int64Array, StringArray, TimestampNanosecondArray,
+};
+pub use arrow_schema::SchemaRef;
+use arrow_schema::{Field, Schema};
+use parquet::arrow::ArrowWriter;
+use rust_decimal::Decimal;
+use time::{Date, OffsetDateTime};
+
+use crate::{
+    Error, Result,
+    quote::{Candlestick, IntradayLine, PrePostQuote, SecurityQuote, Trade, TradeStatus},
+    trade::{Execution, Order},
+};
+
+/// The precision of the decimal columns
+pub const DECIMAL_PRECISION: u8 = 38;
+/// The scale of the decimal columns
+pub const DECIMAL_SCALE: i8 = 9;
+
+/// A type which can be converted to the rows of an Arrow [`RecordBatch`]
+pub trait ArrowRecord: Sized {
+    /// Returns the schema of the record batch
+    fn schema() -> SchemaRef {
+        Self::to_record_batch(&[]).expect("valid schema").schema()
+    }
+
+    /// Converts the rows to a record batch
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch>;
+}
+
+/// The columns of a record batch
+#[derive(Default)]
+struct Columns {
+    fields: Vec<Field>,
+    arrays: Vec<ArrayRef>,
+}
+
+impl Columns {
+    fn push(mut self, name: &str, nullable: bool, array: ArrayRef) -> Self {
+        self.fields
+            .push(Field::new(name, array.data_type().clone(), nullable));
+        self.arrays.push(array);
+        self
+    }
+
+    fn decimal(self, name: &str, values: impl IntoIterator<Item = Decimal>) -> Self {
+        self.decimal_array(name, false, values.into_iter().map(Some))
+    }
+
+    fn decimal_opt(self, name: &str, values: impl IntoIterator<Item = Option<Decimal>>) -> Self {
+        self.decimal_array(name, true, values)
+    }
+
+    fn decimal_array(
+        self,
+        name: &str,
+        nullable: bool,
+        values: impl IntoIterator<Item = Option<Decimal>>,
+    ) -> Self {
+        let array = values
+            .into_iter()
+            .map(|value| value.map(decimal_value))
+            .collect::<Decimal128Array>()
+            .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)
+            .expect("valid decimal precision and scale");
+        self.push(name, nullable, Arc::new(array))
+    }
+
+    fn timestamp(self, name: &str, values: impl IntoIterator<Item = OffsetDateTime>) -> Self {
+        self.timestamp_array(name, false, values.into_iter().map(Some))
+    }
+
+    fn timestamp_opt(
+        self,
+        name: &str,
+        values: impl IntoIterator<Item = Option<OffsetDateTime>>,
+    ) -> Self {
+        self.timestamp_array(name, true, values)
+    }
+
+    fn timestamp_array(
+        self,
+        name: &str,
+        nullable: bool,
+        values: impl IntoIterator<Item = Option<OffsetDateTime>>,
+    ) -> Self {
+        let array = values
+            .into_iter()
+            .map(|value| value.map(|value| value.unix_timestamp_nanos() as i64))
+            .collect::<TimestampNanosecondArray>()
+            .with_timezone("UTC");
+        self.push(name, nullable, Arc::new(array))
+    }
+
+    fn date_opt(self, name: &str, values: impl IntoIterator<Item = Option<Date>>) -> Self {
+        let array = values
+            .into_iter()
+            .map(|value| value.map(|value| value.to_julian_day() - UNIX_EPOCH_JULIAN_DAY))
+            .collect::<Date32Array>();
+        self.push(name, true, Arc::new(array))
+    }
+
+    fn int64(self, name: &str, values: impl IntoIterator<Item = i64>) -> Self {
+        let array = values.into_iter().collect::<Int64Array>();
+        self.push(name, false, Arc::new(array))
+    }
+
+    fn int64_opt(self, name: &str, values: impl IntoIterator<Item = Option<i64>>) -> Self {
+        let array = values.into_iter().collect::<Int64Array>();
+        self.push(name, true, Arc::new(array))
+    }
+
+    fn string<T: AsRef<str>>(self, name: &str, values: impl IntoIterator<Item = T>) -> Self {
+        let array = values
+            .into_iter()
+            .map(|value| Some(value.as_ref().to_string()))
+            .collect::<StringArray>();
+        self.push(name, false, Arc::new(array))
+    }
+
+    fn string_opt<T: AsRef<str>>(
+        self,
+        name: &str,
+        values: impl IntoIterator<Item = Option<T>>,
+    ) -> Self {
+        let array = values
+            .into_iter()
+            .map(|value| value.map(|value| value.as_ref().to_string()))
+            .collect::<StringArray>();
+        self.push(name, true, Arc::new(array))
+    }
+
+    fn finish(self) -> Result<RecordBatch> {
+        RecordBatch::try_new(Arc::new(Schema::new(self.fields)), self.arrays)
+            .map_err(|err| Error::Arrow(err.to_string()))
+    }
+}
+
+const UNIX_EPOCH_JULIAN_DAY: i32 = 2440588;
+
+/// Returns the value of a decimal with [`DECIMAL_SCALE`]
+///
+/// The mantissa of a `Decimal` is less than 2^96, so it never overflows
+/// [`DECIMAL_PRECISION`] digits.
+fn decimal_value(value: Decimal) -> i128 {
+    let value = value.round_dp(DECIMAL_SCALE as u32);
+    value.mantissa() * 10i128.pow(DECIMAL_SCALE as u32 - value.scale())
+}
+
+/// Returns the serde form of a trade status, the protobuf enum has no
+/// `Display`
+fn trade_status_name(status: TradeStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+impl ArrowRecord for Candlestick {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Columns::default()
+            .timestamp("timestamp", rows.iter().map(|row| row.timestamp))
+            .decimal("open", rows.iter().map(|row| row.open))
+            .decimal("high", rows.iter().map(|row| row.high))
+            .decimal("low", rows.iter().map(|row| row.low))
+            .decimal("close", rows.iter().map(|row| row.close))
+            .int64("volume", rows.iter().map(|row| row.volume))
+            .decimal("turnover", rows.iter().map(|row| row.turnover))
+            .string(
+                "trade_session",
+                rows.iter().map(|row| row.trade_session.to_string()),
+            )
+            .finish()
+    }
+}
+
+impl ArrowRecord for Trade {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Columns::default()
+            .timestamp("timestamp", rows.iter().map(|row| row.timestamp))
+            .decimal("price", rows.iter().map(|row| row.price))
+            .int64("volume", rows.iter().map(|row| row.volume))
+            .string("trade_type", rows.iter().map(|row| &row.trade_type))
+            .string(
+                "direction",
+                rows.iter().map(|row| row.direction.to_string()),
+            )
+            .string(
+                "trade_session",
+                rows.iter().map(|row| row.trade_session.to_string()),
+            )
+            .finish()
+    }
+}
+
+impl ArrowRecord for IntradayLine {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Columns::default()
+            .timestamp("timestamp", rows.iter().map(|row| row.timestamp))
+            .decimal("price", rows.iter().map(|row| row.price))
+            .int64("volume", rows.iter().map(|row| row.volume))
+            .decimal("turnover", rows.iter().map(|row| row.turnover))
+            .decimal("avg_price", rows.iter().map(|row| row.avg_price))
+            .finish()
+    }
+}
+
+impl Columns {
+    fn pre_post_quote(self, prefix: &str, quotes: &[Option<&PrePostQuote>]) -> Self {
+        let column = |name: &str| format!("{prefix}_{name}");
+        self.decimal_opt(
+            &column("last_done"),
+            quotes
+                .iter()
+                .map(|quote| quote.map(|quote| quote.last_done)),
+        )
+        .timestamp_opt(
+            &column("timestamp"),
+            quotes
+                .iter()
+                .map(|quote| quote.map(|quote| quote.timestamp)),
+        )
+        .int64_opt(
+            &column("volume"),
+            quotes.iter().map(|quote| quote.map(|quote| quote.volume)),
+        )
+        .decimal_opt(
+            &column("turnover"),
+            quotes.iter().map(|quote| quote.map(|quote| quote.turnover)),
+        )
+        .decimal_opt(
+            &column("high"),
+            quotes.iter().map(|quote| quote.map(|quote| quote.high)),
+        )
+        .decimal_opt(
+            &column("low"),
+            quotes.iter().map(|quote| quote.map(|quote| quote.low)),
+        )
+        .decimal_opt(
+            &column("prev_close"),
+            quotes
+                .iter()
+                .map(|quote| quote.map(|quote| quote.prev_close)),
+        )
+    }
+}
+
+impl ArrowRecord for SecurityQuote {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        let quotes =
+            |f: fn(&SecurityQuote) -> Option<&PrePostQuote>| rows.iter().map(f).collect::<Vec<_>>();
+        Columns::default()
+            .string("symbol", rows.iter().map(|row| &row.symbol))
+            .timestamp("timestamp", rows.iter().map(|row| row.timestamp))
+            .decimal("last_done", rows.iter().map(|row| row.last_done))
+            .decimal("prev_close", rows.iter().map(|row| row.prev_close))
+            .decimal("open", rows.iter().map(|row| row.open))
+            .decimal("high", rows.iter().map(|row| row.high))
+            .decimal("low", rows.iter().map(|row| row.low))
+            .int64("volume", rows.iter().map(|row| row.volume))
+            .decimal("turnover", rows.iter().map(|row| row.turnover))
+            .string(
+                "trade_status",
+                rows.iter().map(|row| trade_status_name(row.trade_status)),
+            )
+            .pre_post_quote("pre_market", &quotes(|row| row.pre_market_quote.as_ref()))
+            .pre_post_quote("post_market", &quotes(|row| row.post_market_quote.as_ref()))
+            .pre_post_quote("overnight", &quotes(|row| row.overnight_quote.as_ref()))
+            .finish()
+    }
+}
+
+impl ArrowRecord for Order {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Columns::default()
+            .string("order_id", rows.iter().map(|row| &row.order_id))
+            .string("status", rows.iter().map(|row| row.status.to_string()))
+            .string("stock_name", rows.iter().map(|row| &row.stock_name))
+            .decimal("quantity", rows.iter().map(|row| row.quantity))
+            .decimal(
+                "executed_quantity",
+                rows.iter().map(|row| row.executed_quantity),
+            )
+            .decimal_opt("price", rows.iter().map(|row| row.price))
+            .decimal_opt("executed_price", rows.iter().map(|row| row.executed_price))
+            .timestamp("submitted_at", rows.iter().map(|row| row.submitted_at))
+            .string("side", rows.iter().map(|row| row.side.to_string()))
+            .string("symbol", rows.iter().map(|row| &row.symbol))
+            .string(
+                "order_type",
+                rows.iter().map(|row| row.order_type.to_string()),
+            )
+            .decimal_opt("last_done", rows.iter().map(|row| row.last_done))
+            .decimal_opt("trigger_price", rows.iter().map(|row| row.trigger_price))
+            .string("msg", rows.iter().map(|row| &row.msg))
+            .string("tag", rows.iter().map(|row| row.tag.to_string()))
+            .string(
+                "time_in_force",
+                rows.iter().map(|row| row.time_in_force.to_string()),
+            )
+            .date_opt("expire_date", rows.iter().map(|row| row.expire_date))
+            .timestamp_opt("updated_at", rows.iter().map(|row| row.updated_at))
+            .timestamp_opt("trigger_at", rows.iter().map(|row| row.trigger_at))
+            .decimal_opt(
+                "trailing_amount",
+                rows.iter().map(|row| row.trailing_amount),
+            )
+            .decimal_opt(
+                "trailing_percent",
+                rows.iter().map(|row| row.trailing_percent),
+            )
+            .decimal_opt("limit_offset", rows.iter().map(|row| row.limit_offset))
+            .string_opt(
+                "trigger_status",
+                rows.iter()
+                    .map(|row| row.trigger_status.map(|status| status.to_string())),
+            )
+            .string("currency", rows.iter().map(|row| &row.currency))
+            .string_opt(
+                "outside_rth",
+                rows.iter()
+                    .map(|row| row.outside_rth.map(|outside_rth| outside_rth.to_string())),
+            )
+            .string("remark", rows.iter().map(|row| &row.remark))
+            .finish()
+    }
+}
+
+impl ArrowRecord for Execution {
+    fn to_record_batch(rows: &[Self]) -> Result<RecordBatch> {
+        Columns::default()
+            .string("order_id", rows.iter().map(|row| &row.order_id))
+            .string("trade_id", rows.iter().map(|row| &row.trade_id))
+            .string("symbol", rows.iter().map(|row| &row.symbol))
+            .timestamp("trade_done_at", rows.iter().map(|row| row.trade_done_at))
+            .decimal("quantity", rows.iter().map(|row| row.quantity))
+            .decimal("price", rows.iter().map(|row| row.price))
+            .finish()
+    }
+}
+
+/// Writes the record batch to a Parquet file
+pub fn write_record_batch<W: Write + Send>(writer: W, batch: &RecordBatch) -> Result<()> {
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)
+        .map_err(|err| Error::Arrow(err.to_string()))?;
+    writer
+        .write(batch)
+        .map_err(|err| Error::Arrow(err.to_string()))?;
+    writer
+        .close()
+        .map_err(|err| Error::Arrow(err.to_string()))?;
+    Ok(())
+}
+
+/// Writes the rows to a Parquet file, the file is created or truncated
+pub fn write_parquet<T: ArrowRecord>(path: impl AsRef<Path>, rows: &[T]) -> Result<()> {
+    let batch = T::to_record_batch(rows)?;
+    write_record_batch(File::create(path)?, &batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{
+        cast::AsArray,
+        types::{Decimal128Type, TimestampNanosecondType},
+    };
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::quote::{TradeDirection, TradeSession};
+
+    #[test]
+    fn test_candlesticks_record_batch() {
+        let candlesticks = vec![Candlestick {
+            close: "320.123456789".parse().unwrap(),
+            open: "1.0000000006".parse().unwrap(),
+            low: Decimal::MAX,
+            high: "-0.5".parse().unwrap(),
+            volume: 100,
+            turnover: Decimal::ZERO,
+            timestamp: datetime!(2024-01-02 01:30:00 UTC),
+            trade_session: TradeSession::Pre,
+        }];
+        let batch = Candlestick::to_record_batch(&candlesticks).unwrap();
+        assert_eq!(batch.schema(), Candlestick::schema());
+        assert_eq!(batch.num_rows(), 1);
+
+        let decimal = |name: &str| {
+            let column = batch.column_by_name(name).unwrap();
+            column.as_primitive::<Decimal128Type>().value_as_string(0)
+        };
+        assert_eq!(decimal("close"), "320.123456789");
+        assert_eq!(decimal("open"), "1.000000001");
+        assert_eq!(decimal("high"), "-0.500000000");
+        assert_eq!(decimal("low"), format!("{}.000000000", Decimal::MAX));
+
+        let timestamp = batch.column_by_name("timestamp").unwrap();
+        assert_eq!(
+            timestamp.as_primitive::<TimestampNanosecondType>().value(0),
+            1_704_159_000_000_000_000
+        );
+        assert_eq!(
+            batch
+                .column_by_name("trade_session")
+                .unwrap()
+                .as_string::<i32>()
+                .value(0),
+            "Pre"
+        );
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_parquet(file.path(), &candlesticks).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file.reopen().unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches = reader.collect::<std::result::Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches, vec![batch]);
+    }
+
+    #[test]
+    fn test_enum_columns() {
+        let trades = vec![Trade {
+            price: Decimal::ONE,
+            volume: 1,
+            timestamp: datetime!(2024-01-02 01:30:00 UTC),
+            trade_type: String::new(),
+            direction: TradeDirection::Up,
+            trade_session: TradeSession::Post,
+        }];
+        let batch = Trade::to_record_batch(&trades).unwrap();
+        let string = |name: &str| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_string::<i32>()
+                .value(0)
+                .to_string()
+        };
+        assert_eq!(string("direction"), "Up");
+        assert_eq!(string("trade_session"), "Post");
+
+        assert_eq!(
+            trade_status_name(TradeStatus::SplitStockHalts),
+            "SplitStockHalts"
+        );
+        assert_eq!(
+            crate::trade::OutsideRTH::RTHOnly.to_string(),
+            serde_json::to_value(crate::trade::OutsideRTH::RTHOnly).unwrap()
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use time::{Date, PrimitiveDateTime};
 
 use crate::{
-    Config, Market, QuoteContext, Result,
+    CallOptions, Config, Market, QuoteContext, Result,
     blocking::runtime::BlockingRuntime,
     quote::{
         AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
@@ -19,7 +19,8 @@ use crate::{
 
 /// Quote context
 pub struct QuoteContextSync {
-    rt: BlockingRuntime<QuoteContext>,
+    rt: Arc<BlockingRuntime<QuoteContext>>,
+    options: CallOptions,
 }
 
 impl QuoteContextSync {
@@ -29,24 +30,46 @@ impl QuoteContextSync {
         F: FnMut(PushEvent) + Send + 'static,
     {
         let rt = BlockingRuntime::try_new(move || QuoteContext::try_new(config), push_callback)?;
-        Ok(Self { rt })
+        Ok(Self {
+            rt: Arc::new(rt),
+            options: CallOptions::default(),
+        })
+    }
+
+    /// Returns a `QuoteContextSync` sharing the connection with this one, whose calls
+    /// use the specified options
+    #[must_use]
+    pub fn with_call_options(&self, options: CallOptions) -> Self {
+        Self {
+            rt: self.rt.clone(),
+            options,
+        }
+    }
+
+    fn call<F, Fut, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(Arc<QuoteContext>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<R>> + Send,
+        R: Send + 'static,
+    {
+        let options = self.options.clone();
+        self.rt
+            .call(move |ctx| f(Arc::new(ctx.with_call_options(options))))
     }
 
     /// Returns the member ID
     pub fn member_id(&self) -> Result<i64> {
-        self.rt.call(|ctx| async move { Ok(ctx.member_id()) })
+        self.call(|ctx| async move { Ok(ctx.member_id()) })
     }
 
     /// Returns the quote level
     pub fn quote_level(&self) -> Result<String> {
-        self.rt
-            .call(|ctx| async move { Ok(ctx.quote_level().to_string()) })
+        self.call(|ctx| async move { Ok(ctx.quote_level().to_string()) })
     }
 
     /// Returns the quote package details
     pub fn quote_package_details(&self) -> Result<Vec<QuotePackageDetail>> {
-        self.rt
-            .call(|ctx| async move { Ok(ctx.quote_package_details().to_vec()) })
+        self.call(|ctx| async move { Ok(ctx.quote_package_details().to_vec()) })
     }
 
     /// Subscribe
@@ -82,7 +105,7 @@ impl QuoteContextSync {
         T: AsRef<str> + Send + 'static,
         F: Into<SubFlags> + Send + 'static,
     {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.subscribe(symbols, sub_types.into(), is_first_push)
                 .await
         })
@@ -113,8 +136,7 @@ impl QuoteContextSync {
         T: AsRef<str> + Send + 'static,
         F: Into<SubFlags> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.unsubscribe(symbols, sub_types.into()).await })
+        self.call(move |ctx| async move { ctx.unsubscribe(symbols, sub_types.into()).await })
     }
 
     /// Subscribe security candlesticks
@@ -152,7 +174,7 @@ impl QuoteContextSync {
     where
         T: AsRef<str> + Send + 'static,
     {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.subscribe_candlesticks(symbol, period, trade_sessions)
                 .await
         })
@@ -163,8 +185,7 @@ impl QuoteContextSync {
     where
         T: AsRef<str> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.unsubscribe_candlesticks(symbol, period).await })
+        self.call(move |ctx| async move { ctx.unsubscribe_candlesticks(symbol, period).await })
     }
 
     /// Get subscription information
@@ -187,8 +208,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn subscriptions(&self) -> Result<Vec<Subscription>> {
-        self.rt
-            .call(move |ctx| async move { ctx.subscriptions().await })
+        self.call(move |ctx| async move { ctx.subscriptions().await })
     }
 
     /// Get basic information of securities
@@ -215,8 +235,7 @@ impl QuoteContextSync {
         I::IntoIter: Send + 'static,
         T: Into<String> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.static_info(symbols).await })
+        self.call(move |ctx| async move { ctx.static_info(symbols).await })
     }
 
     /// Get quote of securities
@@ -243,8 +262,7 @@ impl QuoteContextSync {
         I::IntoIter: Send + 'static,
         T: Into<String> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.quote(symbols).await })
+        self.call(move |ctx| async move { ctx.quote(symbols).await })
     }
 
     /// Get quote of option securities
@@ -271,8 +289,7 @@ impl QuoteContextSync {
         I::IntoIter: Send + 'static,
         T: Into<String> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.option_quote(symbols).await })
+        self.call(move |ctx| async move { ctx.option_quote(symbols).await })
     }
 
     /// Get quote of warrant securities
@@ -299,8 +316,7 @@ impl QuoteContextSync {
         I::IntoIter: Send + 'static,
         T: Into<String> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.warrant_quote(symbols).await })
+        self.call(move |ctx| async move { ctx.warrant_quote(symbols).await })
     }
 
     /// Get security depth
@@ -322,8 +338,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn depth(&self, symbol: impl Into<String> + Send + 'static) -> Result<SecurityDepth> {
-        self.rt
-            .call(move |ctx| async move { ctx.depth(symbol).await })
+        self.call(move |ctx| async move { ctx.depth(symbol).await })
     }
 
     /// Get security brokers
@@ -345,8 +360,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn brokers(&self, symbol: impl Into<String> + Send + 'static) -> Result<SecurityBrokers> {
-        self.rt
-            .call(move |ctx| async move { ctx.brokers(symbol).await })
+        self.call(move |ctx| async move { ctx.brokers(symbol).await })
     }
 
     /// Get participants
@@ -368,8 +382,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn participants(&self) -> Result<Vec<ParticipantInfo>> {
-        self.rt
-            .call(move |ctx| async move { ctx.participants().await })
+        self.call(move |ctx| async move { ctx.participants().await })
     }
 
     /// Get security trades
@@ -395,8 +408,7 @@ impl QuoteContextSync {
         symbol: impl Into<String> + Send + 'static,
         count: usize,
     ) -> Result<Vec<Trade>> {
-        self.rt
-            .call(move |ctx| async move { ctx.trades(symbol, count).await })
+        self.call(move |ctx| async move { ctx.trades(symbol, count).await })
     }
 
     /// Get security intraday lines
@@ -422,8 +434,7 @@ impl QuoteContextSync {
         symbol: impl Into<String> + Send + 'static,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<IntradayLine>> {
-        self.rt
-            .call(move |ctx| async move { ctx.intraday(symbol, trade_sessions).await })
+        self.call(move |ctx| async move { ctx.intraday(symbol, trade_sessions).await })
     }
 
     /// Get security candlesticks
@@ -462,7 +473,7 @@ impl QuoteContextSync {
         adjust_type: AdjustType,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.candlesticks(symbol, period, count, adjust_type, trade_sessions)
                 .await
         })
@@ -480,7 +491,7 @@ impl QuoteContextSync {
         count: usize,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.history_candlesticks_by_offset(
                 symbol,
                 period,
@@ -504,7 +515,7 @@ impl QuoteContextSync {
         end: Option<Date>,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.history_candlesticks_by_date(
                 symbol,
                 period,
@@ -539,8 +550,7 @@ impl QuoteContextSync {
         &self,
         symbol: impl Into<String> + Send + 'static,
     ) -> Result<Vec<Date>> {
-        self.rt
-            .call(move |ctx| async move { ctx.option_chain_expiry_date_list(symbol).await })
+        self.call(move |ctx| async move { ctx.option_chain_expiry_date_list(symbol).await })
     }
 
     /// Get option chain info by date
@@ -567,7 +577,7 @@ impl QuoteContextSync {
         symbol: impl Into<String> + Send + 'static,
         expiry_date: Date,
     ) -> Result<Vec<StrikePriceInfo>> {
-        self.rt.call(
+        self.call(
             move |ctx| async move { ctx.option_chain_info_by_date(symbol, expiry_date).await },
         )
     }
@@ -591,8 +601,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn warrant_issuers(&self) -> Result<Vec<IssuerInfo>> {
-        self.rt
-            .call(move |ctx| async move { ctx.warrant_issuers().await })
+        self.call(move |ctx| async move { ctx.warrant_issuers().await })
     }
 
     /// Query warrant list
@@ -613,7 +622,7 @@ impl QuoteContextSync {
         let expiry_date = expiry_date.map(|v| v.to_vec());
         let price_type = price_type.map(|v| v.to_vec());
         let status = status.map(|v| v.to_vec());
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.warrant_list(
                 symbol,
                 sort_by,
@@ -647,8 +656,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn trading_session(&self) -> Result<Vec<MarketTradingSession>> {
-        self.rt
-            .call(move |ctx| async move { ctx.trading_session().await })
+        self.call(move |ctx| async move { ctx.trading_session().await })
     }
 
     /// Get market trading days
@@ -679,8 +687,7 @@ impl QuoteContextSync {
         begin: Date,
         end: Date,
     ) -> Result<MarketTradingDays> {
-        self.rt
-            .call(move |ctx| async move { ctx.trading_days(market, begin, end).await })
+        self.call(move |ctx| async move { ctx.trading_days(market, begin, end).await })
     }
 
     /// Get capital flow intraday
@@ -706,8 +713,7 @@ impl QuoteContextSync {
         &self,
         symbol: impl Into<String> + Send + 'static,
     ) -> Result<Vec<CapitalFlowLine>> {
-        self.rt
-            .call(move |ctx| async move { ctx.capital_flow(symbol).await })
+        self.call(move |ctx| async move { ctx.capital_flow(symbol).await })
     }
 
     /// Get capital distribution
@@ -733,8 +739,7 @@ impl QuoteContextSync {
         &self,
         symbol: impl Into<String> + Send + 'static,
     ) -> Result<CapitalDistributionResponse> {
-        self.rt
-            .call(move |ctx| async move { ctx.capital_distribution(symbol).await })
+        self.call(move |ctx| async move { ctx.capital_distribution(symbol).await })
     }
 
     /// Get calc indexes
@@ -746,8 +751,7 @@ impl QuoteContextSync {
         J: IntoIterator<Item = CalcIndex> + Send + 'static,
         J::IntoIter: Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.calc_indexes(symbols, indexes).await })
+        self.call(move |ctx| async move { ctx.calc_indexes(symbols, indexes).await })
     }
 
     /// Get watchlist
@@ -769,8 +773,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn watchlist(&self) -> Result<Vec<WatchlistGroup>> {
-        self.rt
-            .call(move |ctx| async move { ctx.watchlist().await })
+        self.call(move |ctx| async move { ctx.watchlist().await })
     }
 
     /// Create watchlist group
@@ -793,8 +796,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn create_watchlist_group(&self, req: RequestCreateWatchlistGroup) -> Result<i64> {
-        self.rt
-            .call(move |ctx| async move { ctx.create_watchlist_group(req).await })
+        self.call(move |ctx| async move { ctx.create_watchlist_group(req).await })
     }
 
     /// Delete watchlist group
@@ -815,8 +817,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn delete_watchlist_group(&self, id: i64, purge: bool) -> Result<()> {
-        self.rt
-            .call(move |ctx| async move { ctx.delete_watchlist_group(id, purge).await })
+        self.call(move |ctx| async move { ctx.delete_watchlist_group(id, purge).await })
     }
 
     /// Update watchlist group
@@ -840,8 +841,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn update_watchlist_group(&self, req: RequestUpdateWatchlistGroup) -> Result<()> {
-        self.rt
-            .call(move |ctx| async move { ctx.update_watchlist_group(req).await })
+        self.call(move |ctx| async move { ctx.update_watchlist_group(req).await })
     }
 
     /// Get security list
@@ -851,8 +851,7 @@ impl QuoteContextSync {
         category: impl Into<Option<SecurityListCategory>>,
     ) -> Result<Vec<Security>> {
         let category = category.into();
-        self.rt
-            .call(move |ctx| async move { ctx.security_list(market, category).await })
+        self.call(move |ctx| async move { ctx.security_list(market, category).await })
     }
 
     /// Get current market temperature
@@ -874,8 +873,7 @@ impl QuoteContextSync {
     /// # }
     /// ```
     pub fn market_temperature(&self, market: Market) -> Result<MarketTemperature> {
-        self.rt
-            .call(move |ctx| async move { ctx.market_temperature(market).await })
+        self.call(move |ctx| async move { ctx.market_temperature(market).await })
     }
 
     /// Get historical market temperature
@@ -904,7 +902,7 @@ impl QuoteContextSync {
         start_date: Date,
         end: Date,
     ) -> Result<HistoryMarketTemperatureResponse> {
-        self.rt.call(move |ctx| async move {
+        self.call(move |ctx| async move {
             ctx.history_market_temperature(market, start_date, end)
                 .await
         })
@@ -940,8 +938,7 @@ impl QuoteContextSync {
         I::IntoIter: Send + 'static,
         T: Into<String> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.realtime_quote(symbols).await })
+        self.call(move |ctx| async move { ctx.realtime_quote(symbols).await })
     }
 
     /// Get real-time depth
@@ -972,8 +969,7 @@ impl QuoteContextSync {
         &self,
         symbol: impl Into<String> + Send + 'static,
     ) -> Result<SecurityDepth> {
-        self.rt
-            .call(move |ctx| async move { ctx.realtime_depth(symbol).await })
+        self.call(move |ctx| async move { ctx.realtime_depth(symbol).await })
     }
 
     /// Get real-time trades
@@ -1005,8 +1001,7 @@ impl QuoteContextSync {
         symbol: impl Into<String> + Send + 'static,
         count: usize,
     ) -> Result<Vec<Trade>> {
-        self.rt
-            .call(move |ctx| async move { ctx.realtime_trades(symbol, count).await })
+        self.call(move |ctx| async move { ctx.realtime_trades(symbol, count).await })
     }
 
     /// Get real-time broker queue
@@ -1037,8 +1032,7 @@ impl QuoteContextSync {
         &self,
         symbol: impl Into<String> + Send + 'static,
     ) -> Result<SecurityBrokers> {
-        self.rt
-            .call(move |ctx| async move { ctx.realtime_brokers(symbol).await })
+        self.call(move |ctx| async move { ctx.realtime_brokers(symbol).await })
     }
 
     /// Get real-time candlesticks
@@ -1075,7 +1069,6 @@ impl QuoteContextSync {
         period: Period,
         count: usize,
     ) -> Result<Vec<Candlestick>> {
-        self.rt
-            .call(move |ctx| async move { ctx.realtime_candlesticks(symbol, period, count).await })
+        self.call(move |ctx| async move { ctx.realtime_candlesticks(symbol, period, count).await })
     }
 }
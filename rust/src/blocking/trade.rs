@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use crate::{
-    Config, Result,
+    CallOptions, Config, Result,
     blocking::runtime::BlockingRuntime,
     trade::{
         AccountBalance, CashFlow, EstimateMaxPurchaseQuantityOptions,
@@ -15,7 +15,8 @@ use crate::{
 
 /// Trade context
 pub struct TradeContextSync {
-    rt: BlockingRuntime<TradeContext>,
+    rt: Arc<BlockingRuntime<TradeContext>>,
+    options: CallOptions,
 }
 
 impl TradeContextSync {
@@ -25,7 +26,31 @@ impl TradeContextSync {
         F: FnMut(PushEvent) + Send + 'static,
     {
         let rt = BlockingRuntime::try_new(move || TradeContext::try_new(config), push_callback)?;
-        Ok(Self { rt })
+        Ok(Self {
+            rt: Arc::new(rt),
+            options: CallOptions::default(),
+        })
+    }
+
+    /// Returns a `TradeContextSync` sharing the connection with this one, whose calls
+    /// use the specified options
+    #[must_use]
+    pub fn with_call_options(&self, options: CallOptions) -> Self {
+        Self {
+            rt: self.rt.clone(),
+            options,
+        }
+    }
+
+    fn call<F, Fut, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(Arc<TradeContext>) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<R>> + Send,
+        R: Send + 'static,
+    {
+        let options = self.options.clone();
+        self.rt
+            .call(move |ctx| f(Arc::new(ctx.with_call_options(options))))
     }
 
     /// Subscribe topics
@@ -33,8 +58,7 @@ impl TradeContextSync {
     where
         I: IntoIterator<Item = TopicType> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.subscribe(topics).await })
+        self.call(move |ctx| async move { ctx.subscribe(topics).await })
     }
 
     /// Unsubscribe topics
@@ -42,8 +66,7 @@ impl TradeContextSync {
     where
         I: IntoIterator<Item = TopicType> + Send + 'static,
     {
-        self.rt
-            .call(move |ctx| async move { ctx.unsubscribe(topics).await })
+        self.call(move |ctx| async move { ctx.unsubscribe(topics).await })
     }
 
     /// Get history executions
@@ -72,8 +95,7 @@ impl TradeContextSync {
         &self,
         options: impl Into<Option<GetHistoryExecutionsOptions>> + Send + 'static,
     ) -> Result<Vec<Execution>> {
-        self.rt
-            .call(move |ctx| async move { ctx.history_executions(options).await })
+        self.call(move |ctx| async move { ctx.history_executions(options).await })
     }
 
     /// Get today executions
@@ -100,8 +122,7 @@ impl TradeContextSync {
         &self,
         options: impl Into<Option<GetTodayExecutionsOptions>> + Send + 'static,
     ) -> Result<Vec<Execution>> {
-        self.rt
-            .call(move |ctx| async move { ctx.today_executions(options).await })
+        self.call(move |ctx| async move { ctx.today_executions(options).await })
     }
 
     /// Get history orders
@@ -138,8 +159,7 @@ impl TradeContextSync {
         &self,
         options: impl Into<Option<GetHistoryOrdersOptions>> + Send + 'static,
     ) -> Result<Vec<Order>> {
-        self.rt
-            .call(move |ctx| async move { ctx.history_orders(options).await })
+        self.call(move |ctx| async move { ctx.history_orders(options).await })
     }
 
     /// Get today orders
@@ -173,8 +193,7 @@ impl TradeContextSync {
         &self,
         options: impl Into<Option<GetTodayOrdersOptions>> + Send + 'static,
     ) -> Result<Vec<Order>> {
-        self.rt
-            .call(move |ctx| async move { ctx.today_orders(options).await })
+        self.call(move |ctx| async move { ctx.today_orders(options).await })
     }
 
     /// Replace order
@@ -198,8 +217,7 @@ impl TradeContextSync {
     /// # }
     /// ```
     pub fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
-        self.rt
-            .call(move |ctx| async move { ctx.replace_order(options).await })
+        self.call(move |ctx| async move { ctx.replace_order(options).await })
     }
 
     /// Submit order
@@ -234,8 +252,7 @@ impl TradeContextSync {
     /// # }
     /// ```
     pub fn submit_order(&self, options: SubmitOrderOptions) -> Result<SubmitOrderResponse> {
-        self.rt
-            .call(move |ctx| async move { ctx.submit_order(options).await })
+        self.call(move |ctx| async move { ctx.submit_order(options).await })
     }
 
     /// Cancel order
@@ -256,8 +273,7 @@ impl TradeContextSync {
     /// # }
     /// ```
     pub fn cancel_order(&self, order_id: impl Into<String> + Send + 'static) -> Result<()> {
-        self.rt
-            .call(move |ctx| async move { ctx.cancel_order(order_id).await })
+        self.call(move |ctx| async move { ctx.cancel_order(order_id).await })
     }
 
     /// Get account balance
@@ -280,8 +296,7 @@ impl TradeContextSync {
     /// ```
     pub fn account_balance(&self, currency: Option<&str>) -> Result<Vec<AccountBalance>> {
         let currency = currency.map(ToString::to_string);
-        self.rt
-            .call(move |ctx| async move { ctx.account_balance(currency.as_deref()).await })
+        self.call(move |ctx| async move { ctx.account_balance(currency.as_deref()).await })
     }
 
     /// Get cash flow
@@ -305,8 +320,7 @@ impl TradeContextSync {
     /// # }
     /// ```
     pub fn cash_flow(&self, options: GetCashFlowOptions) -> Result<Vec<CashFlow>> {
-        self.rt
-            .call(move |ctx| async move { ctx.cash_flow(options).await })
+        self.call(move |ctx| async move { ctx.cash_flow(options).await })
     }
 
     /// Get fund positions
@@ -331,8 +345,7 @@ impl TradeContextSync {
         &self,
         opts: impl Into<Option<GetFundPositionsOptions>> + Send + 'static,
     ) -> Result<FundPositionsResponse> {
-        self.rt
-            .call(move |ctx| async move { ctx.fund_positions(opts).await })
+        self.call(move |ctx| async move { ctx.fund_positions(opts).await })
     }
 
     /// Get stock positions
@@ -357,8 +370,7 @@ impl TradeContextSync {
         &self,
         opts: impl Into<Option<GetStockPositionsOptions>> + Send + 'static,
     ) -> Result<StockPositionsResponse> {
-        self.rt
-            .call(move |ctx| async move { ctx.stock_positions(opts).await })
+        self.call(move |ctx| async move { ctx.stock_positions(opts).await })
     }
 
     /// Get margin ratio
@@ -380,8 +392,7 @@ impl TradeContextSync {
     /// # }
     /// ```
     pub fn margin_ratio(&self, symbol: impl Into<String> + Send + 'static) -> Result<MarginRatio> {
-        self.rt
-            .call(move |ctx| async move { ctx.margin_ratio(symbol).await })
+        self.call(move |ctx| async move { ctx.margin_ratio(symbol).await })
     }
 
     /// Get order detail
@@ -408,8 +419,7 @@ impl TradeContextSync {
         &self,
         order_id: impl Into<String> + Send + 'static,
     ) -> Result<OrderDetail> {
-        self.rt
-            .call(move |ctx| async move { ctx.order_detail(order_id).await })
+        self.call(move |ctx| async move { ctx.order_detail(order_id).await })
     }
 
     /// Estimating the maximum purchase quantity for Hong Kong and US stocks,
@@ -418,7 +428,6 @@ impl TradeContextSync {
         &self,
         opts: EstimateMaxPurchaseQuantityOptions,
     ) -> Result<EstimateMaxPurchaseQuantityResponse> {
-        self.rt
-            .call(move |ctx| async move { ctx.estimate_max_purchase_quantity(opts).await })
+        self.call(move |ctx| async move { ctx.estimate_max_purchase_quantity(opts).await })
     }
 }
@@ -0,0 +1,161 @@
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{Error, Result};
+
+/// Options of the `QuoteContext` and `TradeContext` calls
+///
+/// The options apply to every call of the context returned by
+/// `QuoteContext::with_call_options` or `TradeContext::with_call_options`,
+/// including the retries.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{sync::Arc, time::Duration};
+///
+/// use longport::{CallOptions, Config, QuoteContext};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let (ctx, _) = QuoteContext::try_new(config).await?;
+///
+/// let fast_ctx = ctx.with_call_options(CallOptions::new().timeout(Duration::from_millis(300)));
+/// let resp = fast_ctx.quote(["700.HK"]).await?;
+/// println!("{:?}", resp);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CallOptions {
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl CallOptions {
+    /// Create a new `CallOptions`
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the timeout of every call, the call fails with
+    /// [`Error::Timeout`] if it is not completed within the timeout.
+    ///
+    /// Default: `None`
+    #[must_use]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Specifies the deadline of the calls, the calls fail with
+    /// [`Error::Timeout`] if they are not completed before the deadline.
+    ///
+    /// Default: `None`
+    #[must_use]
+    pub fn deadline(self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Specifies the cancellation token, the calls fail with
+    /// [`Error::Cancelled`] when it is cancelled.
+    ///
+    /// Default: `None`
+    #[must_use]
+    pub fn cancellation_token(self, cancellation_token: CancellationToken) -> Self {
+        Self {
+            cancellation_token: Some(cancellation_token),
+            ..self
+        }
+    }
+
+    /// Returns the deadline of a call starting now
+    fn call_deadline(&self) -> Option<tokio::time::Instant> {
+        let timeout_deadline = self
+            .timeout
+            .map(|timeout| tokio::time::Instant::now() + timeout);
+        let deadline = self.deadline.map(tokio::time::Instant::from_std);
+        match (timeout_deadline, deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the time remaining before the deadline of a call starting now
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        self.call_deadline()
+            .map(|deadline| deadline.saturating_duration_since(tokio::time::Instant::now()))
+    }
+
+    /// Runs the call, dropping it when the deadline is exceeded or the
+    /// cancellation token is cancelled
+    pub(crate) async fn run<F, T>(&self, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let deadline = self.call_deadline();
+        let fut = async move {
+            match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, fut)
+                    .await
+                    .map_err(|_| Error::Timeout)?,
+                None => fut.await,
+            }
+        };
+
+        match &self.cancellation_token {
+            Some(cancellation_token) => tokio::select! {
+                biased;
+                _ = cancellation_token.cancelled() => Err(Error::Cancelled),
+                res = fut => res,
+            },
+            None => fut.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let options = CallOptions::new().timeout(Duration::from_millis(50));
+        let res = options
+            .run(async {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(res, Err(Error::Timeout)));
+
+        let res = options.run(async { Ok(1) }).await;
+        assert_eq!(res.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() {
+        let token = CancellationToken::new();
+        let options = CallOptions::new().cancellation_token(token.clone());
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(async move {
+            options
+                .run(async move {
+                    let _ = started_tx.send(());
+                    std::future::pending::<Result<()>>().await
+                })
+                .await
+        });
+        started_rx.await.unwrap();
+        token.cancel();
+        assert!(matches!(handle.await.unwrap(), Err(Error::Cancelled)));
+    }
+}
@@ -0,0 +1,480 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use tracing_subscriber::filter::Targets;
+
+use crate::{
+    Error, Language, LogFormat, LogRotation, PushCandlestickMode, Region, Result,
+    credentials::Credentials, logger::parse_log_filter,
+};
+
+/// The options of [`Config`](crate::Config) which can be specified in a config
+/// file or by the environment variables, `None` means not specified
+///
+/// The keys of the config file are the names of the environment variables
+/// without the `LONGPORT_` prefix in lowercase, e.g. `app_key` for
+/// `LONGPORT_APP_KEY`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct ConfigOptions {
+    pub(crate) app_key: Option<String>,
+    pub(crate) app_secret: Option<String>,
+    pub(crate) access_token: Option<String>,
+    pub(crate) http_url: Option<String>,
+    pub(crate) quote_ws_url: Option<String>,
+    pub(crate) trade_ws_url: Option<String>,
+    pub(crate) language: Option<Language>,
+    pub(crate) enable_overnight: Option<bool>,
+    pub(crate) push_candlestick_mode: Option<PushCandlestickMode>,
+    pub(crate) print_quote_packages: Option<bool>,
+    pub(crate) log_path: Option<PathBuf>,
+    pub(crate) log_level: Option<Targets>,
+    pub(crate) log_format: Option<LogFormat>,
+    pub(crate) log_rotation: Option<LogRotation>,
+    pub(crate) log_max_files: Option<usize>,
+    pub(crate) log_redaction: Option<bool>,
+    pub(crate) quote_mux_path: Option<PathBuf>,
+    pub(crate) session_tables_path: Option<PathBuf>,
+    pub(crate) proxy: Option<String>,
+    pub(crate) region: Option<Region>,
+}
+
+impl ConfigOptions {
+    /// Read the options from the environment variables
+    ///
+    /// NOTE: `LONGPORT_REGION` is not read here, it is applied when resolving
+    /// [`Region::Auto`].
+    pub(crate) fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok();
+
+        Self {
+            app_key: var("LONGPORT_APP_KEY"),
+            app_secret: var("LONGPORT_APP_SECRET"),
+            access_token: var("LONGPORT_ACCESS_TOKEN"),
+            http_url: var("LONGPORT_HTTP_URL"),
+            quote_ws_url: var("LONGPORT_QUOTE_WS_URL"),
+            trade_ws_url: var("LONGPORT_TRADE_WS_URL"),
+            language: var("LONGPORT_LANGUAGE").and_then(|value| value.parse().ok()),
+            enable_overnight: var("LONGPORT_ENABLE_OVERNIGHT").map(|value| value == "true"),
+            push_candlestick_mode: var("LONGPORT_PUSH_CANDLESTICK_MODE").map(|value| {
+                match value.as_str() {
+                    "confirmed" => PushCandlestickMode::Confirmed,
+                    _ => PushCandlestickMode::Realtime,
+                }
+            }),
+            print_quote_packages: var("LONGPORT_PRINT_QUOTE_PACKAGES").map(|value| value == "true"),
+            log_path: var("LONGPORT_LOG_PATH").map(PathBuf::from),
+            log_level: var("LONGPORT_LOG_LEVEL").and_then(|value| parse_log_filter(&value)),
+            log_format: var("LONGPORT_LOG_FORMAT").and_then(|value| value.parse().ok()),
+            log_rotation: var("LONGPORT_LOG_ROTATION").and_then(|value| value.parse().ok()),
+            log_max_files: var("LONGPORT_LOG_MAX_FILES").and_then(|value| value.parse().ok()),
+            log_redaction: var("LONGPORT_LOG_REDACTION").map(|value| value != "false"),
+            quote_mux_path: var("LONGPORT_QUOTE_MUX_PATH").map(PathBuf::from),
+            session_tables_path: var("LONGPORT_SESSION_TABLES_PATH").map(PathBuf::from),
+            proxy: var("LONGPORT_PROXY"),
+            region: None,
+        }
+    }
+
+    /// Read the options from a TOML file, or a YAML file if the extension is
+    /// `.yaml` or `.yml` and the `yaml` feature is enabled
+    ///
+    /// The options of `profile` in the `profiles` table override the top
+    /// level options.
+    pub(crate) fn from_file(path: &Path, profile: Option<&str>) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yaml" | "yml")
+        );
+        let root = if is_yaml {
+            #[cfg(feature = "yaml")]
+            {
+                serde_yaml::from_str::<Value>(&data).map_err(|err| invalid(path, err))?
+            }
+            #[cfg(not(feature = "yaml"))]
+            return Err(invalid(
+                path,
+                "YAML config files require the `yaml` feature",
+            ));
+        } else {
+            toml::from_str::<Value>(&data).map_err(|err| invalid(path, err))?
+        };
+
+        let mut root = match root {
+            Value::Object(root) => root,
+            Value::Null => Map::new(),
+            _ => return Err(invalid(path, "expected a table at the top level")),
+        };
+        let profiles = match root.remove("profiles") {
+            Some(Value::Object(profiles)) => profiles,
+            Some(Value::Null) | None => Map::new(),
+            Some(_) => return Err(invalid(path, "`profiles`: expected a table")),
+        };
+
+        let mut options = Self::from_table(path, "", root)?;
+
+        // all profiles are validated, so that a mistake is reported before the
+        // profile is used
+        let mut selected = None;
+        for (name, table) in profiles {
+            let prefix = format!("profiles.{name}.");
+            let Value::Object(table) = table else {
+                return Err(invalid(
+                    path,
+                    format!("`profiles.{name}`: expected a table"),
+                ));
+            };
+            let profile_options = Self::from_table(path, &prefix, table)?;
+            if profile == Some(name.as_str()) {
+                selected = Some(profile_options);
+            }
+        }
+
+        if let Some(profile) = profile {
+            let profile_options =
+                selected.ok_or_else(|| invalid(path, format!("profile `{profile}` not found")))?;
+            options = options.merge(profile_options);
+        }
+        Ok(options)
+    }
+
+    fn from_table(path: &Path, prefix: &str, table: Map<String, Value>) -> Result<Self> {
+        let mut options = Self::default();
+
+        for (key, value) in table {
+            let full_key = format!("{prefix}{key}");
+            let field = Field {
+                path,
+                key: &full_key,
+                value,
+            };
+
+            match key.as_str() {
+                "app_key" => options.app_key = Some(field.string()?),
+                "app_secret" => options.app_secret = Some(field.string()?),
+                "access_token" => options.access_token = Some(field.string()?),
+                "http_url" => options.http_url = Some(field.string()?),
+                "quote_ws_url" => options.quote_ws_url = Some(field.string()?),
+                "trade_ws_url" => options.trade_ws_url = Some(field.string()?),
+                "language" => {
+                    options.language =
+                        Some(field.parse(|value| value.parse().ok(), "`zh-CN`, `zh-HK` or `en`")?)
+                }
+                "enable_overnight" => options.enable_overnight = Some(field.bool()?),
+                "push_candlestick_mode" => {
+                    options.push_candlestick_mode = Some(field.parse(
+                        |value| match value {
+                            "realtime" => Some(PushCandlestickMode::Realtime),
+                            "confirmed" => Some(PushCandlestickMode::Confirmed),
+                            _ => None,
+                        },
+                        "`realtime` or `confirmed`",
+                    )?)
+                }
+                "print_quote_packages" => options.print_quote_packages = Some(field.bool()?),
+                "log_path" => options.log_path = Some(field.string()?.into()),
+                "log_level" => {
+                    options.log_level = Some(field.parse(
+                        parse_log_filter,
+                        "a level or comma separated `target=level` directives",
+                    )?)
+                }
+                "log_format" => {
+                    options.log_format =
+                        Some(field.parse(|value| value.parse().ok(), "`text` or `json`")?)
+                }
+                "log_rotation" => {
+                    options.log_rotation = Some(field.parse(
+                        |value| value.parse().ok(),
+                        "`hourly`, `daily`, `never` or a size like `100MB`",
+                    )?)
+                }
+                "log_max_files" => options.log_max_files = Some(field.usize()?),
+                "log_redaction" => options.log_redaction = Some(field.bool()?),
+                "quote_mux_path" => options.quote_mux_path = Some(field.string()?.into()),
+                "session_tables_path" => options.session_tables_path = Some(field.string()?.into()),
+                "proxy" => options.proxy = Some(field.string()?),
+                "region" => {
+                    options.region =
+                        Some(field.parse(|value| value.parse().ok(), "`auto`, `global` or `cn`")?)
+                }
+                _ => return Err(invalid(path, format!("`{full_key}`: unknown key"))),
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Merge two options, the options specified in `other` take precedence
+    pub(crate) fn merge(self, other: Self) -> Self {
+        Self {
+            app_key: other.app_key.or(self.app_key),
+            app_secret: other.app_secret.or(self.app_secret),
+            access_token: other.access_token.or(self.access_token),
+            http_url: other.http_url.or(self.http_url),
+            quote_ws_url: other.quote_ws_url.or(self.quote_ws_url),
+            trade_ws_url: other.trade_ws_url.or(self.trade_ws_url),
+            language: other.language.or(self.language),
+            enable_overnight: other.enable_overnight.or(self.enable_overnight),
+            push_candlestick_mode: other.push_candlestick_mode.or(self.push_candlestick_mode),
+            print_quote_packages: other.print_quote_packages.or(self.print_quote_packages),
+            log_path: other.log_path.or(self.log_path),
+            log_level: other.log_level.or(self.log_level),
+            log_format: other.log_format.or(self.log_format),
+            log_rotation: other.log_rotation.or(self.log_rotation),
+            log_max_files: other.log_max_files.or(self.log_max_files),
+            log_redaction: other.log_redaction.or(self.log_redaction),
+            quote_mux_path: other.quote_mux_path.or(self.quote_mux_path),
+            session_tables_path: other.session_tables_path.or(self.session_tables_path),
+            proxy: other.proxy.or(self.proxy),
+            region: other.region.or(self.region),
+        }
+    }
+
+    /// Take the credentials, returns an error pointing at the missing key
+    pub(crate) fn take_credentials(&mut self, path: &Path) -> Result<Credentials> {
+        let take = |value: &mut Option<String>, key: &str, env: &str| {
+            value.take().ok_or_else(|| {
+                invalid(
+                    path,
+                    format!("missing `{key}`, specify it in the file or with `{env}`"),
+                )
+            })
+        };
+
+        Ok(Credentials {
+            app_key: take(&mut self.app_key, "app_key", "LONGPORT_APP_KEY")?,
+            app_secret: take(&mut self.app_secret, "app_secret", "LONGPORT_APP_SECRET")?,
+            access_token: take(
+                &mut self.access_token,
+                "access_token",
+                "LONGPORT_ACCESS_TOKEN",
+            )?,
+        })
+    }
+}
+
+struct Field<'a> {
+    path: &'a Path,
+    key: &'a str,
+    value: Value,
+}
+
+impl Field<'_> {
+    fn error(&self, expected: &str) -> Error {
+        invalid(
+            self.path,
+            format!(
+                "`{}`: expected {expected}, found `{}`",
+                self.key, self.value
+            ),
+        )
+    }
+
+    fn string(self) -> Result<String> {
+        match self.value {
+            Value::String(value) => Ok(value),
+            _ => Err(self.error("a string")),
+        }
+    }
+
+    fn bool(self) -> Result<bool> {
+        match self.value {
+            Value::Bool(value) => Ok(value),
+            _ => Err(self.error("`true` or `false`")),
+        }
+    }
+
+    fn usize(self) -> Result<usize> {
+        match self
+            .value
+            .as_u64()
+            .and_then(|value| usize::try_from(value).ok())
+        {
+            Some(value) => Ok(value),
+            None => Err(self.error("a non-negative integer")),
+        }
+    }
+
+    fn parse<T>(self, f: impl FnOnce(&str) -> Option<T>, expected: &str) -> Result<T> {
+        self.value
+            .as_str()
+            .and_then(f)
+            .ok_or_else(|| self.error(expected))
+    }
+}
+
+fn invalid(path: &Path, message: impl ToString) -> Error {
+    Error::ConfigFile {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_file(ext: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "longport-config-{}-{}.{ext}",
+            std::process::id(),
+            time::OffsetDateTime::now_utc().unix_timestamp_nanos()
+        ));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn load(ext: &str, content: &str, profile: Option<&str>) -> Result<ConfigOptions> {
+        let path = write_file(ext, content);
+        let res = ConfigOptions::from_file(&path, profile);
+        std::fs::remove_file(&path).unwrap();
+        res
+    }
+
+    fn error_message(res: Result<ConfigOptions>) -> String {
+        match res {
+            Err(Error::ConfigFile { message, .. }) => message,
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    const TOML: &str = r#"
+app_key = "key"
+app_secret = "secret"
+access_token = "token"
+language = "zh-HK"
+push_candlestick_mode = "confirmed"
+log_path = "/var/log/longport"
+log_rotation = "100MB"
+log_max_files = 10
+
+[profiles.paper]
+access_token = "paper-token"
+enable_overnight = true
+
+[profiles.cn]
+region = "cn"
+print_quote_packages = false
+"#;
+
+    #[test]
+    fn test_toml() {
+        let options = load("toml", TOML, None).unwrap();
+        assert_eq!(options.app_key.as_deref(), Some("key"));
+        assert_eq!(options.access_token.as_deref(), Some("token"));
+        assert_eq!(options.language, Some(Language::ZH_HK));
+        assert_eq!(
+            options.push_candlestick_mode,
+            Some(PushCandlestickMode::Confirmed)
+        );
+        assert_eq!(options.log_path, Some(PathBuf::from("/var/log/longport")));
+        assert_eq!(options.log_rotation, Some(LogRotation::Size(100 << 20)));
+        assert_eq!(options.log_max_files, Some(10));
+        assert_eq!(options.enable_overnight, None);
+        assert_eq!(options.region, None);
+
+        let options = load("toml", TOML, Some("paper")).unwrap();
+        assert_eq!(options.app_key.as_deref(), Some("key"));
+        assert_eq!(options.access_token.as_deref(), Some("paper-token"));
+        assert_eq!(options.enable_overnight, Some(true));
+
+        let options = load("toml", TOML, Some("cn")).unwrap();
+        assert_eq!(options.access_token.as_deref(), Some("token"));
+        assert_eq!(options.region, Some(Region::CN));
+        assert_eq!(options.print_quote_packages, Some(false));
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn test_yaml() {
+        let options = load(
+            "yaml",
+            r#"
+app_key: key
+quote_mux_path: /tmp/quote.sock
+log_format: json
+log_redaction: false
+profiles:
+  live:
+    proxy: socks5h://127.0.0.1:1080
+"#,
+            Some("live"),
+        )
+        .unwrap();
+        assert_eq!(options.app_key.as_deref(), Some("key"));
+        assert_eq!(
+            options.quote_mux_path,
+            Some(PathBuf::from("/tmp/quote.sock"))
+        );
+        assert_eq!(options.proxy.as_deref(), Some("socks5h://127.0.0.1:1080"));
+        assert_eq!(options.log_format, Some(LogFormat::Json));
+        assert_eq!(options.log_redaction, Some(false));
+
+        assert_eq!(load("yml", "", None).unwrap(), ConfigOptions::default());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            error_message(load("toml", "language = \"fr\"", None)),
+            "`language`: expected `zh-CN`, `zh-HK` or `en`, found `\"fr\"`"
+        );
+        assert_eq!(
+            error_message(load(
+                "toml",
+                "[profiles.paper]\nenable_overnight = \"yes\"",
+                None
+            )),
+            "`profiles.paper.enable_overnight`: expected `true` or `false`, found `\"yes\"`"
+        );
+        assert_eq!(
+            error_message(load("toml", "log_max_files = -1", None)),
+            "`log_max_files`: expected a non-negative integer, found `-1`"
+        );
+        assert_eq!(
+            error_message(load("toml", "app_keys = \"key\"", None)),
+            "`app_keys`: unknown key"
+        );
+        assert_eq!(
+            error_message(load("toml", TOML, Some("live"))),
+            "profile `live` not found"
+        );
+        #[cfg(feature = "yaml")]
+        assert_eq!(
+            error_message(load("yaml", "- a\n- b", None)),
+            "expected a table at the top level"
+        );
+        #[cfg(not(feature = "yaml"))]
+        assert_eq!(
+            error_message(load("yaml", "app_key: key", None)),
+            "YAML config files require the `yaml` feature"
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let file = ConfigOptions {
+            app_key: Some("file-key".to_string()),
+            app_secret: Some("file-secret".to_string()),
+            language: Some(Language::ZH_CN),
+            ..Default::default()
+        };
+        let env = ConfigOptions {
+            app_key: Some("env-key".to_string()),
+            access_token: Some("env-token".to_string()),
+            ..Default::default()
+        };
+        let mut options = file.merge(env);
+        assert_eq!(options.language, Some(Language::ZH_CN));
+
+        let credentials = options.take_credentials(Path::new("config.toml")).unwrap();
+        assert_eq!(credentials.app_key, "env-key");
+        assert_eq!(credentials.app_secret, "file-secret");
+        assert_eq!(credentials.access_token, "env-token");
+
+        let mut options = ConfigOptions::default();
+        assert!(options.take_credentials(Path::new("config.toml")).is_err());
+    }
+}
@@ -0,0 +1,415 @@
+//! Credential providers
+
+use std::{
+    ffi::OsString,
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
+
+pub use longport_httpcli::Credentials;
+use serde::Deserialize;
+
+use crate::{Error, Result};
+
+/// A source of the [`Credentials`]
+///
+/// The provider is called when creating a [`Config`](crate::Config) with
+/// [`Config::from_credential_provider`](crate::Config::from_credential_provider),
+/// and again before every refresh of the access token by
+/// [`TokenManager`](crate::TokenManager), so that a rotated app secret is
+/// picked up without restarting the process.
+pub trait CredentialProvider: Debug + Send + Sync + 'static {
+    /// Returns the credentials
+    fn credentials(&self) -> Result<Credentials>;
+}
+
+#[derive(Deserialize)]
+struct CredentialsFile {
+    app_key: String,
+    app_secret: String,
+    access_token: String,
+}
+
+impl From<CredentialsFile> for Credentials {
+    fn from(file: CredentialsFile) -> Self {
+        Credentials {
+            app_key: file.app_key,
+            app_secret: file.app_secret,
+            access_token: file.access_token,
+        }
+    }
+}
+
+/// A [`CredentialProvider`] which reads the credentials from the environment
+/// variables, and the `.env` file in the current directory
+///
+/// # Variables
+///
+/// - `LONGPORT_APP_KEY` - App key
+/// - `LONGPORT_APP_SECRET` - App secret
+/// - `LONGPORT_ACCESS_TOKEN` - Access token
+#[derive(Debug, Default, Copy, Clone)]
+pub struct EnvCredentialProvider;
+
+impl CredentialProvider for EnvCredentialProvider {
+    fn credentials(&self) -> Result<Credentials> {
+        let _ = dotenv::dotenv();
+
+        let var = |name: &'static str| {
+            std::env::var(name)
+                .map_err(|_| longport_httpcli::HttpClientError::MissingEnvVar { name })
+        };
+        Ok(Credentials {
+            app_key: var("LONGPORT_APP_KEY")?,
+            app_secret: var("LONGPORT_APP_SECRET")?,
+            access_token: var("LONGPORT_ACCESS_TOKEN")?,
+        })
+    }
+}
+
+/// A [`CredentialProvider`] which reads the credentials from a TOML or JSON
+/// file
+///
+/// The file is parsed as JSON if its extension is `.json`, otherwise as
+/// TOML, and it must contain the `app_key`, `app_secret` and `access_token`
+/// fields:
+///
+/// ```toml
+/// app_key = "..."
+/// app_secret = "..."
+/// access_token = "..."
+/// ```
+///
+/// On unix platforms, the file is rejected if it is accessible by the group
+/// or other users, i.e. the permissions must be `0600` or stricter.
+#[derive(Debug, Clone)]
+pub struct FileCredentialProvider {
+    path: PathBuf,
+}
+
+impl FileCredentialProvider {
+    /// Create a new `FileCredentialProvider`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CredentialProvider for FileCredentialProvider {
+    fn credentials(&self) -> Result<Credentials> {
+        check_permissions(&self.path)?;
+        let data = std::fs::read_to_string(&self.path)?;
+        let file: CredentialsFile = if self.path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&data)?
+        } else {
+            toml::from_str(&data)
+                .map_err(|err| Error::Credential(format!("{}: {}", self.path.display(), err)))?
+        };
+        Ok(file.into())
+    }
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(Error::Credential(format!(
+            "{} is accessible by other users (mode {:o}), run `chmod 600 {}`",
+            path.display(),
+            mode & 0o777,
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// A [`CredentialProvider`] which executes a command and reads the credentials
+/// from its standard output
+///
+/// The command must print a JSON object which contains the `app_key`,
+/// `app_secret` and `access_token` fields, and exit with status `0`. It is
+/// useful to fetch the credentials from a secret manager, e.g. `vault kv get
+/// -format=json -field=data secret/longport`.
+#[derive(Debug, Clone)]
+pub struct CommandCredentialProvider {
+    program: OsString,
+    args: Vec<OsString>,
+}
+
+impl CommandCredentialProvider {
+    /// Create a new `CommandCredentialProvider` which executes `program`
+    pub fn new(program: impl Into<OsString>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds an argument to pass to the program
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds multiple arguments to pass to the program
+    #[must_use]
+    pub fn args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+}
+
+impl CredentialProvider for CommandCredentialProvider {
+    fn credentials(&self) -> Result<Credentials> {
+        let output = std::process::Command::new(&self.program)
+            .args(&self.args)
+            .stdin(std::process::Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(Error::Credential(format!(
+                "command `{}` exited with {}: {}",
+                self.program.to_string_lossy(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let file: CredentialsFile = serde_json::from_slice(&output.stdout)?;
+        Ok(file.into())
+    }
+}
+
+#[cfg(feature = "encrypted-credentials")]
+pub use encrypted::EncryptedFileCredentialProvider;
+
+#[cfg(feature = "encrypted-credentials")]
+mod encrypted {
+    use std::path::PathBuf;
+
+    use aes_gcm::{
+        Aes256Gcm, KeyInit, Nonce,
+        aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+    };
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use serde::{Deserialize, Serialize};
+    use sha2::Sha256;
+
+    use super::{CredentialProvider, CredentialsFile};
+    use crate::{Error, Result, credentials::Credentials};
+
+    // keep the tests fast, the iterations are saved in the file anyway
+    const KDF_ITERATIONS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+    const SALT_LEN: usize = 16;
+
+    #[derive(Serialize, Deserialize)]
+    struct EncryptedFile {
+        version: u32,
+        iterations: u32,
+        salt: String,
+        nonce: String,
+        ciphertext: String,
+    }
+
+    fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Aes256Gcm {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+        Aes256Gcm::new(&key.into())
+    }
+
+    fn decode_base64(name: &str, value: &str) -> Result<Vec<u8>> {
+        STANDARD
+            .decode(value)
+            .map_err(|err| Error::Credential(format!("invalid {name}: {err}")))
+    }
+
+    /// A [`CredentialProvider`] which reads the credentials from a file
+    /// encrypted with a passphrase
+    ///
+    /// The file is encrypted with AES-256-GCM, and the key is derived from the
+    /// passphrase with PBKDF2-HMAC-SHA256. Use
+    /// [`EncryptedFileCredentialProvider::save`] to create the file.
+    #[cfg_attr(docsrs, doc(cfg(feature = "encrypted-credentials")))]
+    #[derive(Clone)]
+    pub struct EncryptedFileCredentialProvider {
+        path: PathBuf,
+        passphrase: String,
+    }
+
+    impl std::fmt::Debug for EncryptedFileCredentialProvider {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("EncryptedFileCredentialProvider")
+                .field("path", &self.path)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl EncryptedFileCredentialProvider {
+        /// Create a new `EncryptedFileCredentialProvider`
+        pub fn new(path: impl Into<PathBuf>, passphrase: impl Into<String>) -> Self {
+            Self {
+                path: path.into(),
+                passphrase: passphrase.into(),
+            }
+        }
+
+        /// Create a new `EncryptedFileCredentialProvider`, the passphrase is
+        /// read from the `LONGPORT_CREDENTIALS_PASSPHRASE` environment
+        /// variable
+        pub fn from_env(path: impl Into<PathBuf>) -> Result<Self> {
+            let passphrase = std::env::var("LONGPORT_CREDENTIALS_PASSPHRASE").map_err(|_| {
+                longport_httpcli::HttpClientError::MissingEnvVar {
+                    name: "LONGPORT_CREDENTIALS_PASSPHRASE",
+                }
+            })?;
+            Ok(Self::new(path, passphrase))
+        }
+
+        /// Encrypt `credentials` and save them to the file
+        pub fn save(&self, credentials: &Credentials) -> Result<()> {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+            let plaintext = serde_json::json!({
+                "app_key": credentials.app_key,
+                "app_secret": credentials.app_secret,
+                "access_token": credentials.access_token,
+            })
+            .to_string();
+            let ciphertext = derive_key(&self.passphrase, &salt, KDF_ITERATIONS)
+                .encrypt(&nonce, plaintext.as_bytes())
+                .map_err(|_| Error::Credential("failed to encrypt credentials".to_string()))?;
+
+            let file = EncryptedFile {
+                version: 1,
+                iterations: KDF_ITERATIONS,
+                salt: STANDARD.encode(salt),
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+            };
+            std::fs::write(&self.path, serde_json::to_vec_pretty(&file)?)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl CredentialProvider for EncryptedFileCredentialProvider {
+        fn credentials(&self) -> Result<Credentials> {
+            let file: EncryptedFile = serde_json::from_slice(&std::fs::read(&self.path)?)?;
+            if file.version != 1 {
+                return Err(Error::Credential(format!(
+                    "unsupported encrypted credentials version: {}",
+                    file.version
+                )));
+            }
+            let salt = decode_base64("salt", &file.salt)?;
+            let nonce = decode_base64("nonce", &file.nonce)?;
+            if nonce.len() != 12 {
+                return Err(Error::Credential("invalid nonce".to_string()));
+            }
+            let ciphertext = decode_base64("ciphertext", &file.ciphertext)?;
+            let plaintext = derive_key(&self.passphrase, &salt, file.iterations)
+                .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| {
+                    Error::Credential(format!(
+                        "failed to decrypt {}, wrong passphrase?",
+                        self.path.display()
+                    ))
+                })?;
+            let file: CredentialsFile = serde_json::from_slice(&plaintext)?;
+            Ok(file.into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("longport-{}-{}", std::process::id(), name))
+    }
+
+    fn expected() -> Credentials {
+        Credentials {
+            app_key: "key".to_string(),
+            app_secret: "secret".to_string(),
+            access_token: "token".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_file_provider() {
+        let path = temp_path("credentials.toml");
+        std::fs::write(
+            &path,
+            "app_key = \"key\"\napp_secret = \"secret\"\naccess_token = \"token\"\n",
+        )
+        .unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            assert!(matches!(
+                FileCredentialProvider::new(&path).credentials(),
+                Err(Error::Credential(_))
+            ));
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        assert_eq!(
+            FileCredentialProvider::new(&path).credentials().unwrap(),
+            expected()
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_command_provider() {
+        let provider = CommandCredentialProvider::new("sh").args([
+            "-c",
+            r#"echo '{"app_key":"key","app_secret":"secret","access_token":"token"}'"#,
+        ]);
+        assert_eq!(provider.credentials().unwrap(), expected());
+
+        let provider = CommandCredentialProvider::new("sh").args(["-c", "exit 1"]);
+        assert!(matches!(provider.credentials(), Err(Error::Credential(_))));
+    }
+
+    #[cfg(feature = "encrypted-credentials")]
+    #[test]
+    fn test_encrypted_file_provider() {
+        let path = temp_path("credentials.enc");
+        EncryptedFileCredentialProvider::new(&path, "passphrase")
+            .save(&expected())
+            .unwrap();
+
+        assert_eq!(
+            EncryptedFileCredentialProvider::new(&path, "passphrase")
+                .credentials()
+                .unwrap(),
+            expected()
+        );
+        assert!(matches!(
+            EncryptedFileCredentialProvider::new(&path, "wrong").credentials(),
+            Err(Error::Credential(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,8 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 
 use longport_httpcli::HttpClientError;
 use longport_wscli::WsClientError;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
+
+use crate::{Market, quote::TradeSession};
 
 /// LongPort OpenAPI SDK error type
 #[derive(Debug, thiserror::Error)]
@@ -55,6 +57,29 @@ pub enum Error {
         time: OffsetDateTime,
     },
 
+    /// The market has no such trade session
+    #[error("no {trade_session:?} session in market {market}")]
+    NoTradeSession {
+        /// Market
+        market: Market,
+        /// Trade session
+        trade_session: TradeSession,
+    },
+
+    /// Unknown account
+    #[error("unknown account: {account_id}")]
+    UnknownAccount {
+        /// Account id
+        account_id: String,
+    },
+
+    /// Duplicate account
+    #[error("duplicate account: {account_id}")]
+    DuplicateAccount {
+        /// Account id
+        account_id: String,
+    },
+
     /// HTTP client error
     #[error(transparent)]
     HttpClient(#[from] HttpClientError),
@@ -63,6 +88,59 @@ pub enum Error {
     #[error(transparent)]
     WsClient(#[from] WsClientError),
 
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Credential provider error
+    #[error("credential provider error: {0}")]
+    Credential(String),
+
+    /// Invalid config file
+    #[error("invalid config file `{}`: {message}", path.display())]
+    ConfigFile {
+        /// The path of the file
+        path: PathBuf,
+        /// The reason, which starts with the offending key if any
+        message: String,
+    },
+
+    /// The circuit breaker of the quote websocket requests is open, the
+    /// request is not sent
+    #[error("circuit breaker is open")]
+    CircuitOpen,
+
+    /// The call is not completed before the deadline of the call options
+    #[error("call timeout")]
+    Timeout,
+
+    /// The call is cancelled by the cancellation token of the call options
+    #[error("call cancelled")]
+    Cancelled,
+
+    /// The token manager is stopped
+    #[error("token manager closed")]
+    TokenManagerClosed,
+
+    /// Invalid corporate action
+    #[error("invalid corporate action on {ex_date}: {reason}")]
+    InvalidCorporateAction {
+        /// The ex date of the action
+        ex_date: Date,
+        /// The reason
+        reason: &'static str,
+    },
+
+    /// Metrics error
+    #[cfg(feature = "prometheus")]
+    #[error("metrics error: {0}")]
+    Metrics(String),
+
+    /// Arrow or Parquet error
+    #[cfg(feature = "arrow")]
+    #[error("arrow error: {0}")]
+    Arrow(String),
+
     /// Blocking error
     #[cfg(feature = "blocking")]
     #[error(transparent)]
@@ -78,6 +156,86 @@ impl Error {
         }
     }
 
+    /// Returns the kind of the error, [`ErrorKind::Other`] if it is not a
+    /// known OpenAPI error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::HttpClient(HttpClientError::BadStatus(status)) if status.as_u16() == 429 => {
+                ErrorKind::RateLimited
+            }
+            _ => self
+                .openapi_error_code()
+                .map(ErrorKind::from_code)
+                .unwrap_or(ErrorKind::Other),
+        }
+    }
+
+    /// Returns `true` if the request may succeed when it is sent again, e.g.
+    /// it is rate limited, timed out or the connection is lost
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::HttpClient(
+                HttpClientError::RequestTimeout | HttpClientError::ConnectionLimitExceeded { .. },
+            ) => true,
+            Error::HttpClient(HttpClientError::BadStatus(status)) => {
+                status.as_u16() == 429 || status.is_server_error()
+            }
+            Error::HttpClient(HttpClientError::Http(err)) => {
+                err.0.is_timeout() || err.0.is_connect()
+            }
+            Error::WsClient(
+                WsClientError::ConnectTimeout
+                | WsClientError::RequestTimeout
+                | WsClientError::ConnectionClosed { .. }
+                | WsClientError::Cancelled
+                | WsClientError::Websocket(_),
+            ) => true,
+            _ => self.kind().is_retryable(),
+        }
+    }
+
+    /// Returns `true` if the websocket request failed because the server is
+    /// unreachable
+    pub(crate) fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Error::WsClient(
+                WsClientError::ConnectTimeout
+                    | WsClientError::RequestTimeout
+                    | WsClientError::ConnectionClosed { .. }
+                    | WsClientError::Websocket(_),
+            )
+        )
+    }
+
+    /// Returns `true` if the credentials are invalid or expired, or have no
+    /// permission for the request
+    pub fn is_auth(&self) -> bool {
+        match self {
+            Error::HttpClient(
+                HttpClientError::InvalidApiKey | HttpClientError::InvalidAccessToken,
+            )
+            | Error::Credential(_) => true,
+            Error::HttpClient(HttpClientError::BadStatus(status)) => {
+                matches!(status.as_u16(), 401 | 403)
+            }
+            _ => self.kind().is_auth(),
+        }
+    }
+
+    /// Returns the trace id of the failed HTTP request, which identifies the
+    /// request when reporting a problem to LongPort
+    pub fn trace_id(&self) -> Option<&str> {
+        match self {
+            Error::HttpClient(HttpClientError::OpenApi { trace_id, .. })
+                if !trace_id.is_empty() =>
+            {
+                Some(trace_id)
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the OpenAPI error code
     pub fn openapi_error_code(&self) -> Option<i64> {
         match self {
@@ -114,16 +272,91 @@ impl Error {
             | Error::InvalidSecuritySymbol { .. }
             | Error::UnknownMarket { .. }
             | Error::UnknownTradeSession { .. }
+            | Error::UnknownAccount { .. }
+            | Error::DuplicateAccount { .. }
+            | Error::NoTradeSession { .. }
             | Error::ParseField { .. }
             | Error::UnknownCommand(_)
             | Error::HttpClient(_)
-            | Error::WsClient(_) => SimpleError::Other(self.to_string()),
+            | Error::WsClient(_)
+            | Error::Io(_)
+            | Error::Credential(_)
+            | Error::ConfigFile { .. }
+            | Error::CircuitOpen
+            | Error::Timeout
+            | Error::Cancelled
+            | Error::TokenManagerClosed
+            | Error::InvalidCorporateAction { .. } => SimpleError::Other(self.to_string()),
+            #[cfg(feature = "prometheus")]
+            Error::Metrics(_) => SimpleError::Other(self.to_string()),
+            #[cfg(feature = "arrow")]
+            Error::Arrow(_) => SimpleError::Other(self.to_string()),
             #[cfg(feature = "blocking")]
             Error::Blocking(_) => SimpleError::Other(self.to_string()),
         }
     }
 }
 
+/// The kind of an [`Error`], mapped from the known OpenAPI error codes
+///
+/// | Kind | Codes |
+/// |------|-------|
+/// | `InsufficientBuyingPower` | `602035` |
+/// | `MarketClosed` | `602019` |
+/// | `InvalidPriceTick` | `602023` |
+/// | `RateLimited` | `301606`, `429001`, `429002`, HTTP status `429` |
+/// | `TokenExpired` | `401003`, `401004` |
+/// | `PermissionDenied` | `301604`, `403201` |
+///
+/// Reference: <https://open.longportapp.com/en/docs/error-codes>
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The buying power is insufficient for the order
+    InsufficientBuyingPower,
+    /// The market is closed
+    MarketClosed,
+    /// The price does not match the tick size of the security
+    InvalidPriceTick,
+    /// Too many requests
+    RateLimited,
+    /// The access token is expired
+    TokenExpired,
+    /// The account has no permission for the request
+    PermissionDenied,
+    /// Other errors
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns the kind of an OpenAPI error code, the codes are listed in the
+    /// error codes of the OpenAPI documentation
+    ///
+    /// Reference: <https://open.longportapp.com/en/docs/error-codes>
+    pub fn from_code(code: i64) -> Self {
+        match code {
+            602035 => ErrorKind::InsufficientBuyingPower,
+            602019 => ErrorKind::MarketClosed,
+            602023 => ErrorKind::InvalidPriceTick,
+            301606 | 429001 | 429002 => ErrorKind::RateLimited,
+            401003 | 401004 => ErrorKind::TokenExpired,
+            301604 | 403201 => ErrorKind::PermissionDenied,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Returns `true` if the request may succeed when it is sent again
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorKind::RateLimited)
+    }
+
+    /// Returns `true` if the error is caused by the credentials
+    #[inline]
+    pub fn is_auth(&self) -> bool {
+        matches!(self, ErrorKind::TokenExpired | ErrorKind::PermissionDenied)
+    }
+}
+
 /// LongPort OpenAPI SDK result type
 pub type Result<T> = ::std::result::Result<T, Error>;
 
@@ -177,3 +410,56 @@ impl SimpleError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use longport_wscli::WsResponseErrorDetail;
+
+    use super::*;
+
+    fn openapi_error(code: i32) -> Error {
+        Error::HttpClient(HttpClientError::OpenApi {
+            code,
+            message: "error".to_string(),
+            trace_id: "trace".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let err = openapi_error(429002);
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert!(err.is_retryable());
+        assert!(!err.is_auth());
+        assert_eq!(err.trace_id(), Some("trace"));
+
+        let err = openapi_error(401003);
+        assert_eq!(err.kind(), ErrorKind::TokenExpired);
+        assert!(!err.is_retryable());
+        assert!(err.is_auth());
+
+        let err = Error::WsClient(WsClientError::ResponseError {
+            status: 3,
+            detail: Some(WsResponseErrorDetail {
+                code: 301604,
+                msg: "no permission".to_string(),
+            }),
+        });
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(err.is_auth());
+        assert_eq!(err.trace_id(), None);
+
+        assert_eq!(
+            openapi_error(602035).kind(),
+            ErrorKind::InsufficientBuyingPower
+        );
+        assert_eq!(openapi_error(1).kind(), ErrorKind::Other);
+
+        let err = Error::HttpClient(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(err.kind(), ErrorKind::RateLimited);
+        assert!(err.is_retryable());
+        assert!(Error::WsClient(WsClientError::RequestTimeout).is_retryable());
+        assert!(!Error::UnknownCommand(1).is_retryable());
+    }
+}
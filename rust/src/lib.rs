@@ -8,22 +8,38 @@
 #[macro_use]
 mod macros;
 
+mod call_options;
 mod config;
+mod config_file;
 mod error;
+mod logger;
 mod serde_utils;
+mod telemetry;
 mod types;
 
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
 #[cfg(feature = "blocking")]
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 pub mod blocking;
+pub mod credentials;
+pub mod metrics;
 pub mod quote;
+pub mod token;
 pub mod trade;
 
-pub use config::{Config, Language, PushCandlestickMode};
-pub use error::{Error, Result, SimpleError};
+pub use call_options::CallOptions;
+pub use config::{
+    Config, Endpoints, Language, PushCandlestickMode, Region, RegionReason, RegionResolution,
+};
+pub use error::{Error, ErrorKind, Result, SimpleError};
+pub use logger::{LogFormat, LogRotation};
 pub use longport_httpcli as httpclient;
 pub use longport_wscli as wsclient;
 pub use quote::QuoteContext;
 pub use rust_decimal::Decimal;
+pub use token::TokenManager;
+pub use tokio_util::sync::CancellationToken;
 pub use trade::TradeContext;
 pub use types::Market;
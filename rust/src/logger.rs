@@ -0,0 +1,269 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+};
+
+use tracing::{Level, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{Layer, filter::Targets, fmt::MakeWriter, registry::LookupSpan};
+
+/// Log format
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Log file rotation
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum LogRotation {
+    /// A new file every hour
+    Hourly,
+    /// A new file every day
+    #[default]
+    Daily,
+    /// A new file when the current file exceeds the size in bytes
+    Size(u64),
+    /// A single file
+    Never,
+}
+
+impl FromStr for LogRotation {
+    type Err = ();
+
+    /// Parses `hourly`, `daily`, `never` or a size, e.g. `100MB`, `512KB`
+    /// or `1GB`
+    fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+        match s {
+            "hourly" => Ok(LogRotation::Hourly),
+            "daily" => Ok(LogRotation::Daily),
+            "never" => Ok(LogRotation::Never),
+            _ => {
+                let upper = s.to_ascii_uppercase();
+                let upper = upper.strip_suffix('B').unwrap_or(&upper);
+                let (digits, unit) = match upper.char_indices().last() {
+                    Some((idx, 'K')) => (&upper[..idx], 1 << 10),
+                    Some((idx, 'M')) => (&upper[..idx], 1 << 20),
+                    Some((idx, 'G')) => (&upper[..idx], 1 << 30),
+                    _ => (upper, 1),
+                };
+                match digits
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|size| size.checked_mul(unit))
+                {
+                    Some(size) if size > 0 => Ok(LogRotation::Size(size)),
+                    _ => Err(()),
+                }
+            }
+        }
+    }
+}
+
+/// Parses a log level, e.g. `debug`, which applies to the targets of the
+/// SDK, or comma separated `target=level` directives, e.g.
+/// `longport=info,longport_httpcli=debug`
+pub(crate) fn parse_log_filter(s: &str) -> Option<Targets> {
+    match s.parse::<Level>() {
+        Ok(level) => Some(default_log_filter(level)),
+        Err(_) => s.parse().ok(),
+    }
+}
+
+pub(crate) fn default_log_filter(level: Level) -> Targets {
+    Targets::new().with_target("longport", level)
+}
+
+/// Creates the layer writing the logs to the files in `dir`
+pub(crate) fn file_layer<S>(
+    dir: &Path,
+    format: LogFormat,
+    rotation: LogRotation,
+    max_files: Option<usize>,
+) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let rotation = match rotation {
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+        LogRotation::Size(max_size) => {
+            let file = SizeRollingFile::new(dir, max_size, max_files).ok()?;
+            return Some(fmt_layer(Mutex::new(file), format));
+        }
+    };
+
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation.clone())
+        .filename_suffix("log");
+    if rotation == Rotation::NEVER {
+        builder = builder.filename_prefix("longport");
+    }
+    if let Some(max_files) = max_files {
+        builder = builder.max_log_files(max_files);
+    }
+    Some(fmt_layer(builder.build(dir).ok()?, format))
+}
+
+fn fmt_layer<S, W>(writer: W, format: LogFormat) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false);
+    match format {
+        LogFormat::Text => Box::new(layer),
+        LogFormat::Json => Box::new(layer.json()),
+    }
+}
+
+/// A log file which is rotated when it exceeds `max_size` bytes
+///
+/// The logs are written to `longport.log`, the rotated files are
+/// `longport.1.log`, `longport.2.log`, ..., the larger the number the older
+/// the file. At most `max_files` files are kept, including the current file.
+struct SizeRollingFile {
+    dir: PathBuf,
+    max_size: u64,
+    max_files: Option<usize>,
+    file: File,
+    size: u64,
+}
+
+impl SizeRollingFile {
+    fn new(dir: &Path, max_size: u64, max_files: Option<usize>) -> io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let dir = dir.to_path_buf();
+        let file = Self::open(&dir)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    fn path(dir: &Path, index: usize) -> PathBuf {
+        match index {
+            0 => dir.join("longport.log"),
+            _ => dir.join(format!("longport.{index}.log")),
+        }
+    }
+
+    fn open(dir: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::path(dir, 0))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        // the number of rotated files to keep
+        let keep = self.max_files.map(|max_files| max_files.max(1) - 1);
+        let mut last = 0;
+        while Self::path(&self.dir, last + 1).exists() {
+            last += 1;
+        }
+        for index in (0..=last).rev() {
+            let path = Self::path(&self.dir, index);
+            if keep.is_some_and(|keep| index >= keep) {
+                std::fs::remove_file(path)?;
+            } else {
+                std::fs::rename(path, Self::path(&self.dir, index + 1))?;
+            }
+        }
+
+        self.file = Self::open(&self.dir)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_rotation() {
+        assert_eq!("daily".parse(), Ok(LogRotation::Daily));
+        assert_eq!("100MB".parse(), Ok(LogRotation::Size(100 << 20)));
+        assert_eq!("512k".parse(), Ok(LogRotation::Size(512 << 10)));
+        assert_eq!("1G".parse(), Ok(LogRotation::Size(1 << 30)));
+        assert_eq!("4096".parse(), Ok(LogRotation::Size(4096)));
+        assert_eq!("0MB".parse::<LogRotation>(), Err(()));
+        assert_eq!("weekly".parse::<LogRotation>(), Err(()));
+    }
+
+    #[test]
+    fn test_parse_log_filter() {
+        assert_eq!(
+            parse_log_filter("debug"),
+            Some(default_log_filter(Level::DEBUG))
+        );
+        let filter = parse_log_filter("longport=info,longport_httpcli=debug").unwrap();
+        assert!(filter.would_enable("longport_httpcli::request", &Level::DEBUG));
+        assert!(!filter.would_enable("longport::quote", &Level::DEBUG));
+        assert_eq!(parse_log_filter("longport=loud"), None);
+    }
+
+    #[test]
+    fn test_size_rolling_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "longport-log-{}-{}",
+            std::process::id(),
+            time::OffsetDateTime::now_utc().unix_timestamp_nanos()
+        ));
+        let mut file = SizeRollingFile::new(&dir, 10, Some(3)).unwrap();
+        for line in ["aaaaaaaa\n", "bbbbbbbb\n", "cccccccc\n", "dddddddd\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let read = |index| std::fs::read_to_string(SizeRollingFile::path(&dir, index)).ok();
+        assert_eq!(read(0).as_deref(), Some("dddddddd\n"));
+        assert_eq!(read(1).as_deref(), Some("cccccccc\n"));
+        assert_eq!(read(2).as_deref(), Some("bbbbbbbb\n"));
+        assert_eq!(read(3), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
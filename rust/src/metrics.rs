@@ -0,0 +1,130 @@
+//! Metrics of the SDK
+//!
+//! The metrics are recorded through the [`metrics`](https://docs.rs/metrics)
+//! facade, they are no-op until a recorder is installed, either by the
+//! application or with [`install_prometheus_exporter`] (requires the
+//! `prometheus` feature).
+//!
+//! # Metrics
+//!
+//! | Name | Type | Labels |
+//! |------|------|--------|
+//! | `longport_http_requests_total` | counter | `method`, `path`, `status` |
+//! | `longport_http_request_duration_seconds` | histogram | `method`, `path`, `status` |
+//! | `longport_http_rate_limit_wait_seconds` | histogram | `method`, `path` |
+//! | `longport_http_rate_limit_queue_depth` | gauge | `method`, `path` |
+//! | `longport_ws_request_duration_seconds` | histogram | `command`, `status` |
+//! | `longport_ws_inflight_requests` | gauge | |
+//! | `longport_ws_rate_limit_wait_seconds` | histogram | `command` |
+//! | `longport_push_messages_total` | counter | `context`, `type` |
+//! | `longport_ws_reconnect_attempts_total` | counter | `context` |
+//! | `longport_ws_reconnects_total` | counter | `context` |
+//!
+//! The `status` of HTTP requests is the HTTP status code, or `timeout` and
+//! `error` if no response was received. The `context` is `quote` or `trade`.
+
+#[cfg(feature = "prometheus")]
+use std::net::SocketAddr;
+
+use metrics::{counter, describe_counter};
+#[cfg(feature = "prometheus")]
+pub use metrics_exporter_prometheus::PrometheusHandle;
+
+#[cfg(feature = "prometheus")]
+use crate::{Error, Result};
+
+/// The number of push messages received from the server, labeled by
+/// `context` and `type`
+pub const PUSH_MESSAGES_TOTAL: &str = "longport_push_messages_total";
+/// The number of attempts to reconnect to the server, labeled by `context`
+pub const WS_RECONNECT_ATTEMPTS_TOTAL: &str = "longport_ws_reconnect_attempts_total";
+/// The number of successful reconnections to the server, labeled by
+/// `context`
+pub const WS_RECONNECTS_TOTAL: &str = "longport_ws_reconnects_total";
+
+#[cfg(feature = "prometheus")]
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Register the descriptions of all the metrics with the installed recorder
+///
+/// It is called by [`install_prometheus_exporter`] and
+/// [`install_prometheus_recorder`], call it after installing another recorder.
+pub fn describe_metrics() {
+    longport_httpcli::metrics::describe_metrics();
+    longport_wscli::metrics::describe_metrics();
+    describe_counter!(
+        PUSH_MESSAGES_TOTAL,
+        "The number of push messages received from the server"
+    );
+    describe_counter!(
+        WS_RECONNECT_ATTEMPTS_TOTAL,
+        "The number of attempts to reconnect to the server"
+    );
+    describe_counter!(
+        WS_RECONNECTS_TOTAL,
+        "The number of successful reconnections to the server"
+    );
+}
+
+pub(crate) fn record_push(context: &'static str, ty: &'static str) {
+    counter!(PUSH_MESSAGES_TOTAL, "context" => context, "type" => ty).increment(1);
+}
+
+pub(crate) fn record_reconnect_attempt(context: &'static str) {
+    counter!(WS_RECONNECT_ATTEMPTS_TOTAL, "context" => context).increment(1);
+}
+
+pub(crate) fn record_reconnect(context: &'static str) {
+    counter!(WS_RECONNECTS_TOTAL, "context" => context).increment(1);
+}
+
+#[cfg(feature = "prometheus")]
+fn prometheus_builder() -> Result<metrics_exporter_prometheus::PrometheusBuilder> {
+    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".to_string()), DURATION_BUCKETS)
+        .map_err(|err| Error::Metrics(err.to_string()))
+}
+
+/// Install a Prometheus recorder as the global recorder, and serve the
+/// metrics in the Prometheus text format over HTTP on `addr`
+///
+/// When called within a Tokio runtime the HTTP listener runs on it,
+/// otherwise it runs on a background thread.
+///
+/// # Examples
+///
+/// ```no_run
+/// use longport::metrics::install_prometheus_exporter;
+///
+/// install_prometheus_exporter(([127, 0, 0, 1], 9000).into())?;
+/// # Ok::<_, longport::Error>(())
+/// ```
+#[cfg(feature = "prometheus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+pub fn install_prometheus_exporter(addr: SocketAddr) -> Result<()> {
+    prometheus_builder()?
+        .with_http_listener(addr)
+        .install()
+        .map_err(|err| Error::Metrics(err.to_string()))?;
+    describe_metrics();
+    Ok(())
+}
+
+/// Install a Prometheus recorder as the global recorder, and returns a
+/// handle to render the metrics in the Prometheus text format
+///
+/// Use it to serve the metrics from an existing HTTP server. The caller is
+/// responsible for calling [`PrometheusHandle::run_upkeep`] periodically.
+#[cfg(feature = "prometheus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+pub fn install_prometheus_recorder() -> Result<PrometheusHandle> {
+    let handle = prometheus_builder()?
+        .install_recorder()
+        .map_err(|err| Error::Metrics(err.to_string()))?;
+    describe_metrics();
+    Ok(handle)
+}
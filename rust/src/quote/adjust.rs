@@ -0,0 +1,451 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime};
+use time_tz::{OffsetDateTimeExt, Tz};
+
+use crate::{
+    Error, Market, Result,
+    quote::{AdjustType, Candlestick, SecurityBoard, store::get_market},
+};
+
+/// Price adjustment of the candlesticks
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PriceAdjustment {
+    /// Actual prices
+    NoAdjust,
+    /// The prices before the corporate actions are adjusted to the latest
+    /// prices
+    ForwardAdjust,
+    /// The prices after the corporate actions are adjusted to the earliest
+    /// prices
+    BackwardAdjust,
+}
+
+impl From<AdjustType> for PriceAdjustment {
+    fn from(adjust_type: AdjustType) -> Self {
+        match adjust_type {
+            AdjustType::NoAdjust => PriceAdjustment::NoAdjust,
+            AdjustType::ForwardAdjust => PriceAdjustment::ForwardAdjust,
+        }
+    }
+}
+
+/// Corporate action kind
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CorporateActionKind {
+    /// Split, reverse split or bonus shares
+    Split {
+        /// The number of shares after the split for each share before, e.g.
+        /// `2` for a 2-for-1 split and `0.1` for a 1-for-10 reverse split
+        ratio: Decimal,
+    },
+    /// Cash dividend
+    ///
+    /// Without `prev_close` the amount is subtracted from the prices before
+    /// the ex date (the additive model), so the adjusted prices can be
+    /// negative if the prices were lower than the accumulated dividends. With
+    /// `prev_close` the prices are multiplied by `(prev_close - amount) /
+    /// prev_close` (the proportional model), which keeps them positive.
+    Dividend {
+        /// The amount per share, in the currency of the security
+        amount: Decimal,
+        /// The close price of the trading day before the ex date, selects the
+        /// proportional model
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prev_close: Option<Decimal>,
+    },
+    /// Rights issue
+    Rights {
+        /// The number of new shares for each existing share
+        ratio: Decimal,
+        /// The subscription price of the new shares
+        price: Decimal,
+    },
+}
+
+/// Corporate action
+///
+/// The JSON format is
+/// `{"ex_date": "2024-06-10", "type": "split", "ratio": "10"}`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CorporateAction {
+    /// Ex date, in the timezone of the market
+    pub ex_date: Date,
+    /// Kind
+    #[serde(flatten)]
+    pub kind: CorporateActionKind,
+}
+
+/// `price * a + b`
+#[derive(Debug, Copy, Clone)]
+struct Linear {
+    a: Decimal,
+    b: Decimal,
+}
+
+impl Linear {
+    const IDENTITY: Linear = Linear {
+        a: Decimal::ONE,
+        b: Decimal::ZERO,
+    };
+
+    /// Returns the forward adjustment of the prices before the action
+    fn of_action(action: &CorporateAction) -> Result<Self> {
+        let invalid = |reason| Error::InvalidCorporateAction {
+            ex_date: action.ex_date,
+            reason,
+        };
+        Ok(match action.kind {
+            CorporateActionKind::Split { ratio } => {
+                if ratio <= Decimal::ZERO {
+                    return Err(invalid("the ratio must be positive"));
+                }
+                Linear {
+                    a: Decimal::ONE / ratio,
+                    b: Decimal::ZERO,
+                }
+            }
+            CorporateActionKind::Dividend { amount, prev_close } => {
+                if amount < Decimal::ZERO {
+                    return Err(invalid("the dividend must not be negative"));
+                }
+                match prev_close {
+                    Some(prev_close) => {
+                        if prev_close <= amount {
+                            return Err(invalid(
+                                "the previous close must be greater than the dividend",
+                            ));
+                        }
+                        Linear {
+                            a: (prev_close - amount) / prev_close,
+                            b: Decimal::ZERO,
+                        }
+                    }
+                    None => Linear {
+                        a: Decimal::ONE,
+                        b: -amount,
+                    },
+                }
+            }
+            CorporateActionKind::Rights { ratio, price } => {
+                if ratio <= Decimal::ZERO {
+                    return Err(invalid("the ratio must be positive"));
+                }
+                let shares = Decimal::ONE + ratio;
+                Linear {
+                    a: Decimal::ONE / shares,
+                    b: ratio * price / shares,
+                }
+            }
+        })
+    }
+
+    /// Returns `other(self(price))`
+    fn then(self, other: Linear) -> Linear {
+        Linear {
+            a: other.a * self.a,
+            b: other.a * self.b + other.b,
+        }
+    }
+
+    fn inverse(self) -> Linear {
+        Linear {
+            a: Decimal::ONE / self.a,
+            b: -self.b / self.a,
+        }
+    }
+
+    #[inline]
+    fn apply(self, price: Decimal) -> Decimal {
+        price * self.a + self.b
+    }
+}
+
+/// Local price adjuster of the candlesticks
+///
+/// Converts the candlesticks of a security between
+/// [`PriceAdjustment::NoAdjust`], [`PriceAdjustment::ForwardAdjust`] and
+/// [`PriceAdjustment::BackwardAdjust`] with the corporate actions of the
+/// security, so that the cached or recorded candlesticks and the candlesticks
+/// of [`PushCandlestick`](crate::quote::PushCandlestick) can be mixed with the
+/// adjusted candlesticks of the server.
+///
+/// The open, high, low and close prices are adjusted, the volume and the
+/// turnover are not. The forward adjusted prices are consistent with the
+/// server only if the same corporate actions are supplied.
+///
+/// # Examples
+///
+/// ```
+/// use longport::{
+///     Market,
+///     quote::{CorporateAction, CorporateActionKind, PriceAdjuster, PriceAdjustment},
+/// };
+/// use time::macros::date;
+///
+/// let adjuster = PriceAdjuster::new(
+///     Market::US,
+///     [CorporateAction {
+///         ex_date: date!(2024 - 06 - 10),
+///         kind: CorporateActionKind::Split { ratio: 10.into() },
+///     }],
+/// )?;
+/// # let mut candlesticks: Vec<longport::quote::Candlestick> = vec![];
+/// adjuster.adjust(
+///     &mut candlesticks,
+///     PriceAdjustment::NoAdjust,
+///     PriceAdjustment::ForwardAdjust,
+/// );
+/// # Ok::<_, longport::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PriceAdjuster {
+    timezone: Option<&'static Tz>,
+    actions: Vec<CorporateAction>,
+    /// `forward[i]` is the forward adjustment of the prices before
+    /// `actions[i]`, `forward[actions.len()]` is the identity
+    forward: Vec<Linear>,
+    /// `backward[i]` is the inverse of the backward adjustment of the prices
+    /// after `actions[..i]`
+    backward: Vec<Linear>,
+}
+
+impl PriceAdjuster {
+    /// Create a `PriceAdjuster` with the corporate actions of a security
+    ///
+    /// The actions on the same ex date are applied in the order given.
+    pub fn new(market: Market, actions: impl IntoIterator<Item = CorporateAction>) -> Result<Self> {
+        let mut actions = actions.into_iter().collect::<Vec<_>>();
+        actions.sort_by_key(|action| action.ex_date);
+        let linears = actions
+            .iter()
+            .map(Linear::of_action)
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut forward = vec![Linear::IDENTITY; linears.len() + 1];
+        for (idx, linear) in linears.iter().enumerate().rev() {
+            forward[idx] = linear.then(forward[idx + 1]);
+        }
+        let mut backward = vec![Linear::IDENTITY; linears.len() + 1];
+        for (idx, linear) in linears.iter().enumerate() {
+            backward[idx + 1] = backward[idx].then(*linear);
+        }
+
+        Ok(Self {
+            timezone: get_market(market, SecurityBoard::Unknown).map(|market| market.timezone),
+            actions,
+            forward,
+            backward,
+        })
+    }
+
+    /// Returns the corporate actions, sorted by the ex date
+    #[inline]
+    pub fn actions(&self) -> &[CorporateAction] {
+        &self.actions
+    }
+
+    /// Returns the adjustment of the actual prices at the timestamp
+    fn linear(&self, timestamp: OffsetDateTime, adjustment: PriceAdjustment) -> Linear {
+        let date = match self.timezone {
+            Some(timezone) => timestamp.to_timezone(timezone).date(),
+            None => timestamp.date(),
+        };
+        let idx = self
+            .actions
+            .partition_point(|action| action.ex_date <= date);
+        match adjustment {
+            PriceAdjustment::NoAdjust => Linear::IDENTITY,
+            PriceAdjustment::ForwardAdjust => self.forward[idx],
+            PriceAdjustment::BackwardAdjust => self.backward[idx].inverse(),
+        }
+    }
+
+    /// Converts the prices of a candlestick
+    pub fn adjust_candlestick(
+        &self,
+        candlestick: &mut Candlestick,
+        from: PriceAdjustment,
+        to: PriceAdjustment,
+    ) {
+        if from == to {
+            return;
+        }
+        let linear = self
+            .linear(candlestick.timestamp, from)
+            .inverse()
+            .then(self.linear(candlestick.timestamp, to));
+        for price in [
+            &mut candlestick.open,
+            &mut candlestick.high,
+            &mut candlestick.low,
+            &mut candlestick.close,
+        ] {
+            *price = linear.apply(*price);
+        }
+    }
+
+    /// Converts the prices of the candlesticks
+    pub fn adjust(
+        &self,
+        candlesticks: &mut [Candlestick],
+        from: PriceAdjustment,
+        to: PriceAdjustment,
+    ) {
+        for candlestick in candlesticks {
+            self.adjust_candlestick(candlestick, from, to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::quote::TradeSession;
+
+    fn candlestick(timestamp: OffsetDateTime, close: Decimal) -> Candlestick {
+        Candlestick {
+            close,
+            open: close,
+            low: close,
+            high: close,
+            volume: 0,
+            turnover: Decimal::ZERO,
+            timestamp,
+            trade_session: TradeSession::Intraday,
+        }
+    }
+
+    fn closes(candlesticks: &[Candlestick]) -> Vec<Decimal> {
+        candlesticks
+            .iter()
+            .map(|candlestick| candlestick.close.round_dp(6).normalize())
+            .collect()
+    }
+
+    #[test]
+    fn test_price_adjuster() {
+        let adjuster = PriceAdjuster::new(
+            Market::HK,
+            [
+                CorporateAction {
+                    ex_date: date!(2024 - 03 - 01),
+                    kind: CorporateActionKind::Split {
+                        ratio: Decimal::from(2),
+                    },
+                },
+                CorporateAction {
+                    ex_date: date!(2024 - 02 - 01),
+                    kind: CorporateActionKind::Dividend {
+                        amount: Decimal::from(1),
+                        prev_close: None,
+                    },
+                },
+            ],
+        )
+        .unwrap();
+
+        let raw = vec![
+            candlestick(datetime!(2024-01-31 00:00 +8), Decimal::from(101)),
+            // the ex date in the timezone of the market
+            candlestick(datetime!(2024-01-31 16:00 UTC), Decimal::from(100)),
+            candlestick(datetime!(2024-03-01 00:00 +8), Decimal::from(50)),
+        ];
+
+        let mut candlesticks = raw.clone();
+        adjuster.adjust(
+            &mut candlesticks,
+            PriceAdjustment::NoAdjust,
+            PriceAdjustment::ForwardAdjust,
+        );
+        assert_eq!(
+            closes(&candlesticks),
+            [Decimal::from(50), Decimal::from(50), Decimal::from(50)]
+        );
+
+        adjuster.adjust(
+            &mut candlesticks,
+            PriceAdjustment::ForwardAdjust,
+            PriceAdjustment::BackwardAdjust,
+        );
+        assert_eq!(
+            closes(&candlesticks),
+            [Decimal::from(101), Decimal::from(101), Decimal::from(101)]
+        );
+
+        adjuster.adjust(
+            &mut candlesticks,
+            PriceAdjustment::BackwardAdjust,
+            PriceAdjustment::NoAdjust,
+        );
+        assert_eq!(closes(&candlesticks), closes(&raw));
+    }
+
+    #[test]
+    fn test_invalid_corporate_action() {
+        assert!(matches!(
+            PriceAdjuster::new(
+                Market::US,
+                [CorporateAction {
+                    ex_date: date!(2024 - 03 - 01),
+                    kind: CorporateActionKind::Split {
+                        ratio: Decimal::from(0)
+                    },
+                }],
+            ),
+            Err(Error::InvalidCorporateAction { .. })
+        ));
+    }
+
+    #[test]
+    fn test_proportional_dividend() {
+        let adjuster = PriceAdjuster::new(
+            Market::HK,
+            [CorporateAction {
+                ex_date: date!(2024 - 02 - 01),
+                kind: CorporateActionKind::Dividend {
+                    amount: Decimal::from(10),
+                    prev_close: Some(Decimal::from(100)),
+                },
+            }],
+        )
+        .unwrap();
+
+        let mut candlesticks = vec![
+            candlestick(datetime!(2024-01-30 00:00 +8), Decimal::from(5)),
+            candlestick(datetime!(2024-02-01 00:00 +8), Decimal::from(90)),
+        ];
+        adjuster.adjust(
+            &mut candlesticks,
+            PriceAdjustment::NoAdjust,
+            PriceAdjustment::ForwardAdjust,
+        );
+        assert_eq!(
+            closes(&candlesticks),
+            ["4.5".parse::<Decimal>().unwrap(), Decimal::from(90)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_dividend() {
+        for (amount, prev_close) in [
+            (Decimal::from(-1), None),
+            (Decimal::from(-1), Some(Decimal::from(10))),
+            (Decimal::from(10), Some(Decimal::from(10))),
+        ] {
+            assert!(matches!(
+                PriceAdjuster::new(
+                    Market::US,
+                    [CorporateAction {
+                        ex_date: date!(2024 - 03 - 01),
+                        kind: CorporateActionKind::Dividend { amount, prev_close },
+                    }],
+                ),
+                Err(Error::InvalidCorporateAction { .. })
+            ));
+        }
+    }
+}
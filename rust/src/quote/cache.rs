@@ -42,7 +42,7 @@ where
     {
         let mut inner = self.inner.lock().await;
         match inner.values.get(&key) {
-            Some(Item { deadline, value }) if deadline < &Instant::now() => Ok(value.clone()),
+            Some(Item { deadline, value }) if &Instant::now() < deadline => Ok(value.clone()),
             _ => {
                 let value = f(key.clone()).await?;
                 let deadline = Instant::now() + inner.timeout;
@@ -81,3 +81,25 @@ where
         self.inner.get_or_update((), |_| f()).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_expires() {
+        let cache = CacheWithKey::new(Duration::from_millis(100));
+        let value = |value: i32| move |_| async move { Ok::<_, Infallible>(value) };
+
+        assert_eq!(cache.get_or_update("a", value(1)).await, Ok(1));
+        // the value is cached before the deadline
+        assert_eq!(cache.get_or_update("a", value(2)).await, Ok(1));
+        assert_eq!(cache.get_or_update("b", value(3)).await, Ok(3));
+
+        // the value is updated after the deadline
+        std::thread::sleep(Duration::from_millis(150));
+        assert_eq!(cache.get_or_update("a", value(4)).await, Ok(4));
+    }
+}
@@ -0,0 +1,431 @@
+use std::{collections::HashSet, sync::Arc};
+
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, Weekday};
+use time_tz::PrimitiveDateTimeExt;
+
+use crate::{
+    Error, Market, Result,
+    quote::{
+        MarketTradingDays, QuoteContext, SecurityBoard, TradeSession, cache::CacheWithKey,
+        store::get_market,
+    },
+};
+
+const MARKET_DAYS_CACHE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60 * 60 * 24);
+
+/// The trading days are fetched from 5 days ago, for 30 days
+const MARKET_DAYS_BEFORE: Duration = Duration::days(5);
+const MARKET_DAYS_RANGE: Duration = Duration::days(30);
+
+/// Exchange trading calendar
+///
+/// The session times are the ones of the candlestick engine, the trading days
+/// and the half trading days are fetched from the server for the next few
+/// weeks and refreshed daily.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{
+///     Config, Market,
+///     quote::{MarketCalendar, QuoteContext, TradeSession},
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let (ctx, _) = QuoteContext::try_new(config).await?;
+/// let calendar = MarketCalendar::new(ctx);
+///
+/// if !calendar.is_open(Market::HK, TradeSession::Intraday).await? {
+///     println!(
+///         "next open: {:?}",
+///         calendar.next_open(Market::HK, TradeSession::Intraday).await?
+///     );
+///     calendar.sleep_until_open(Market::HK).await?;
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+#[derive(Clone)]
+pub struct MarketCalendar {
+    ctx: QuoteContext,
+    cache_market_days: Arc<CacheWithKey<Market, Arc<MarketDays>>>,
+}
+
+impl MarketCalendar {
+    /// Create a `MarketCalendar`
+    pub fn new(ctx: QuoteContext) -> Self {
+        Self {
+            ctx,
+            cache_market_days: Arc::new(CacheWithKey::new(MARKET_DAYS_CACHE_TIMEOUT)),
+        }
+    }
+
+    /// Returns the trading days of the market, every day is a trading day of
+    /// the crypto market
+    async fn trading_days(
+        &self,
+        market: Market,
+        begin: Date,
+        end: Date,
+    ) -> Result<MarketTradingDays> {
+        if market == Market::Crypto {
+            return Ok(MarketTradingDays {
+                trading_days: std::iter::successors(Some(begin), |date| date.next_day())
+                    .take_while(|date| *date <= end)
+                    .collect(),
+                half_trading_days: vec![],
+            });
+        }
+        self.ctx.trading_days(market, begin, end).await
+    }
+
+    async fn market_days(&self, market: Market) -> Result<Arc<MarketDays>> {
+        self.cache_market_days
+            .get_or_update(market, |market| async move {
+                let begin = OffsetDateTime::now_utc().date() - MARKET_DAYS_BEFORE;
+                let end = begin + MARKET_DAYS_RANGE;
+                let days = self.trading_days(market, begin, end).await?;
+                Ok(Arc::new(MarketDays::new(market, begin, end, days)))
+            })
+            .await
+    }
+
+    /// Returns `true` if the date is a trading day of the market, including
+    /// the half trading days
+    pub async fn is_trading_day(&self, market: Market, date: Date) -> Result<bool> {
+        let days = self.market_days(market).await?;
+        if days.contains(date) {
+            return Ok(days.is_trading_day(date));
+        }
+        let days = self.trading_days(market, date, date).await?;
+        Ok(days.trading_days.contains(&date) || days.half_trading_days.contains(&date))
+    }
+
+    /// Returns `true` if the date is a half trading day of the market
+    pub async fn is_half_trading_day(&self, market: Market, date: Date) -> Result<bool> {
+        let days = self.market_days(market).await?;
+        if days.contains(date) {
+            return Ok(days.is_half_trading_day(date));
+        }
+        let days = self.trading_days(market, date, date).await?;
+        Ok(days.half_trading_days.contains(&date))
+    }
+
+    /// Returns the holidays of the market between `begin` and `end`, the
+    /// weekdays which are not trading days
+    ///
+    /// The range is limited by [`QuoteContext::trading_days`].
+    pub async fn holidays(&self, market: Market, begin: Date, end: Date) -> Result<Vec<Date>> {
+        let days = self.trading_days(market, begin, end).await?;
+        Ok(MarketDays::new(market, begin, end, days).holidays())
+    }
+
+    /// Returns `true` if the trade session of the market is open now
+    pub async fn is_open(&self, market: Market, trade_session: TradeSession) -> Result<bool> {
+        let periods = self.market_days(market).await?.periods(trade_session)?;
+        Ok(periods.is_open(OffsetDateTime::now_utc()))
+    }
+
+    /// Returns the time the trade session of the market opens next, in the
+    /// timezone of the market
+    ///
+    /// Returns `None` if the trade session does not open within the trading
+    /// days fetched.
+    pub async fn next_open(
+        &self,
+        market: Market,
+        trade_session: TradeSession,
+    ) -> Result<Option<OffsetDateTime>> {
+        let periods = self.market_days(market).await?.periods(trade_session)?;
+        Ok(periods.next_open(OffsetDateTime::now_utc()))
+    }
+
+    /// Returns the time the trade session of the market closes next, in the
+    /// timezone of the market
+    ///
+    /// If the trade session is open, it is the time the current session
+    /// closes. Returns `None` if the trade session does not close within the
+    /// trading days fetched.
+    pub async fn next_close(
+        &self,
+        market: Market,
+        trade_session: TradeSession,
+    ) -> Result<Option<OffsetDateTime>> {
+        let periods = self.market_days(market).await?.periods(trade_session)?;
+        Ok(periods.next_close(OffsetDateTime::now_utc()))
+    }
+
+    /// Waits until the intraday session of the market opens, returns
+    /// immediately if it is open
+    pub async fn sleep_until_open(&self, market: Market) -> Result<()> {
+        self.sleep_until(market, TradeSession::Intraday, true).await
+    }
+
+    /// Waits until the intraday session of the market closes, returns
+    /// immediately if it is closed
+    pub async fn sleep_until_close(&self, market: Market) -> Result<()> {
+        self.sleep_until(market, TradeSession::Intraday, false)
+            .await
+    }
+
+    /// Waits until the trade session of the market opens, returns
+    /// immediately if it is open
+    pub async fn sleep_until_session_open(
+        &self,
+        market: Market,
+        trade_session: TradeSession,
+    ) -> Result<()> {
+        self.sleep_until(market, trade_session, true).await
+    }
+
+    /// Waits until the trade session of the market closes, returns
+    /// immediately if it is closed
+    pub async fn sleep_until_session_close(
+        &self,
+        market: Market,
+        trade_session: TradeSession,
+    ) -> Result<()> {
+        self.sleep_until(market, trade_session, false).await
+    }
+
+    async fn sleep_until(
+        &self,
+        market: Market,
+        trade_session: TradeSession,
+        open: bool,
+    ) -> Result<()> {
+        loop {
+            let periods = self.market_days(market).await?.periods(trade_session)?;
+            let now = OffsetDateTime::now_utc();
+            if periods.is_open(now) == open {
+                return Ok(());
+            }
+            let next = if open {
+                periods.next_open(now)
+            } else {
+                periods.next_close(now)
+            };
+            // wake up daily at least to check the refreshed trading days
+            let wait = next.map_or(Duration::DAY, |t| (t - now).min(Duration::DAY));
+            tokio::time::sleep(wait.try_into().unwrap_or_default()).await;
+        }
+    }
+}
+
+/// The trading days of a market between `begin` and `end`
+#[derive(Debug)]
+struct MarketDays {
+    market: Market,
+    begin: Date,
+    end: Date,
+    trading_days: HashSet<Date>,
+    half_trading_days: HashSet<Date>,
+}
+
+impl MarketDays {
+    fn new(market: Market, begin: Date, end: Date, days: MarketTradingDays) -> Self {
+        Self {
+            market,
+            begin,
+            end,
+            trading_days: days.trading_days.into_iter().collect(),
+            half_trading_days: days.half_trading_days.into_iter().collect(),
+        }
+    }
+
+    #[inline]
+    fn contains(&self, date: Date) -> bool {
+        (self.begin..=self.end).contains(&date)
+    }
+
+    #[inline]
+    fn is_trading_day(&self, date: Date) -> bool {
+        self.trading_days.contains(&date) || self.half_trading_days.contains(&date)
+    }
+
+    #[inline]
+    fn is_half_trading_day(&self, date: Date) -> bool {
+        self.half_trading_days.contains(&date)
+    }
+
+    fn dates(&self) -> impl Iterator<Item = Date> + '_ {
+        std::iter::successors(Some(self.begin), |date| date.next_day())
+            .take_while(|date| *date <= self.end)
+    }
+
+    fn holidays(&self) -> Vec<Date> {
+        self.dates()
+            .filter(|date| !matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday))
+            .filter(|date| !self.is_trading_day(*date))
+            .collect()
+    }
+
+    /// Returns the periods the trade session is open
+    ///
+    /// A session ending at midnight opens the session of the next trading day,
+    /// e.g. the overnight session of the US market on Monday starts at 20:00 on
+    /// Sunday.
+    fn periods(&self, trade_session: TradeSession) -> Result<SessionPeriods> {
+        let no_session = || Error::NoTradeSession {
+            market: self.market,
+            trade_session,
+        };
+        let market = get_market(self.market, SecurityBoard::Unknown).ok_or_else(no_session)?;
+        if market
+            .sessions(trade_session, false)
+            .is_none_or(|sessions| sessions.is_empty())
+        {
+            return Err(no_session());
+        }
+
+        let at = |date: Date, time: Time| {
+            PrimitiveDateTime::new(date, time)
+                .assume_timezone(market.timezone)
+                .take_first()
+        };
+        let mut periods = Vec::new();
+        for date in self.dates().filter(|date| self.is_trading_day(*date)) {
+            let sessions = market
+                .sessions(trade_session, self.is_half_trading_day(date))
+                .unwrap_or_default();
+            for session in sessions {
+                let (start, end) = if session.inclusive {
+                    let start = date.previous_day().and_then(|day| at(day, session.start));
+                    (start, at(date, Time::MIDNIGHT))
+                } else {
+                    (at(date, session.start), at(date, session.end))
+                };
+                if let (Some(start), Some(end)) = (start, end) {
+                    periods.push((start, end));
+                }
+            }
+        }
+
+        periods.sort();
+        let mut merged: Vec<(OffsetDateTime, OffsetDateTime)> = Vec::with_capacity(periods.len());
+        for (start, end) in periods {
+            match merged.last_mut() {
+                Some(last) if last.1 >= start => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        Ok(SessionPeriods(merged))
+    }
+}
+
+/// The periods a trade session is open, sorted by time
+#[derive(Debug)]
+struct SessionPeriods(Vec<(OffsetDateTime, OffsetDateTime)>);
+
+impl SessionPeriods {
+    fn is_open(&self, t: OffsetDateTime) -> bool {
+        self.0.iter().any(|(start, end)| *start <= t && t < *end)
+    }
+
+    fn next_open(&self, t: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.0
+            .iter()
+            .map(|(start, _)| *start)
+            .find(|start| *start > t)
+    }
+
+    fn next_close(&self, t: OffsetDateTime) -> Option<OffsetDateTime> {
+        self.0.iter().map(|(_, end)| *end).find(|end| *end > t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+
+    fn market_days(
+        market: Market,
+        begin: Date,
+        end: Date,
+        days: &[Date],
+        half: &[Date],
+    ) -> MarketDays {
+        MarketDays::new(
+            market,
+            begin,
+            end,
+            MarketTradingDays {
+                trading_days: days.to_vec(),
+                half_trading_days: half.to_vec(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_hk_periods() {
+        let days = market_days(
+            Market::HK,
+            date!(2024 - 12 - 23),
+            date!(2024 - 12 - 29),
+            &[date!(2024 - 12 - 23), date!(2024 - 12 - 27)],
+            &[date!(2024 - 12 - 24)],
+        );
+        assert_eq!(
+            days.holidays(),
+            vec![date!(2024 - 12 - 25), date!(2024 - 12 - 26)]
+        );
+
+        let periods = days.periods(TradeSession::Intraday).unwrap();
+        assert!(periods.is_open(datetime!(2024-12-23 10:00 +8)));
+        assert!(!periods.is_open(datetime!(2024-12-23 12:30 +8)));
+        assert_eq!(
+            periods.next_open(datetime!(2024-12-23 12:30 +8)),
+            Some(datetime!(2024-12-23 13:00 +8))
+        );
+        assert_eq!(
+            periods.next_close(datetime!(2024-12-23 12:30 +8)),
+            Some(datetime!(2024-12-23 16:00 +8))
+        );
+
+        // half trading day
+        assert!(!periods.is_open(datetime!(2024-12-24 13:00 +8)));
+        assert_eq!(
+            periods.next_open(datetime!(2024-12-24 12:30 +8)),
+            Some(datetime!(2024-12-27 09:30 +8))
+        );
+
+        assert!(matches!(
+            days.periods(TradeSession::Pre),
+            Err(Error::NoTradeSession { .. })
+        ));
+    }
+
+    #[test]
+    fn test_us_overnight_periods() {
+        let days = market_days(
+            Market::US,
+            date!(2024 - 06 - 27),
+            date!(2024 - 07 - 02),
+            &[
+                date!(2024 - 06 - 27),
+                date!(2024 - 06 - 28),
+                date!(2024 - 07 - 01),
+                date!(2024 - 07 - 02),
+            ],
+            &[],
+        );
+
+        let periods = days.periods(TradeSession::Overnight).unwrap();
+        assert!(periods.is_open(datetime!(2024-06-28 01:00 -4)));
+        assert!(!periods.is_open(datetime!(2024-06-28 21:00 -4)));
+        assert!(!periods.is_open(datetime!(2024-06-29 01:00 -4)));
+        assert_eq!(
+            periods.next_open(datetime!(2024-06-29 01:00 -4)),
+            Some(datetime!(2024-06-30 20:00 -4))
+        );
+        assert_eq!(
+            periods.next_close(datetime!(2024-06-30 21:00 -4)),
+            Some(datetime!(2024-07-01 04:00 -4))
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
-use longport_httpcli::{HttpClient, Json, Method};
+use longport_httpcli::{CircuitBreaker, HttpClient, Json, Method, RetryPolicy};
+use longport_macros::call;
 use longport_proto::quote;
 use longport_wscli::WsClientError;
 use serde::{Deserialize, Serialize};
@@ -9,7 +10,7 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{Subscriber, dispatcher, instrument::WithSubscriber};
 
 use crate::{
-    Config, Error, Language, Market, Result,
+    CallOptions, Config, Error, Language, Market, Result,
     quote::{
         AdjustType, CalcIndex, Candlestick, CapitalDistributionResponse, CapitalFlowLine,
         HistoryMarketTemperatureResponse, IntradayLine, IssuerInfo, MarketTemperature,
@@ -28,10 +29,9 @@ use crate::{
         },
         utils::{format_date, parse_date},
     },
-    serde_utils,
+    serde_utils, telemetry,
 };
 
-const RETRY_COUNT: usize = 3;
 const PARTICIPANT_INFO_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const ISSUER_INFO_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
@@ -42,6 +42,8 @@ struct InnerQuoteContext {
     language: Language,
     http_cli: HttpClient,
     command_tx: mpsc::UnboundedSender<Command>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<CircuitBreaker>,
     cache_participants: Cache<Vec<ParticipantInfo>>,
     cache_issuers: Cache<Vec<IssuerInfo>>,
     cache_option_chain_expiry_date_list: CacheWithKey<String, Vec<Date>>,
@@ -63,18 +65,28 @@ impl Drop for InnerQuoteContext {
 
 /// Quote context
 #[derive(Clone)]
-pub struct QuoteContext(Arc<InnerQuoteContext>);
+pub struct QuoteContext(Arc<InnerQuoteContext>, CallOptions);
 
 impl QuoteContext {
     /// Create a `QuoteContext`
     pub async fn try_new(
         config: Arc<Config>,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
+        Self::try_new_with_raw_push(config, None).await
+    }
+
+    pub(crate) async fn try_new_with_raw_push(
+        config: Arc<Config>,
+        raw_push_tx: Option<mpsc::UnboundedSender<(u8, Vec<u8>)>>,
     ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
         let log_subscriber = config.create_log_subscriber("quote");
+        let region = config.resolve_region().await;
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
             tracing::info!(
                 language = ?config.language,
+                region = %region.region,
+                region_reason = %region.reason,
                 enable_overnight = ?config.enable_overnight,
                 push_candlestick_mode = ?config.push_candlestick_mode,
                 enable_print_quote_packages = ?config.enable_print_quote_packages,
@@ -84,9 +96,11 @@ impl QuoteContext {
 
         let language = config.language;
         let http_cli = config.create_http_client();
+        let retry_policy = config.retry_policy;
+        let circuit_breaker = config.circuit_breaker.map(CircuitBreaker::new);
         let (command_tx, command_rx) = mpsc::unbounded_channel();
         let (push_tx, push_rx) = mpsc::unbounded_channel();
-        let core = Core::try_new(config, command_rx, push_tx)
+        let core = Core::try_new(config, command_rx, push_tx, raw_push_tx)
             .with_subscriber(log_subscriber.clone())
             .await?;
         let member_id = core.member_id();
@@ -99,24 +113,29 @@ impl QuoteContext {
         });
 
         Ok((
-            QuoteContext(Arc::new(InnerQuoteContext {
-                language,
-                http_cli,
-                command_tx,
-                cache_participants: Cache::new(PARTICIPANT_INFO_CACHE_TIMEOUT),
-                cache_issuers: Cache::new(ISSUER_INFO_CACHE_TIMEOUT),
-                cache_option_chain_expiry_date_list: CacheWithKey::new(
-                    OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT,
-                ),
-                cache_option_chain_strike_info: CacheWithKey::new(
-                    OPTION_CHAIN_STRIKE_INFO_CACHE_TIMEOUT,
-                ),
-                cache_trading_session: Cache::new(TRADING_SESSION_CACHE_TIMEOUT),
-                member_id,
-                quote_level,
-                quote_package_details,
-                log_subscriber,
-            })),
+            QuoteContext(
+                Arc::new(InnerQuoteContext {
+                    language,
+                    http_cli,
+                    command_tx,
+                    retry_policy,
+                    circuit_breaker,
+                    cache_participants: Cache::new(PARTICIPANT_INFO_CACHE_TIMEOUT),
+                    cache_issuers: Cache::new(ISSUER_INFO_CACHE_TIMEOUT),
+                    cache_option_chain_expiry_date_list: CacheWithKey::new(
+                        OPTION_CHAIN_EXPIRY_DATE_LIST_CACHE_TIMEOUT,
+                    ),
+                    cache_option_chain_strike_info: CacheWithKey::new(
+                        OPTION_CHAIN_STRIKE_INFO_CACHE_TIMEOUT,
+                    ),
+                    cache_trading_session: Cache::new(TRADING_SESSION_CACHE_TIMEOUT),
+                    member_id,
+                    quote_level,
+                    quote_package_details,
+                    log_subscriber,
+                }),
+                CallOptions::default(),
+            ),
             push_rx,
         ))
     }
@@ -145,28 +164,82 @@ impl QuoteContext {
         &self.0.quote_package_details
     }
 
+    /// Returns a `QuoteContext` sharing the connection with this one, whose calls
+    /// use the specified options
+    #[must_use]
+    pub fn with_call_options(&self, options: CallOptions) -> Self {
+        Self(self.0.clone(), options)
+    }
+
+    /// Returns the options of the calls
+    #[inline]
+    pub fn call_options(&self) -> &CallOptions {
+        &self.1
+    }
+
+    /// Runs the call within a span, with the call options
+    async fn call<F, T>(&self, method: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        telemetry::call(&self.0.log_subscriber, "quote", method, self.1.run(fut)).await
+    }
+
     /// Send a raw request
-    async fn request_raw(&self, command_code: u8, body: Vec<u8>) -> Result<Vec<u8>> {
-        for _ in 0..RETRY_COUNT {
-            let (reply_tx, reply_rx) = oneshot::channel();
-            self.0
-                .command_tx
-                .send(Command::Request {
-                    command_code,
-                    body: body.clone(),
-                    reply_tx,
-                })
-                .map_err(|_| WsClientError::ClientClosed)?;
-            let res = reply_rx.await.map_err(|_| WsClientError::ClientClosed)?;
+    ///
+    /// The quote requests only read data, so they are retried according to
+    /// the retry policy if the error is retryable.
+    pub(crate) async fn request_raw(&self, command_code: u8, body: Vec<u8>) -> Result<Vec<u8>> {
+        let mut retry_count = 0;
+        telemetry::record_retry_count(retry_count);
+
+        loop {
+            if self
+                .0
+                .circuit_breaker
+                .as_ref()
+                .is_some_and(|circuit_breaker| !circuit_breaker.allow())
+            {
+                return Err(Error::CircuitOpen);
+            }
+
+            let res = self.send_request(command_code, body.clone()).await;
+            if let Some(circuit_breaker) = &self.0.circuit_breaker {
+                if res.as_ref().is_err_and(Error::is_transient) {
+                    circuit_breaker.record_failure();
+                } else {
+                    circuit_breaker.record_success();
+                }
+            }
 
             match res {
-                Ok(resp) => return Ok(resp),
-                Err(Error::WsClient(WsClientError::Cancelled)) => {}
-                Err(err) => return Err(err),
+                Err(err) if err.is_retryable() && retry_count < self.0.retry_policy.max_retries => {
+                    retry_count += 1;
+                    // the request is cancelled when the connection is lost, it is sent again
+                    // after reconnecting
+                    if !matches!(err, Error::WsClient(WsClientError::Cancelled)) {
+                        tokio::time::sleep(self.0.retry_policy.delay(retry_count)).await;
+                    }
+                    telemetry::record_retry_count(retry_count);
+                }
+                res => return res,
             }
         }
+    }
 
-        Err(Error::WsClient(WsClientError::RequestTimeout))
+    async fn send_request(&self, command_code: u8, body: Vec<u8>) -> Result<Vec<u8>> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.0
+            .command_tx
+            .send(Command::Request {
+                command_code,
+                timeout: self.1.remaining(),
+                body,
+                span: tracing::Span::current(),
+                reply_tx,
+            })
+            .map_err(|_| WsClientError::ClientClosed)?;
+        reply_rx.await.map_err(|_| WsClientError::ClientClosed)?
     }
 
     /// Send a request `T` to get a response `R`
@@ -214,6 +287,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn subscribe<I, T>(
         &self,
         symbols: I,
@@ -224,14 +298,16 @@ impl QuoteContext {
         I: IntoIterator<Item = T>,
         T: AsRef<str>,
     {
+        let symbols = symbols
+            .into_iter()
+            .map(|symbol| normalize_symbol(symbol.as_ref()).to_string())
+            .collect::<Vec<_>>();
+        telemetry::record_symbols(&symbols);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
             .send(Command::Subscribe {
-                symbols: symbols
-                    .into_iter()
-                    .map(|symbol| normalize_symbol(symbol.as_ref()).to_string())
-                    .collect(),
+                symbols,
                 sub_types: sub_types.into(),
                 is_first_push,
                 reply_tx,
@@ -264,19 +340,22 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn unsubscribe<I, T>(&self, symbols: I, sub_types: impl Into<SubFlags>) -> Result<()>
     where
         I: IntoIterator<Item = T>,
         T: AsRef<str>,
     {
+        let symbols = symbols
+            .into_iter()
+            .map(|symbol| normalize_symbol(symbol.as_ref()).to_string())
+            .collect::<Vec<_>>();
+        telemetry::record_symbols(&symbols);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
             .send(Command::Unsubscribe {
-                symbols: symbols
-                    .into_iter()
-                    .map(|symbol| normalize_symbol(symbol.as_ref()).to_string())
-                    .collect(),
+                symbols,
                 sub_types: sub_types.into(),
                 reply_tx,
             })
@@ -308,6 +387,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn subscribe_candlesticks<T>(
         &self,
         symbol: T,
@@ -317,6 +397,7 @@ impl QuoteContext {
     where
         T: AsRef<str>,
     {
+        telemetry::record_symbol(symbol.as_ref());
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
@@ -331,10 +412,12 @@ impl QuoteContext {
     }
 
     /// Unsubscribe security candlesticks
+    #[call]
     pub async fn unsubscribe_candlesticks<T>(&self, symbol: T, period: Period) -> Result<()>
     where
         T: AsRef<str>,
     {
+        telemetry::record_symbol(symbol.as_ref());
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
@@ -370,6 +453,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn subscriptions(&self) -> Result<Vec<Subscription>> {
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
@@ -401,17 +485,18 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn static_info<I, T>(&self, symbols: I) -> Result<Vec<SecurityStaticInfo>>
     where
         I: IntoIterator<Item = T>,
         T: Into<String>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let resp: quote::SecurityStaticInfoResponse = self
             .request(
                 cmd_code::GET_BASIC_INFO,
-                quote::MultiSecurityRequest {
-                    symbol: symbols.into_iter().map(Into::into).collect(),
-                },
+                quote::MultiSecurityRequest { symbol: symbols },
             )
             .await?;
         resp.secu_static_info
@@ -442,17 +527,18 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn quote<I, T>(&self, symbols: I) -> Result<Vec<SecurityQuote>>
     where
         I: IntoIterator<Item = T>,
         T: Into<String>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let resp: quote::SecurityQuoteResponse = self
             .request(
                 cmd_code::GET_REALTIME_QUOTE,
-                quote::MultiSecurityRequest {
-                    symbol: symbols.into_iter().map(Into::into).collect(),
-                },
+                quote::MultiSecurityRequest { symbol: symbols },
             )
             .await?;
         resp.secu_quote.into_iter().map(TryInto::try_into).collect()
@@ -478,17 +564,18 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn option_quote<I, T>(&self, symbols: I) -> Result<Vec<OptionQuote>>
     where
         I: IntoIterator<Item = T>,
         T: Into<String>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let resp: quote::OptionQuoteResponse = self
             .request(
                 cmd_code::GET_REALTIME_OPTION_QUOTE,
-                quote::MultiSecurityRequest {
-                    symbol: symbols.into_iter().map(Into::into).collect(),
-                },
+                quote::MultiSecurityRequest { symbol: symbols },
             )
             .await?;
         resp.secu_quote.into_iter().map(TryInto::try_into).collect()
@@ -514,17 +601,18 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn warrant_quote<I, T>(&self, symbols: I) -> Result<Vec<WarrantQuote>>
     where
         I: IntoIterator<Item = T>,
         T: Into<String>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let resp: quote::WarrantQuoteResponse = self
             .request(
                 cmd_code::GET_REALTIME_WARRANT_QUOTE,
-                quote::MultiSecurityRequest {
-                    symbol: symbols.into_iter().map(Into::into).collect(),
-                },
+                quote::MultiSecurityRequest { symbol: symbols },
             )
             .await?;
         resp.secu_quote.into_iter().map(TryInto::try_into).collect()
@@ -550,13 +638,14 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn depth(&self, symbol: impl Into<String>) -> Result<SecurityDepth> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityDepthResponse = self
             .request(
                 cmd_code::GET_SECURITY_DEPTH,
-                quote::SecurityRequest {
-                    symbol: symbol.into(),
-                },
+                quote::SecurityRequest { symbol },
             )
             .await?;
         Ok(SecurityDepth {
@@ -593,13 +682,14 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn brokers(&self, symbol: impl Into<String>) -> Result<SecurityBrokers> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityBrokersResponse = self
             .request(
                 cmd_code::GET_SECURITY_BROKERS,
-                quote::SecurityRequest {
-                    symbol: symbol.into(),
-                },
+                quote::SecurityRequest { symbol },
             )
             .await?;
         Ok(SecurityBrokers {
@@ -628,6 +718,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn participants(&self) -> Result<Vec<ParticipantInfo>> {
         self.0
             .cache_participants
@@ -667,12 +758,15 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn trades(&self, symbol: impl Into<String>, count: usize) -> Result<Vec<Trade>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityTradeResponse = self
             .request(
                 cmd_code::GET_SECURITY_TRADES,
                 quote::SecurityTradeRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     count: count as i32,
                 },
             )
@@ -708,16 +802,19 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn intraday(
         &self,
         symbol: impl Into<String>,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<IntradayLine>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityIntradayResponse = self
             .request(
                 cmd_code::GET_SECURITY_INTRADAY,
                 quote::SecurityIntradayRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     trade_session: trade_sessions as i32,
                 },
             )
@@ -761,6 +858,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn candlesticks(
         &self,
         symbol: impl Into<String>,
@@ -769,11 +867,13 @@ impl QuoteContext {
         adjust_type: AdjustType,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityCandlestickResponse = self
             .request(
                 cmd_code::GET_SECURITY_CANDLESTICKS,
                 quote::SecurityCandlestickRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     period: period.into(),
                     count: count as i32,
                     adjust_type: adjust_type.into(),
@@ -791,6 +891,7 @@ impl QuoteContext {
 
     /// Get security history candlesticks by offset
     #[allow(clippy::too_many_arguments)]
+    #[call]
     pub async fn history_candlesticks_by_offset(
         &self,
         symbol: impl Into<String>,
@@ -801,11 +902,13 @@ impl QuoteContext {
         count: usize,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityCandlestickResponse = self
             .request(
                 cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
                 quote::SecurityHistoryCandlestickRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     period: period.into(),
                     adjust_type: adjust_type.into(),
                     query_type: quote::HistoryCandlestickQueryType::QueryByOffset.into(),
@@ -847,6 +950,7 @@ impl QuoteContext {
     }
 
     /// Get security history candlesticks by date
+    #[call]
     pub async fn history_candlesticks_by_date(
         &self,
         symbol: impl Into<String>,
@@ -856,11 +960,13 @@ impl QuoteContext {
         end: Option<Date>,
         trade_sessions: TradeSessions,
     ) -> Result<Vec<Candlestick>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp: quote::SecurityCandlestickResponse = self
             .request(
                 cmd_code::GET_SECURITY_HISTORY_CANDLESTICKS,
                 quote::SecurityHistoryCandlestickRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     period: period.into(),
                     adjust_type: adjust_type.into(),
                     query_type: quote::HistoryCandlestickQueryType::QueryByDate.into(),
@@ -919,13 +1025,16 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn option_chain_expiry_date_list(
         &self,
         symbol: impl Into<String>,
     ) -> Result<Vec<Date>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         self.0
             .cache_option_chain_expiry_date_list
-            .get_or_update(symbol.into(), |symbol| async {
+            .get_or_update(symbol, |symbol| async {
                 let resp: quote::OptionChainDateListResponse = self
                     .request(
                         cmd_code::GET_OPTION_CHAIN_EXPIRY_DATE_LIST,
@@ -965,31 +1074,31 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn option_chain_info_by_date(
         &self,
         symbol: impl Into<String>,
         expiry_date: Date,
     ) -> Result<Vec<StrikePriceInfo>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         self.0
             .cache_option_chain_strike_info
-            .get_or_update(
-                (symbol.into(), expiry_date),
-                |(symbol, expiry_date)| async move {
-                    let resp: quote::OptionChainDateStrikeInfoResponse = self
-                        .request(
-                            cmd_code::GET_OPTION_CHAIN_INFO_BY_DATE,
-                            quote::OptionChainDateStrikeInfoRequest {
-                                symbol,
-                                expiry_date: format_date(expiry_date),
-                            },
-                        )
-                        .await?;
-                    resp.strike_price_info
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<Result<Vec<_>>>()
-                },
-            )
+            .get_or_update((symbol, expiry_date), |(symbol, expiry_date)| async move {
+                let resp: quote::OptionChainDateStrikeInfoResponse = self
+                    .request(
+                        cmd_code::GET_OPTION_CHAIN_INFO_BY_DATE,
+                        quote::OptionChainDateStrikeInfoRequest {
+                            symbol,
+                            expiry_date: format_date(expiry_date),
+                        },
+                    )
+                    .await?;
+                resp.strike_price_info
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<Vec<_>>>()
+            })
             .await
     }
 
@@ -1013,6 +1122,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn warrant_issuers(&self) -> Result<Vec<IssuerInfo>> {
         self.0
             .cache_issuers
@@ -1029,6 +1139,7 @@ impl QuoteContext {
 
     /// Query warrant list
     #[allow(clippy::too_many_arguments)]
+    #[call]
     pub async fn warrant_list(
         &self,
         symbol: impl Into<String>,
@@ -1040,11 +1151,13 @@ impl QuoteContext {
         price_type: Option<&[FilterWarrantInOutBoundsType]>,
         status: Option<&[WarrantStatus]>,
     ) -> Result<Vec<WarrantInfo>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let resp = self
             .request::<_, quote::WarrantFilterListResponse>(
                 cmd_code::GET_FILTERED_WARRANT,
                 quote::WarrantFilterListRequest {
-                    symbol: symbol.into(),
+                    symbol,
                     filter_config: Some(quote::FilterConfig {
                         sort_by: sort_by.into(),
                         sort_order: sort_order.into(),
@@ -1094,6 +1207,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn trading_session(&self) -> Result<Vec<MarketTradingSession>> {
         self.0
             .cache_trading_session
@@ -1137,6 +1251,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn trading_days(
         &self,
         market: Market,
@@ -1192,12 +1307,13 @@ impl QuoteContext {
     /// println!("{:?}", resp);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
+    #[call]
     pub async fn capital_flow(&self, symbol: impl Into<String>) -> Result<Vec<CapitalFlowLine>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         self.request::<_, quote::CapitalFlowIntradayResponse>(
             cmd_code::GET_CAPITAL_FLOW_INTRADAY,
-            quote::CapitalFlowIntradayRequest {
-                symbol: symbol.into(),
-            },
+            quote::CapitalFlowIntradayRequest { symbol },
         )
         .await?
         .capital_flow_lines
@@ -1225,21 +1341,23 @@ impl QuoteContext {
     /// println!("{:?}", resp);
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
+    #[call]
     pub async fn capital_distribution(
         &self,
         symbol: impl Into<String>,
     ) -> Result<CapitalDistributionResponse> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         self.request::<_, quote::CapitalDistributionResponse>(
             cmd_code::GET_SECURITY_CAPITAL_DISTRIBUTION,
-            quote::SecurityRequest {
-                symbol: symbol.into(),
-            },
+            quote::SecurityRequest { symbol },
         )
         .await?
         .try_into()
     }
 
     /// Get calc indexes
+    #[call]
     pub async fn calc_indexes<I, T, J>(
         &self,
         symbols: I,
@@ -1250,12 +1368,14 @@ impl QuoteContext {
         T: Into<String>,
         J: IntoIterator<Item = CalcIndex>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let indexes = indexes.into_iter().collect::<Vec<CalcIndex>>();
         let resp: quote::SecurityCalcQuoteResponse = self
             .request(
                 cmd_code::GET_CALC_INDEXES,
                 quote::SecurityCalcQuoteRequest {
-                    symbols: symbols.into_iter().map(Into::into).collect(),
+                    symbols,
                     calc_index: indexes
                         .iter()
                         .map(|i| quote::CalcIndex::from(*i).into())
@@ -1291,6 +1411,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn watchlist(&self) -> Result<Vec<WatchlistGroup>> {
         #[derive(Debug, Deserialize)]
         struct Response {
@@ -1332,6 +1453,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn create_watchlist_group(&self, req: RequestCreateWatchlistGroup) -> Result<i64> {
         #[derive(Debug, Serialize)]
         struct RequestCreate {
@@ -1381,6 +1503,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn delete_watchlist_group(&self, id: i64, purge: bool) -> Result<()> {
         #[derive(Debug, Serialize)]
         struct Request {
@@ -1423,6 +1546,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn update_watchlist_group(&self, req: RequestUpdateWatchlistGroup) -> Result<()> {
         #[derive(Debug, Serialize)]
         struct RequestUpdate {
@@ -1452,6 +1576,7 @@ impl QuoteContext {
     }
 
     /// Get security list
+    #[call]
     pub async fn security_list(
         &self,
         market: Market,
@@ -1505,6 +1630,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn market_temperature(&self, market: Market) -> Result<MarketTemperature> {
         #[derive(Debug, Serialize)]
         struct Request {
@@ -1546,6 +1672,7 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn history_market_temperature(
         &self,
         market: Market,
@@ -1603,18 +1730,18 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn realtime_quote<I, T>(&self, symbols: I) -> Result<Vec<RealtimeQuote>>
     where
         I: IntoIterator<Item = T>,
         T: Into<String>,
     {
+        let symbols = symbols.into_iter().map(Into::into).collect::<Vec<String>>();
+        telemetry::record_symbols(&symbols);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
-            .send(Command::GetRealtimeQuote {
-                symbols: symbols.into_iter().map(Into::into).collect(),
-                reply_tx,
-            })
+            .send(Command::GetRealtimeQuote { symbols, reply_tx })
             .map_err(|_| WsClientError::ClientClosed)?;
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
@@ -1647,14 +1774,14 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn realtime_depth(&self, symbol: impl Into<String>) -> Result<SecurityDepth> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
-            .send(Command::GetRealtimeDepth {
-                symbol: symbol.into(),
-                reply_tx,
-            })
+            .send(Command::GetRealtimeDepth { symbol, reply_tx })
             .map_err(|_| WsClientError::ClientClosed)?;
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
@@ -1687,16 +1814,19 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn realtime_trades(
         &self,
         symbol: impl Into<String>,
         count: usize,
     ) -> Result<Vec<Trade>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
             .send(Command::GetRealtimeTrade {
-                symbol: symbol.into(),
+                symbol,
                 count,
                 reply_tx,
             })
@@ -1733,14 +1863,14 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn realtime_brokers(&self, symbol: impl Into<String>) -> Result<SecurityBrokers> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
-            .send(Command::GetRealtimeBrokers {
-                symbol: symbol.into(),
-                reply_tx,
-            })
+            .send(Command::GetRealtimeBrokers { symbol, reply_tx })
             .map_err(|_| WsClientError::ClientClosed)?;
         Ok(reply_rx.await.map_err(|_| WsClientError::ClientClosed)?)
     }
@@ -1775,17 +1905,20 @@ impl QuoteContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn realtime_candlesticks(
         &self,
         symbol: impl Into<String>,
         period: Period,
         count: usize,
     ) -> Result<Vec<Candlestick>> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         let (reply_tx, reply_rx) = oneshot::channel();
         self.0
             .command_tx
             .send(Command::GetRealtimeCandlesticks {
-                symbol: symbol.into(),
+                symbol,
                 period,
                 count,
                 reply_tx,
@@ -20,14 +20,17 @@ use tokio::{
     sync::{mpsc, oneshot},
     time::{Duration, Instant},
 };
+use tracing::{Instrument, Span};
 
 use crate::{
     Config, Error, Market, Result,
     config::PushCandlestickMode,
+    metrics,
     quote::{
-        Candlestick, PushCandlestick, PushEvent, PushEventDetail, PushQuote, PushTrades,
-        RealtimeQuote, SecurityBoard, SecurityBrokers, SecurityDepth, Subscription, Trade,
-        TradeSession, TradeSessions, cmd_code,
+        Candlestick, MarketTradingSession, PushCandlestick, PushEvent, PushEventDetail, PushQuote,
+        PushTrades, RealtimeQuote, SecurityBoard, SecurityBrokers, SecurityDepth, Subscription,
+        Trade, TradeSession, TradeSessions, cmd_code,
+        session_tables::SessionTables,
         store::{Candlesticks, Store, TailCandlestick},
         sub_flags::SubFlags,
         types::QuotePackageDetail,
@@ -40,7 +43,9 @@ const RECONNECT_DELAY: Duration = Duration::from_secs(2);
 pub(crate) enum Command {
     Request {
         command_code: u8,
+        timeout: Option<Duration>,
         body: Vec<u8>,
+        span: Span,
         reply_tx: oneshot::Sender<Result<Vec<u8>>>,
     },
     Subscribe {
@@ -128,6 +133,7 @@ pub(crate) struct MarketPackageDetail {
 
 pub(crate) struct Core {
     config: Arc<Config>,
+    raw_push_tx: Option<mpsc::UnboundedSender<(u8, Vec<u8>)>>,
     rate_limit: Vec<(u8, RateLimit)>,
     command_rx: mpsc::UnboundedReceiver<Command>,
     push_tx: mpsc::UnboundedSender<PushEvent>,
@@ -139,6 +145,7 @@ pub(crate) struct Core {
     close: bool,
     subscriptions: HashMap<String, SubFlags>,
     trading_days: TradingDays,
+    session_tables: SessionTables,
     store: Store,
     member_id: i64,
     quote_level: String,
@@ -151,26 +158,15 @@ impl Core {
         config: Arc<Config>,
         command_rx: mpsc::UnboundedReceiver<Command>,
         push_tx: mpsc::UnboundedSender<PushEvent>,
+        raw_push_tx: Option<mpsc::UnboundedSender<(u8, Vec<u8>)>>,
     ) -> Result<Self> {
         let http_cli = config.create_http_client();
-        let otp = http_cli.get_otp().await?;
+        let otp = request_otp(&config, &http_cli).await?;
 
         let (event_tx, event_rx) = mpsc::unbounded_channel();
 
         tracing::info!("connecting to quote server");
-        let (url, res) = config.create_quote_ws_request().await;
-        let request = res.map_err(WsClientError::from)?;
-
-        let mut ws_cli = WsClient::open(
-            request,
-            ProtocolVersion::Version1,
-            CodecType::Protobuf,
-            Platform::OpenAPI,
-            event_tx.clone(),
-            vec![],
-        )
-        .await?;
-
+        let (url, mut ws_cli) = connect(&config, event_tx.clone(), vec![]).await?;
         tracing::info!(url = url, "quote server connected");
 
         let session = ws_cli.request_auth(otp, config.create_metadata()).await?;
@@ -233,6 +229,11 @@ impl Core {
         ws_cli.set_rate_limit(rate_limit.clone());
 
         let current_trade_days = fetch_trading_days(&ws_cli).await?;
+        let mut session_tables = match &config.session_tables_path {
+            Some(path) => SessionTables::from_file(path)?,
+            None => SessionTables::default(),
+        };
+        update_session_tables(&ws_cli, &mut session_tables).await;
         let push_candlestick_mode = config.push_candlestick_mode.unwrap_or_default();
 
         let mut table = Table::new();
@@ -264,6 +265,7 @@ impl Core {
 
         Ok(Self {
             config,
+            raw_push_tx,
             rate_limit,
             command_rx,
             push_tx,
@@ -275,6 +277,7 @@ impl Core {
             close: false,
             subscriptions: HashMap::new(),
             trading_days: current_trade_days,
+            session_tables,
             store: Store::default(),
             member_id,
             quote_level,
@@ -308,27 +311,22 @@ impl Core {
             loop {
                 // reconnect
                 tokio::time::sleep(RECONNECT_DELAY).await;
+                metrics::record_reconnect_attempt("quote");
 
                 tracing::info!("connecting to quote server");
-                let (url, res) = self.config.create_quote_ws_request().await;
-                let request = res.expect("BUG: failed to create quote ws request");
-
-                match WsClient::open(
-                    request,
-                    ProtocolVersion::Version1,
-                    CodecType::Protobuf,
-                    Platform::OpenAPI,
-                    self.event_tx.clone(),
-                    self.rate_limit.clone(),
-                )
-                .await
-                {
-                    Ok(ws_cli) => self.ws_cli = ws_cli,
-                    Err(err) => {
-                        tracing::error!(error = %err, "failed to connect quote server");
-                        continue;
-                    }
-                }
+                let url =
+                    match connect(&self.config, self.event_tx.clone(), self.rate_limit.clone())
+                        .await
+                    {
+                        Ok((url, ws_cli)) => {
+                            self.ws_cli = ws_cli;
+                            url
+                        }
+                        Err(err) => {
+                            tracing::error!(error = %err, "failed to connect quote server");
+                            continue;
+                        }
+                    };
 
                 tracing::info!(url = url, "quote server connected");
 
@@ -349,7 +347,7 @@ impl Core {
                         }
                     }
                     _ => {
-                        let otp = match self.http_cli.get_otp().await {
+                        let otp = match request_otp(&self.config, &self.http_cli).await {
                             Ok(otp) => otp,
                             Err(err) => {
                                 tracing::error!(error = %err, "failed to request otp");
@@ -373,7 +371,10 @@ impl Core {
 
                 // handle reconnect
                 match self.resubscribe().await {
-                    Ok(()) => break,
+                    Ok(()) => {
+                        metrics::record_reconnect("quote");
+                        break;
+                    }
                     Err(err) => {
                         tracing::error!(error = %err, "failed to subscribe topics");
                         continue;
@@ -410,6 +411,7 @@ impl Core {
                     if let Ok(days) = fetch_trading_days(&self.ws_cli).await {
                         self.trading_days = days;
                     }
+                    update_session_tables(&self.ws_cli, &mut self.session_tables).await;
                 }
             }
         }
@@ -419,9 +421,15 @@ impl Core {
         match command {
             Command::Request {
                 command_code,
+                timeout,
                 body,
+                span,
                 reply_tx,
-            } => self.handle_request(command_code, body, reply_tx).await,
+            } => {
+                self.handle_request(command_code, timeout, body, reply_tx)
+                    .instrument(span)
+                    .await
+            }
             Command::Subscribe {
                 symbols,
                 sub_types,
@@ -502,10 +510,15 @@ impl Core {
     async fn handle_request(
         &mut self,
         command_code: u8,
+        timeout: Option<Duration>,
         body: Vec<u8>,
-        reply_tx: oneshot::Sender<Result<Vec<u8>>>,
+        mut reply_tx: oneshot::Sender<Result<Vec<u8>>>,
     ) -> Result<()> {
-        let res = self.ws_cli.request_raw(command_code, None, body).await;
+        // stop waiting for the response if the call is cancelled or timed out
+        let res = tokio::select! {
+            res = self.ws_cli.request_raw(command_code, timeout, body) => res,
+            _ = reply_tx.closed() => return Ok(()),
+        };
         let _ = reply_tx.send(res.map_err(Into::into));
         Ok(())
     }
@@ -783,7 +796,12 @@ impl Core {
     async fn handle_ws_event(&mut self, event: WsEvent) -> Result<()> {
         match event {
             WsEvent::Error(err) => Err(err.into()),
-            WsEvent::Push { command_code, body } => self.handle_push(command_code, body),
+            WsEvent::Push { command_code, body } => {
+                if let Some(raw_push_tx) = &self.raw_push_tx {
+                    let _ = raw_push_tx.send((command_code, body.clone()));
+                }
+                self.handle_push(command_code, body)
+            }
         }
     }
 
@@ -845,9 +863,15 @@ impl Core {
             };
 
             let action = if mtype == MergeType::QuoteDay {
-                Some(candlesticks.merge_quote_day(market_type, security_data.board, push_quote))
+                Some(candlesticks.merge_quote_day(
+                    &self.session_tables,
+                    market_type,
+                    security_data.board,
+                    push_quote,
+                ))
             } else if mtype == MergeType::Quote {
                 Some(candlesticks.merge_quote(
+                    &self.session_tables,
                     market_type,
                     half_days,
                     security_data.board,
@@ -891,6 +915,7 @@ impl Core {
                 }
 
                 let action = candlesticks.merge_trade(
+                    &self.session_tables,
                     market_type,
                     half_days,
                     security_data.board,
@@ -914,6 +939,16 @@ impl Core {
         match PushEvent::parse(command_code, &body) {
             Ok((mut event, tag)) => {
                 tracing::info!(event = ?event, tag = ?tag, "push event");
+                metrics::record_push(
+                    "quote",
+                    match &event.detail {
+                        PushEventDetail::Quote(_) => "quote",
+                        PushEventDetail::Depth(_) => "depth",
+                        PushEventDetail::Brokers(_) => "brokers",
+                        PushEventDetail::Trade(_) => "trade",
+                        PushEventDetail::Candlestick(_) => "candlestick",
+                    },
+                );
 
                 if tag != Some(PushQuoteTag::Eod) {
                     self.store.handle_push(&mut event);
@@ -1066,6 +1101,50 @@ fn merge_type(
     })
 }
 
+async fn connect(
+    config: &Config,
+    event_tx: mpsc::UnboundedSender<WsEvent>,
+    rate_limit: Vec<(u8, RateLimit)>,
+) -> Result<(String, WsClient)> {
+    #[cfg(unix)]
+    if let Some(path) = &config.quote_mux_path {
+        let ws_cli = WsClient::open_unix(
+            path,
+            "ws://localhost",
+            ProtocolVersion::Version1,
+            CodecType::Protobuf,
+            Platform::OpenAPI,
+            event_tx,
+            rate_limit,
+        )
+        .await?;
+        return Ok((format!("unix:{}", path.display()), ws_cli));
+    }
+
+    let (url, res) = config.create_quote_ws_request().await;
+    let request = res.map_err(WsClientError::from)?;
+    let ws_cli = WsClient::open_with_options(
+        request,
+        ProtocolVersion::Version1,
+        CodecType::Protobuf,
+        Platform::OpenAPI,
+        event_tx,
+        rate_limit,
+        config.ws_open_options(),
+    )
+    .await?;
+    Ok((url.to_string(), ws_cli))
+}
+
+/// The quote mux server authenticates its own upstream connection, so the
+/// clients connected to it don't need an OTP.
+async fn request_otp(config: &Config, http_cli: &HttpClient) -> Result<String> {
+    if cfg!(unix) && config.quote_mux_path.is_some() {
+        return Ok(String::new());
+    }
+    Ok(http_cli.get_otp().await?)
+}
+
 async fn fetch_trading_days(cli: &WsClient) -> Result<TradingDays> {
     let mut days = TradingDays::default();
     let begin_day = OffsetDateTime::now_utc().date() - time::Duration::days(5);
@@ -1108,6 +1187,25 @@ async fn fetch_trading_days(cli: &WsClient) -> Result<TradingDays> {
     Ok(days)
 }
 
+/// Updates the trade session tables with the trading sessions of the server,
+/// the current tables are kept if the request fails
+async fn update_session_tables(cli: &WsClient, tables: &mut SessionTables) {
+    let res = cli
+        .request::<_, quote::MarketTradePeriodResponse>(cmd_code::GET_TRADING_SESSION, None, ())
+        .await
+        .map_err(Error::from)
+        .and_then(|resp| {
+            resp.market_trade_session
+                .into_iter()
+                .map(MarketTradingSession::try_from)
+                .collect::<Result<Vec<_>>>()
+        });
+    match res {
+        Ok(sessions) => tables.update(&sessions),
+        Err(err) => tracing::warn!(error = %err, "failed to fetch the trading sessions"),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn update_and_push_candlestick(
     candlesticks: &mut Candlesticks,
@@ -1203,6 +1301,8 @@ fn parse_market_from_symbol(symbol: &str) -> Option<Market> {
         "HK" => Market::HK,
         "SG" => Market::SG,
         "SH" | "SZ" => Market::CN,
+        // the crypto pairs, e.g. `BTCUSD.HAS`
+        "HAS" => Market::Crypto,
         _ => return None,
     })
 }
@@ -1215,6 +1315,45 @@ mod tests {
     fn test_parse_market_from_symbol() {
         assert_eq!(parse_market_from_symbol("AAPL.US"), Some(Market::US));
         assert_eq!(parse_market_from_symbol("BRK.A.US"), Some(Market::US));
+        assert_eq!(parse_market_from_symbol("BTCUSD.HAS"), Some(Market::Crypto));
+    }
+
+    #[test]
+    fn test_merge_crypto_trade() {
+        use rust_decimal::Decimal;
+        use time::macros::datetime;
+
+        use crate::quote::TradeDirection;
+
+        let market_type = parse_market_from_symbol("ETHUSD.HAS").unwrap();
+        let mut candlesticks = Candlesticks {
+            trade_sessions: TradeSessions::Intraday,
+            candlesticks: vec![],
+            tails: HashMap::new(),
+        };
+        // the crypto market trades on the weekends
+        let trade = Trade {
+            price: Decimal::from(3500),
+            volume: 2,
+            timestamp: datetime!(2024-06-08 23:59:30 UTC),
+            trade_type: String::new(),
+            direction: TradeDirection::Up,
+            trade_session: TradeSession::Intraday,
+        };
+        let action = candlesticks.merge_trade(
+            &SessionTables::default(),
+            market_type,
+            false,
+            SecurityBoard::Unknown,
+            Period::OneMinute,
+            &trade,
+        );
+        let UpdateAction::AppendNew { new, .. } = action else {
+            panic!("unexpected action: {action:?}");
+        };
+        assert_eq!(new.timestamp, datetime!(2024-06-08 23:59 UTC));
+        assert_eq!(new.close, Decimal::from(3500));
+        assert_eq!(new.volume, 2);
     }
 
     #[test]
@@ -1,17 +1,27 @@
 //! Quote related types
 
+mod adjust;
 mod cache;
+mod calendar;
 mod cmd_code;
 mod context;
 mod core;
+#[cfg(unix)]
+mod mux;
 mod push_types;
+mod session_tables;
 mod store;
 mod sub_flags;
 mod types;
 mod utils;
 
+pub use adjust::{CorporateAction, CorporateActionKind, PriceAdjuster, PriceAdjustment};
+pub use calendar::MarketCalendar;
 pub use context::QuoteContext;
 pub use longport_proto::quote::{AdjustType, Period, TradeStatus};
+#[cfg(unix)]
+#[cfg_attr(docsrs, doc(cfg(unix)))]
+pub use mux::QuoteMuxServer;
 pub use push_types::{
     PushBrokers, PushCandlestick, PushDepth, PushEvent, PushEventDetail, PushQuote, PushTrades,
 };
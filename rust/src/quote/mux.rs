@@ -0,0 +1,672 @@
+use std::{
+    collections::HashMap,
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use longport_proto::{
+    control::{AuthResponse, ReconnectResponse},
+    quote::{SubscribeRequest, UnsubscribeRequest},
+};
+use longport_wscli::{WsClientError, WsRequest, WsServerConn};
+use prost::Message;
+use tokio::{net::UnixListener, sync::mpsc};
+
+use crate::{
+    Config, Error, Result,
+    quote::{PushEvent, QuoteContext, SubFlags, cmd_code},
+};
+
+const COMMAND_CODE_AUTH: u8 = 2;
+const COMMAND_CODE_RECONNECT: u8 = 3;
+const SESSION_EXPIRES: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// Response status for the errors that were not returned by the upstream
+/// server
+const STATUS_INTERNAL_ERROR: u8 = 1;
+
+/// The id of the subscriptions confirmed by the upstream server, the client
+/// ids start from 1
+const UPSTREAM_ID: u64 = 0;
+
+enum Event {
+    Connected {
+        id: u64,
+        conn: WsServerConn,
+    },
+    Request {
+        id: u64,
+        req: WsRequest,
+    },
+    Disconnected {
+        id: u64,
+    },
+    /// The upstream subscription of a client failed, the subscriptions added
+    /// for the request are removed
+    SubscribeFailed {
+        id: u64,
+        added: HashMap<SubFlags, Vec<String>>,
+    },
+}
+
+/// The response to a client request
+struct Reply {
+    conn: WsServerConn,
+    command_code: u8,
+    request_id: u32,
+}
+
+/// An upstream subscription change, the changes are sent to the upstream
+/// server one by one in order
+enum Upstream {
+    /// Subscribes the flags which are not confirmed by the upstream server yet
+    Subscribe {
+        id: u64,
+        symbols: Vec<String>,
+        flags: SubFlags,
+        is_first_push: bool,
+        /// The subscriptions added for the request
+        added: HashMap<SubFlags, Vec<String>>,
+        reply: Reply,
+    },
+    /// Unsubscribes the flags which are no longer needed by any client and
+    /// were confirmed by the upstream server
+    Unsubscribe {
+        groups: HashMap<SubFlags, Vec<String>>,
+        reply: Option<Reply>,
+    },
+}
+
+/// The subscriptions of every client connected to the mux server
+#[derive(Debug, Default)]
+struct Subscriptions(HashMap<u64, HashMap<String, SubFlags>>);
+
+impl Subscriptions {
+    /// Returns the union of the flags subscribed by all clients
+    fn flags(&self, symbol: &str) -> SubFlags {
+        self.0
+            .values()
+            .filter_map(|subscriptions| subscriptions.get(symbol))
+            .fold(SubFlags::empty(), |acc, flags| acc | *flags)
+    }
+
+    fn contains(&self, id: u64, symbol: &str, flags: SubFlags) -> bool {
+        self.0
+            .get(&id)
+            .and_then(|subscriptions| subscriptions.get(symbol))
+            .is_some_and(|sub_flags| sub_flags.contains(flags))
+    }
+
+    /// Returns the subscriptions which are not subscribed yet, grouped by
+    /// flags
+    fn missing(&self, symbols: &[String], flags: SubFlags) -> HashMap<SubFlags, Vec<String>> {
+        let mut groups: HashMap<SubFlags, Vec<String>> = HashMap::new();
+        for symbol in symbols {
+            let missing = flags - self.flags(symbol);
+            if !missing.is_empty() {
+                groups.entry(missing).or_default().push(symbol.clone());
+            }
+        }
+        groups
+    }
+
+    /// Adds the subscriptions of the client, and returns the flags which were
+    /// not subscribed by the client yet, grouped by flags
+    fn add(
+        &mut self,
+        id: u64,
+        symbols: Vec<String>,
+        flags: SubFlags,
+    ) -> HashMap<SubFlags, Vec<String>> {
+        let subscriptions = self.0.entry(id).or_default();
+        let mut added: HashMap<SubFlags, Vec<String>> = HashMap::new();
+        for symbol in symbols {
+            let sub_flags = subscriptions
+                .entry(symbol.clone())
+                .or_insert_with(SubFlags::empty);
+            let new_flags = flags - *sub_flags;
+            *sub_flags |= flags;
+            if !new_flags.is_empty() {
+                added.entry(new_flags).or_default().push(symbol);
+            }
+        }
+        added
+    }
+
+    /// Removes the subscriptions of the client, and returns the subscriptions
+    /// which are no longer needed by any client, grouped by flags
+    ///
+    /// If `symbols` is `None`, all subscriptions of the client are removed.
+    fn remove(
+        &mut self,
+        id: u64,
+        symbols: Option<Vec<String>>,
+        flags: SubFlags,
+    ) -> HashMap<SubFlags, Vec<String>> {
+        let Some(subscriptions) = self.0.get_mut(&id) else {
+            return HashMap::new();
+        };
+
+        let mut removed = Vec::new();
+        let symbols = symbols.unwrap_or_else(|| subscriptions.keys().cloned().collect());
+        for symbol in symbols {
+            if let Some(sub_flags) = subscriptions.get_mut(&symbol) {
+                removed.push((symbol.clone(), *sub_flags & flags));
+                *sub_flags -= flags;
+                if sub_flags.is_empty() {
+                    subscriptions.remove(&symbol);
+                }
+            }
+        }
+
+        let mut groups: HashMap<SubFlags, Vec<String>> = HashMap::new();
+        for (symbol, flags) in removed {
+            let unused = flags - self.flags(&symbol);
+            if !unused.is_empty() {
+                groups.entry(unused).or_default().push(symbol);
+            }
+        }
+        groups
+    }
+
+    fn remove_client(&mut self, id: u64) -> HashMap<SubFlags, Vec<String>> {
+        let groups = self.remove(id, None, SubFlags::all());
+        self.0.remove(&id);
+        groups
+    }
+}
+
+/// A local daemon that shares one upstream quote connection with many
+/// [`QuoteContext`]s
+///
+/// The server listens on a unix domain socket and speaks the same packet
+/// format as the OpenAPI quote server. Subscriptions of all connected clients
+/// are merged, so the server only subscribes each symbol once and
+/// unsubscribes it after the last client no longer needs it.
+///
+/// Use [`Config::quote_mux_path`] or the `LONGPORT_QUOTE_MUX_PATH` environment
+/// variable to connect a [`QuoteContext`] to the server.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{Config, quote::QuoteMuxServer};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let server = QuoteMuxServer::bind(config, "/tmp/longport-quote.sock").await?;
+/// server.run().await?;
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+pub struct QuoteMuxServer {
+    ctx: QuoteContext,
+    push_rx: mpsc::UnboundedReceiver<PushEvent>,
+    raw_push_rx: mpsc::UnboundedReceiver<(u8, Vec<u8>)>,
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl QuoteMuxServer {
+    /// Connect to the OpenAPI quote server and listen on the unix domain
+    /// socket `path`
+    ///
+    /// A stale socket file at `path` is removed, and the new socket is only
+    /// accessible by the current user: it is bound in a private directory and
+    /// moved to `path` after its permissions are set.
+    pub async fn bind(config: Arc<Config>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut config = (*config).clone();
+        config.quote_mux_path = None;
+
+        let (raw_push_tx, raw_push_rx) = mpsc::unbounded_channel();
+        let (ctx, push_rx) =
+            QuoteContext::try_new_with_raw_push(Arc::new(config), Some(raw_push_tx)).await?;
+
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        let listener = bind_private(&path)?;
+        tracing::info!(path = %path.display(), "quote mux server listening");
+
+        Ok(Self {
+            ctx,
+            push_rx,
+            raw_push_rx,
+            listener,
+            path,
+        })
+    }
+
+    /// Returns the path of the unix domain socket
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the upstream quote context
+    #[inline]
+    pub fn quote_context(&self) -> &QuoteContext {
+        &self.ctx
+    }
+
+    /// Serve the clients until an error occurs
+    pub async fn run(mut self) -> Result<()> {
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (upstream_tx, upstream_rx) = mpsc::unbounded_channel();
+        let mut next_id = 0;
+        let mut conns: HashMap<u64, WsServerConn> = HashMap::new();
+        let mut subscriptions = Subscriptions::default();
+
+        tokio::spawn(update_upstream(
+            self.ctx.clone(),
+            upstream_rx,
+            event_tx.clone(),
+        ));
+
+        loop {
+            tokio::select! {
+                res = self.listener.accept() => {
+                    let (stream, _) = res?;
+                    next_id += 1;
+                    tokio::spawn(serve_client(next_id, stream, event_tx.clone()));
+                }
+                Some(event) = event_rx.recv() => match event {
+                    Event::Connected { id, conn } => {
+                        tracing::info!(id = id, "quote mux client connected");
+                        conns.insert(id, conn);
+                    }
+                    Event::Request { id, req } => {
+                        let Some(conn) = conns.get(&id) else {
+                            continue;
+                        };
+                        self.handle_request(id, conn, req, &mut subscriptions, &upstream_tx);
+                    }
+                    Event::Disconnected { id } => {
+                        tracing::info!(id = id, "quote mux client disconnected");
+                        conns.remove(&id);
+                        let _ = upstream_tx.send(Upstream::Unsubscribe {
+                            groups: subscriptions.remove_client(id),
+                            reply: None,
+                        });
+                    }
+                    Event::SubscribeFailed { id, added } => {
+                        let mut groups: HashMap<SubFlags, Vec<String>> = HashMap::new();
+                        for (flags, symbols) in added {
+                            for (flags, symbols) in subscriptions.remove(id, Some(symbols), flags) {
+                                groups.entry(flags).or_default().extend(symbols);
+                            }
+                        }
+                        let _ = upstream_tx.send(Upstream::Unsubscribe {
+                            groups,
+                            reply: None,
+                        });
+                    }
+                },
+                Some((command_code, body)) = self.raw_push_rx.recv() => {
+                    let flags = match command_code {
+                        cmd_code::PUSH_REALTIME_QUOTE => SubFlags::QUOTE,
+                        cmd_code::PUSH_REALTIME_DEPTH => SubFlags::DEPTH,
+                        cmd_code::PUSH_REALTIME_BROKERS => SubFlags::BROKER,
+                        cmd_code::PUSH_REALTIME_TRADES => SubFlags::TRADE,
+                        _ => continue,
+                    };
+                    let Ok((event, _)) = PushEvent::parse(command_code, &body) else {
+                        continue;
+                    };
+                    for (id, conn) in &conns {
+                        if subscriptions.contains(*id, &event.symbol, flags) {
+                            let _ = conn.push(command_code, body.clone());
+                        }
+                    }
+                }
+                // the pushes are forwarded from `raw_push_rx`
+                Some(_) = self.push_rx.recv() => {}
+            }
+        }
+    }
+
+    fn handle_request(
+        &self,
+        id: u64,
+        conn: &WsServerConn,
+        req: WsRequest,
+        subscriptions: &mut Subscriptions,
+        upstream_tx: &mpsc::UnboundedSender<Upstream>,
+    ) {
+        let WsRequest {
+            command_code,
+            request_id,
+            body,
+            ..
+        } = req;
+        let reply = Reply {
+            conn: conn.clone(),
+            command_code,
+            request_id,
+        };
+
+        let res = match command_code {
+            COMMAND_CODE_AUTH => Ok(AuthResponse {
+                session_id: format!("mux-{id}"),
+                expires: (SystemTime::now() + SESSION_EXPIRES)
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as i64,
+                ..Default::default()
+            }
+            .encode_to_vec()),
+            COMMAND_CODE_RECONNECT => Ok(ReconnectResponse {
+                session_id: format!("mux-{id}"),
+                expires: SESSION_EXPIRES.as_millis() as i64,
+                ..Default::default()
+            }
+            .encode_to_vec()),
+            cmd_code::SUBSCRIBE => match SubscribeRequest::decode(&*body) {
+                Ok(req) => {
+                    let flags = SubFlags::from(req.sub_type);
+                    let added = subscriptions.add(id, req.symbol.clone(), flags);
+                    let _ = upstream_tx.send(Upstream::Subscribe {
+                        id,
+                        symbols: req.symbol,
+                        flags,
+                        is_first_push: req.is_first_push,
+                        added,
+                        reply,
+                    });
+                    return;
+                }
+                Err(err) => Err(err.into()),
+            },
+            cmd_code::UNSUBSCRIBE => match UnsubscribeRequest::decode(&*body) {
+                Ok(req) => {
+                    let (symbols, flags) = if req.unsub_all {
+                        (None, SubFlags::all())
+                    } else {
+                        (Some(req.symbol), SubFlags::from(req.sub_type))
+                    };
+                    let _ = upstream_tx.send(Upstream::Unsubscribe {
+                        groups: subscriptions.remove(id, symbols, flags),
+                        reply: Some(reply),
+                    });
+                    return;
+                }
+                Err(err) => Err(err.into()),
+            },
+            _ => {
+                // forward other requests to the upstream server without blocking the loop
+                let ctx = self.ctx.clone();
+                tokio::spawn(async move {
+                    let res = ctx.request_raw(command_code, body).await;
+                    reply.send(res);
+                });
+                return;
+            }
+        };
+
+        reply.send(res);
+    }
+}
+
+/// Sends the subscription changes to the upstream server in order, and
+/// replies to the clients
+///
+/// The subscriptions are compared with the ones confirmed by the upstream
+/// server, so a request depending on a pending subscription of another client
+/// subscribes it again if the pending one fails.
+async fn update_upstream(
+    ctx: QuoteContext,
+    mut upstream_rx: mpsc::UnboundedReceiver<Upstream>,
+    event_tx: mpsc::UnboundedSender<Event>,
+) {
+    let mut confirmed = Subscriptions::default();
+
+    while let Some(upstream) = upstream_rx.recv().await {
+        match upstream {
+            Upstream::Subscribe {
+                id,
+                symbols,
+                flags,
+                is_first_push,
+                added,
+                reply,
+            } => {
+                let groups = if is_first_push {
+                    // subscribe again to receive the first push
+                    HashMap::from([(flags, symbols)])
+                } else {
+                    confirmed.missing(&symbols, flags)
+                };
+                let mut res = Ok(());
+                for (flags, symbols) in groups {
+                    res = ctx.subscribe(symbols.clone(), flags, is_first_push).await;
+                    if res.is_err() {
+                        break;
+                    }
+                    confirmed.add(UPSTREAM_ID, symbols, flags);
+                }
+                if res.is_err() {
+                    let _ = event_tx.send(Event::SubscribeFailed { id, added });
+                }
+                reply.send(res.map(|_| vec![]));
+            }
+            Upstream::Unsubscribe { groups, reply } => {
+                let mut res = Ok(());
+                let groups = groups
+                    .into_iter()
+                    .flat_map(|(flags, symbols)| {
+                        confirmed.remove(UPSTREAM_ID, Some(symbols), flags)
+                    })
+                    .collect::<Vec<_>>();
+                for (flags, symbols) in groups {
+                    if let Err(err) = ctx.unsubscribe(symbols, flags).await {
+                        tracing::error!(error = %err, "failed to unsubscribe");
+                        res = Err(err);
+                    }
+                }
+                if let Some(reply) = reply {
+                    reply.send(res.map(|_| vec![]));
+                }
+            }
+        }
+    }
+}
+
+/// Binds the unix domain socket in a directory only accessible by the current
+/// user, and moves it to `path` after its permissions are set, so no other
+/// user can connect to it in between
+fn bind_private(path: &Path) -> Result<UnixListener> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let dir = path.with_file_name(format!(".{file_name}.{}", std::process::id()));
+    std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+
+    let tmp_path = dir.join("socket");
+    let res = UnixListener::bind(&tmp_path)
+        .and_then(|listener| {
+            std::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&tmp_path, path)?;
+            Ok(listener)
+        })
+        .map_err(Error::from);
+    let _ = std::fs::remove_file(&tmp_path);
+    let _ = std::fs::remove_dir(&dir);
+    res
+}
+
+async fn serve_client(
+    id: u64,
+    stream: tokio::net::UnixStream,
+    event_tx: mpsc::UnboundedSender<Event>,
+) {
+    let (request_tx, mut request_rx) = mpsc::unbounded_channel();
+    let conn = match WsServerConn::accept(stream, request_tx).await {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!(error = %err, "failed to accept quote mux client");
+            return;
+        }
+    };
+
+    let _ = event_tx.send(Event::Connected { id, conn });
+    while let Some(req) = request_rx.recv().await {
+        let _ = event_tx.send(Event::Request { id, req });
+    }
+    let _ = event_tx.send(Event::Disconnected { id });
+}
+
+impl Reply {
+    fn send(self, res: Result<Vec<u8>>) {
+        let (status, body) = match res {
+            Ok(body) => (0, body),
+            Err(Error::WsClient(WsClientError::ResponseError { status, detail })) => (
+                status,
+                detail
+                    .map(|detail| {
+                        longport_proto::Error {
+                            code: detail.code,
+                            msg: detail.msg,
+                        }
+                        .encode_to_vec()
+                    })
+                    .unwrap_or_default(),
+            ),
+            Err(err) => (
+                STATUS_INTERNAL_ERROR,
+                longport_proto::Error {
+                    code: 0,
+                    msg: err.to_string(),
+                }
+                .encode_to_vec(),
+            ),
+        };
+        let _ = self
+            .conn
+            .respond(self.command_code, self.request_id, status, body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(symbols: &[&str]) -> Vec<String> {
+        symbols.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_merge_subscriptions() {
+        let mut subscriptions = Subscriptions::default();
+
+        assert_eq!(
+            subscriptions.missing(&symbols(&["700.HK"]), SubFlags::QUOTE),
+            HashMap::from([(SubFlags::QUOTE, symbols(&["700.HK"]))])
+        );
+        assert_eq!(
+            subscriptions.add(1, symbols(&["700.HK"]), SubFlags::QUOTE),
+            HashMap::from([(SubFlags::QUOTE, symbols(&["700.HK"]))])
+        );
+
+        assert_eq!(
+            subscriptions.missing(&symbols(&["700.HK", "AAPL.US"]), SubFlags::QUOTE),
+            HashMap::from([(SubFlags::QUOTE, symbols(&["AAPL.US"]))])
+        );
+        assert_eq!(
+            subscriptions.missing(&symbols(&["700.HK"]), SubFlags::QUOTE | SubFlags::DEPTH),
+            HashMap::from([(SubFlags::DEPTH, symbols(&["700.HK"]))])
+        );
+        subscriptions.add(
+            2,
+            symbols(&["700.HK", "AAPL.US"]),
+            SubFlags::QUOTE | SubFlags::DEPTH,
+        );
+        // only the new flags are added for the rollback
+        assert_eq!(
+            subscriptions.add(2, symbols(&["700.HK"]), SubFlags::QUOTE | SubFlags::TRADE),
+            HashMap::from([(SubFlags::TRADE, symbols(&["700.HK"]))])
+        );
+        assert_eq!(
+            subscriptions.remove(2, Some(symbols(&["700.HK"])), SubFlags::TRADE),
+            HashMap::from([(SubFlags::TRADE, symbols(&["700.HK"]))])
+        );
+        assert!(subscriptions.contains(2, "AAPL.US", SubFlags::DEPTH));
+        assert!(!subscriptions.contains(1, "700.HK", SubFlags::DEPTH));
+
+        // 700.HK quote is still used by client 2
+        assert_eq!(
+            subscriptions.remove(1, Some(symbols(&["700.HK"])), SubFlags::QUOTE),
+            HashMap::new()
+        );
+
+        let mut groups = subscriptions.remove_client(2);
+        for symbols in groups.values_mut() {
+            symbols.sort();
+        }
+        assert_eq!(
+            groups,
+            HashMap::from([(
+                SubFlags::QUOTE | SubFlags::DEPTH,
+                symbols(&["700.HK", "AAPL.US"])
+            )])
+        );
+        assert_eq!(subscriptions.flags("700.HK"), SubFlags::empty());
+    }
+
+    #[test]
+    fn test_confirmed_subscriptions() {
+        let mut subscriptions = Subscriptions::default();
+        let mut confirmed = Subscriptions::default();
+
+        // the subscription of client 1 is pending when client 2 subscribes
+        let added = subscriptions.add(1, symbols(&["700.HK"]), SubFlags::QUOTE);
+        subscriptions.add(2, symbols(&["700.HK"]), SubFlags::QUOTE);
+
+        // the subscription of client 1 fails, and is subscribed again for
+        // client 2
+        for (flags, symbols) in added {
+            assert_eq!(
+                subscriptions.remove(1, Some(symbols), flags),
+                HashMap::new()
+            );
+        }
+        assert_eq!(
+            confirmed.missing(&symbols(&["700.HK"]), SubFlags::QUOTE),
+            HashMap::from([(SubFlags::QUOTE, symbols(&["700.HK"]))])
+        );
+        confirmed.add(UPSTREAM_ID, symbols(&["700.HK"]), SubFlags::QUOTE);
+        assert_eq!(
+            confirmed.missing(&symbols(&["700.HK"]), SubFlags::QUOTE),
+            HashMap::new()
+        );
+
+        // only the confirmed flags are unsubscribed
+        let groups = subscriptions.remove_client(2);
+        assert_eq!(
+            groups,
+            HashMap::from([(SubFlags::QUOTE, symbols(&["700.HK"]))])
+        );
+        assert_eq!(
+            confirmed.remove(
+                UPSTREAM_ID,
+                Some(symbols(&["700.HK"])),
+                SubFlags::QUOTE | SubFlags::DEPTH
+            ),
+            HashMap::from([(SubFlags::QUOTE, symbols(&["700.HK"]))])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bind_private() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quote.sock");
+        let _listener = bind_private(&path).unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        tokio::net::UnixStream::connect(&path).await.unwrap();
+    }
+}
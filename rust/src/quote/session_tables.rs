@@ -0,0 +1,183 @@
+use std::{collections::HashMap, path::Path};
+
+use longport_candlesticks::TradeSession as SessionPeriod;
+
+use crate::{
+    Error, Market, Result,
+    quote::{MarketTradingSession, SecurityBoard, store::get_market},
+};
+
+/// A trade session table with the sessions owned
+pub(crate) type Table = longport_candlesticks::Market<Vec<Vec<SessionPeriod>>>;
+
+/// The trade session tables used to merge the candlesticks
+///
+/// The tables are loaded from the `trading_session` response of the server,
+/// the tables of a user-supplied file override them, and the compiled-in
+/// tables of [`longport_candlesticks::markets`] are used for the markets not
+/// loaded. The half trading day sessions and the sessions of the US options
+/// are always compiled-in.
+#[derive(Debug)]
+pub(crate) struct SessionTables {
+    file_sessions: Vec<MarketTradingSession>,
+    builtin: HashMap<Market, Table>,
+    us_option: Table,
+    markets: HashMap<Market, Table>,
+}
+
+impl Default for SessionTables {
+    fn default() -> Self {
+        let builtin = [
+            Market::US,
+            Market::HK,
+            Market::CN,
+            Market::SG,
+            Market::Crypto,
+        ]
+        .into_iter()
+        .filter_map(|market| {
+            let table = get_market(market, SecurityBoard::Unknown)?;
+            Some((market, table.to_owned_sessions()))
+        })
+        .collect();
+        Self {
+            file_sessions: Vec::new(),
+            builtin,
+            us_option: longport_candlesticks::markets::US_OPTION.to_owned_sessions(),
+            markets: HashMap::new(),
+        }
+    }
+}
+
+impl SessionTables {
+    /// Create a `SessionTables` with the tables of the file, which has the
+    /// JSON format of the [`QuoteContext::trading_session`](crate::quote::QuoteContext::trading_session)
+    /// response
+    pub(crate) fn from_file(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        let file_sessions = serde_json::from_str(&data).map_err(|err| Error::ConfigFile {
+            path: path.to_path_buf(),
+            message: err.to_string(),
+        })?;
+        let mut tables = Self {
+            file_sessions,
+            ..Self::default()
+        };
+        tables.update(&[]);
+        Ok(tables)
+    }
+
+    /// Updates the tables with the `trading_session` response of the server
+    pub(crate) fn update(&mut self, sessions: &[MarketTradingSession]) {
+        let mut markets = HashMap::new();
+        for item in sessions.iter().chain(&self.file_sessions) {
+            let Some(base) = get_market(item.market, SecurityBoard::Unknown) else {
+                continue;
+            };
+            if item.trade_sessions.is_empty() {
+                continue;
+            }
+            let trade_sessions = base.build_trade_sessions(
+                item.trade_sessions
+                    .iter()
+                    .map(|info| (info.trade_session, info.begin_time, info.end_time)),
+            );
+            markets.insert(item.market, base.with_trade_sessions(trade_sessions));
+        }
+        self.markets = markets;
+    }
+
+    /// Returns the table of the market
+    pub(crate) fn get(&self, market: Market, board: SecurityBoard) -> Option<&Table> {
+        if market == Market::US && board == SecurityBoard::USOptionS {
+            return Some(&self.us_option);
+        }
+        self.markets
+            .get(&market)
+            .or_else(|| self.builtin.get(&market))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use longport_candlesticks::{
+        Period, TRADE_SESSION_INTRADAY,
+        markets::{HK, US_OPTION},
+    };
+    use time::macros::{datetime, time};
+
+    use super::*;
+    use crate::quote::{TradeSession, TradingSessionInfo};
+
+    fn hk_sessions(close: time::Time) -> MarketTradingSession {
+        MarketTradingSession {
+            market: Market::HK,
+            trade_sessions: vec![
+                TradingSessionInfo {
+                    begin_time: time!(9:30),
+                    end_time: time!(12:00),
+                    trade_session: TradeSession::Intraday,
+                },
+                TradingSessionInfo {
+                    begin_time: time!(13:00),
+                    end_time: close,
+                    trade_session: TradeSession::Intraday,
+                },
+            ],
+        }
+    }
+
+    fn get(tables: &SessionTables, market: Market, board: SecurityBoard) -> Table {
+        tables.get(market, board).unwrap().clone()
+    }
+
+    #[test]
+    fn test_session_tables() {
+        let mut tables = SessionTables::default();
+        assert_eq!(
+            get(&tables, Market::HK, SecurityBoard::HKEquity),
+            HK.to_owned_sessions()
+        );
+        assert_eq!(
+            get(&tables, Market::US, SecurityBoard::USOptionS),
+            US_OPTION.to_owned_sessions()
+        );
+
+        // the same sessions as the compiled-in table
+        tables.update(&[hk_sessions(time!(16:00))]);
+        assert_eq!(
+            get(&tables, Market::HK, SecurityBoard::HKEquity),
+            HK.to_owned_sessions()
+        );
+
+        // the market closes early
+        tables.update(&[hk_sessions(time!(15:00))]);
+        let table = tables.get(Market::HK, SecurityBoard::HKEquity).unwrap();
+        assert_eq!(
+            table.candlestick_time(
+                TRADE_SESSION_INTRADAY,
+                false,
+                Period::Min_1,
+                datetime!(2024-1-2 15:30:00 +8)
+            ),
+            None
+        );
+        assert_eq!(
+            table.candlestick_time(
+                TRADE_SESSION_INTRADAY,
+                false,
+                Period::Min_1,
+                datetime!(2024-1-2 14:59:30 +8)
+            ),
+            Some(datetime!(2024-1-2 14:59:00 +8))
+        );
+
+        // the file overrides the server
+        tables.file_sessions = vec![hk_sessions(time!(16:00))];
+        tables.update(&[hk_sessions(time!(15:00))]);
+        assert_eq!(
+            get(&tables, Market::HK, SecurityBoard::HKEquity),
+            HK.to_owned_sessions()
+        );
+    }
+}
@@ -9,6 +9,7 @@ use crate::{
         Brokers, Candlestick, Depth, PushBrokers, PushDepth, PushEvent, PushTrades, SecurityBoard,
         Trade, TradeDirection, TradeSession, TradeSessions,
         push_types::{PushEventDetail, PushQuote},
+        session_tables::SessionTables,
     },
 };
 
@@ -73,6 +74,7 @@ impl Candlesticks {
 
     pub(crate) fn merge_trade<H>(
         &mut self,
+        tables: &SessionTables,
         market_type: Market,
         half_days: H,
         board: SecurityBoard,
@@ -82,7 +84,7 @@ impl Candlesticks {
     where
         H: Days,
     {
-        let Some(market) = get_market(market_type, board) else {
+        let Some(market) = tables.get(market_type, board) else {
             return UpdateAction::None;
         };
         let ts = trade.trade_session;
@@ -140,11 +142,12 @@ impl Candlesticks {
 
     pub(crate) fn merge_quote_day(
         &mut self,
+        tables: &SessionTables,
         market_type: Market,
         board: SecurityBoard,
         push_quote: &PushQuote,
     ) -> UpdateAction<Candlestick> {
-        let Some(market) = get_market(market_type, board) else {
+        let Some(market) = tables.get(market_type, board) else {
             return UpdateAction::None;
         };
         let ts = push_quote.trade_session;
@@ -153,6 +156,7 @@ impl Candlesticks {
 
     pub(crate) fn merge_quote<H>(
         &mut self,
+        tables: &SessionTables,
         market_type: Market,
         half_days: H,
         board: SecurityBoard,
@@ -162,7 +166,7 @@ impl Candlesticks {
     where
         H: Days,
     {
-        let Some(market) = get_market(market_type, board) else {
+        let Some(market) = tables.get(market_type, board) else {
             return UpdateAction::None;
         };
         let ts = push_quote.trade_session;
@@ -307,7 +311,8 @@ pub(crate) fn get_market(
         Market::HK => &HK,
         Market::SG => &SG,
         Market::CN => &CN,
-        Market::Unknown | Market::Crypto => return None,
+        Market::Crypto => &CRYPTO,
+        Market::Unknown => return None,
     })
 }
 
@@ -13,7 +13,7 @@ use crate::{
 };
 
 /// Trade session type
-#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Display)]
 pub enum TradeSession {
     /// Intraday
     #[default]
@@ -106,7 +106,9 @@ impl From<quote::Brokers> for Brokers {
 }
 
 /// Trade direction
-#[derive(Debug, FromPrimitive, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(
+    Debug, FromPrimitive, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize, Display,
+)]
 #[repr(i32)]
 pub enum TradeDirection {
     /// Neutral
@@ -984,7 +986,7 @@ pub enum SortOrderType {
 }
 
 /// Warrant sort by
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, IntoPrimitive)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, EnumString, IntoPrimitive)]
 #[repr(i32)]
 pub enum WarrantSortBy {
     /// Last done
@@ -1497,7 +1499,7 @@ impl RequestUpdateWatchlistGroup {
 }
 
 /// Calc index
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, EnumString)]
 pub enum CalcIndex {
     /// Latest price
     LastDone,
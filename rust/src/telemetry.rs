@@ -0,0 +1,153 @@
+use std::{fmt::Display, sync::Arc};
+
+use tracing::{Instrument, Span, Subscriber, dispatcher, field::Empty, instrument::WithSubscriber};
+
+/// Runs a `QuoteContext` or `TradeContext` call within a span
+///
+/// The span is created with the log subscriber of the context, so the spans
+/// of the HTTP and Websocket requests sent by the call are its children. With
+/// the `opentelemetry` feature its parent is the OpenTelemetry context of the
+/// caller.
+pub(crate) async fn call<F, T, E>(
+    log_subscriber: &Arc<dyn Subscriber + Send + Sync>,
+    context: &str,
+    method: &str,
+    fut: F,
+) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    #[cfg(feature = "opentelemetry")]
+    let parent_cx = caller_context();
+
+    let span = dispatcher::with_default(&log_subscriber.clone().into(), || {
+        tracing::info_span!(
+            "call",
+            otel.name = %format_args!("{context}.{method}"),
+            symbol = Empty,
+            retry_count = Empty,
+            otel.status_code = Empty,
+            otel.status_message = Empty,
+        )
+    });
+
+    #[cfg(feature = "opentelemetry")]
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        let _ = span.set_parent(parent_cx);
+    }
+
+    let res = fut
+        .instrument(span.clone())
+        .with_subscriber(log_subscriber.clone())
+        .await;
+    if let Err(err) = &res {
+        span.record("otel.status_code", "ERROR");
+        span.record("otel.status_message", err.to_string().as_str());
+    }
+    res
+}
+
+/// Records the symbol of the current call
+pub(crate) fn record_symbol(symbol: &str) {
+    Span::current().record("symbol", symbol);
+}
+
+/// Records the symbols of the current call
+pub(crate) fn record_symbols<T: AsRef<str>>(symbols: &[T]) {
+    let symbols = symbols
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(",");
+    Span::current().record("symbol", symbols.as_str());
+}
+
+/// Records the retry count of the current call
+pub(crate) fn record_retry_count(retry_count: usize) {
+    Span::current().record("retry_count", retry_count);
+}
+
+/// Returns the OpenTelemetry context of the caller, from the current span of
+/// the default subscriber if it has an OpenTelemetry layer, otherwise the
+/// current OpenTelemetry context.
+#[cfg(feature = "opentelemetry")]
+fn caller_context() -> opentelemetry::Context {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let cx = Span::current().context();
+    if cx.span().span_context().is_valid() {
+        cx
+    } else {
+        opentelemetry::Context::current()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tracing::{
+        Id, Subscriber,
+        field::{Field, Visit},
+        span::{Attributes, Record},
+    };
+    use tracing_subscriber::{Layer, layer::Context, layer::SubscriberExt, registry::LookupSpan};
+
+    use super::*;
+
+    #[derive(Default, Clone)]
+    struct RecordLayer(Arc<Mutex<Vec<(String, String)>>>);
+
+    impl Visit for RecordLayer {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    impl<S> Layer<S> for RecordLayer
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_span() {
+        let layer = RecordLayer::default();
+        let log_subscriber: Arc<dyn Subscriber + Send + Sync> =
+            Arc::new(tracing_subscriber::registry().with(layer.clone()));
+
+        let res = call(&log_subscriber, "quote", "depth", async {
+            record_symbols(&["700.HK", "AAPL.US"]);
+            record_retry_count(1);
+            Err::<(), _>("request timeout")
+        })
+        .await;
+        assert!(res.is_err());
+
+        let fields = layer.0.lock().unwrap().clone();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(field("otel.name"), Some("quote.depth"));
+        assert_eq!(field("symbol"), Some("\"700.HK,AAPL.US\""));
+        assert_eq!(field("retry_count"), Some("1"));
+        assert_eq!(field("otel.status_code"), Some("\"ERROR\""));
+        assert_eq!(field("otel.status_message"), Some("\"request timeout\""));
+    }
+}
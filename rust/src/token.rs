@@ -0,0 +1,386 @@
+//! Access token management
+
+use std::{
+    fmt::Debug,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{Config, Error, Result};
+
+const DEFAULT_REFRESH_AHEAD: Duration = Duration::days(7);
+const DEFAULT_VALIDITY: Duration = Duration::days(90);
+const RETRY_DELAY: Duration = Duration::minutes(1);
+
+/// An access token and its expiration time
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenInfo {
+    /// Access token
+    pub token: String,
+    /// Expiration time
+    #[serde(with = "time::serde::rfc3339")]
+    pub expired_at: OffsetDateTime,
+}
+
+/// A storage which persists the refreshed access tokens, so that a restarted
+/// process continues with the latest token
+pub trait TokenStore: Debug + Send + Sync + 'static {
+    /// Load the latest access token, returns `None` if no token has been
+    /// saved
+    fn load(&self) -> Result<Option<TokenInfo>>;
+
+    /// Save a refreshed access token
+    fn save(&self, token: &TokenInfo) -> Result<()>;
+}
+
+/// A [`TokenStore`] which saves the access token to a JSON file
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Create a new `FileTokenStore`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the file
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<TokenInfo>> {
+        match std::fs::read(&self.path) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&self, token: &TokenInfo) -> Result<()> {
+        // write to a temporary file first, so that the file is never left half
+        // written, the file is created only readable by the current user and a
+        // stale one is removed, because the mode only applies to the new files
+        let tmp_path = self.path.with_extension("tmp");
+        let _ = std::fs::remove_file(&tmp_path);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(&serde_json::to_vec_pretty(token)?)?;
+        drop(file);
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Options for [`TokenManager`]
+#[derive(Debug, Clone)]
+pub struct TokenManagerOptions {
+    store: Option<Arc<dyn TokenStore>>,
+    expired_at: Option<OffsetDateTime>,
+    refresh_ahead: Duration,
+    validity: Duration,
+}
+
+impl Default for TokenManagerOptions {
+    fn default() -> Self {
+        Self {
+            store: None,
+            expired_at: None,
+            refresh_ahead: DEFAULT_REFRESH_AHEAD,
+            validity: DEFAULT_VALIDITY,
+        }
+    }
+}
+
+impl TokenManagerOptions {
+    /// Create a new `TokenManagerOptions`
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Specifies the store to persist the refreshed access tokens
+    ///
+    /// If the store contains an unexpired token when the manager starts, it
+    /// replaces the access token of the [`Config`].
+    #[inline]
+    #[must_use]
+    pub fn store(self, store: impl TokenStore) -> Self {
+        Self {
+            store: Some(Arc::new(store)),
+            ..self
+        }
+    }
+
+    /// Specifies the expiration time of the access token in the [`Config`]
+    ///
+    /// If it is unknown, the access token is refreshed immediately when the
+    /// manager starts.
+    #[inline]
+    #[must_use]
+    pub fn expired_at(self, expired_at: OffsetDateTime) -> Self {
+        Self {
+            expired_at: Some(expired_at),
+            ..self
+        }
+    }
+
+    /// Specifies how long before the expiration the access token is refreshed
+    ///
+    /// Default: `7 days`
+    #[inline]
+    #[must_use]
+    pub fn refresh_ahead(self, refresh_ahead: Duration) -> Self {
+        Self {
+            refresh_ahead,
+            ..self
+        }
+    }
+
+    /// Specifies the validity of the refreshed access tokens
+    ///
+    /// Default: `90 days`
+    #[inline]
+    #[must_use]
+    pub fn validity(self, validity: Duration) -> Self {
+        Self { validity, ..self }
+    }
+}
+
+enum Command {
+    Refresh {
+        reply_tx: oneshot::Sender<Result<TokenInfo>>,
+    },
+}
+
+/// Access token manager
+///
+/// It refreshes the access token of the [`Config`] ahead of its expiration,
+/// and replaces the token used by all the contexts created from the
+/// [`Config`], including their reconnections, without restarting them.
+///
+/// The manager stops when it is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{
+///     Config, QuoteContext,
+///     token::{FileTokenStore, TokenManager, TokenManagerOptions},
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let config = Arc::new(Config::from_env()?);
+/// let token_manager = TokenManager::try_new(
+///     config.clone(),
+///     TokenManagerOptions::new().store(FileTokenStore::new("token.json")),
+/// )
+/// .await?;
+/// let (ctx, _) = QuoteContext::try_new(config).await?;
+///
+/// println!("token expires at {}", token_manager.token().expired_at);
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+pub struct TokenManager {
+    command_tx: mpsc::UnboundedSender<Command>,
+    current: Arc<RwLock<TokenInfo>>,
+}
+
+impl TokenManager {
+    /// Create a `TokenManager` and start refreshing the access token of
+    /// `config`
+    pub async fn try_new(config: Arc<Config>, options: TokenManagerOptions) -> Result<Self> {
+        let now = OffsetDateTime::now_utc();
+        let mut current = None;
+
+        if let Some(store) = &options.store {
+            match store.load()? {
+                Some(info) if info.expired_at > now => {
+                    tracing::info!(expired_at = %info.expired_at, "access token loaded from the store");
+                    config.set_access_token(info.token.clone());
+                    current = Some(info);
+                }
+                _ => {}
+            }
+        }
+
+        let current = match current.or_else(|| {
+            options.expired_at.map(|expired_at| TokenInfo {
+                token: config.access_token(),
+                expired_at,
+            })
+        }) {
+            Some(info) => info,
+            None => refresh(&config, &options).await?,
+        };
+
+        let current = Arc::new(RwLock::new(current));
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        tokio::spawn(
+            Core {
+                config,
+                options,
+                current: current.clone(),
+                command_rx,
+            }
+            .run(),
+        );
+        Ok(Self {
+            command_tx,
+            current,
+        })
+    }
+
+    /// Returns the current access token
+    pub fn token(&self) -> TokenInfo {
+        self.current.read().clone()
+    }
+
+    /// Refresh the access token immediately
+    pub async fn refresh(&self) -> Result<TokenInfo> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.command_tx
+            .send(Command::Refresh { reply_tx })
+            .map_err(|_| Error::TokenManagerClosed)?;
+        reply_rx.await.map_err(|_| Error::TokenManagerClosed)?
+    }
+}
+
+struct Core {
+    config: Arc<Config>,
+    options: TokenManagerOptions,
+    current: Arc<RwLock<TokenInfo>>,
+    command_rx: mpsc::UnboundedReceiver<Command>,
+}
+
+impl Core {
+    async fn run(mut self) {
+        let mut retry_at = None;
+
+        loop {
+            let refresh_at = retry_at
+                .unwrap_or_else(|| refresh_at(&self.current.read(), self.options.refresh_ahead));
+            let delay = (refresh_at - OffsetDateTime::now_utc())
+                .try_into()
+                .unwrap_or_default();
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {
+                    retry_at = match self.refresh().await {
+                        Ok(_) => None,
+                        Err(err) => {
+                            tracing::error!(error = %err, "failed to refresh access token");
+                            Some(OffsetDateTime::now_utc() + RETRY_DELAY)
+                        }
+                    };
+                }
+                item = self.command_rx.recv() => {
+                    match item {
+                        Some(Command::Refresh { reply_tx }) => {
+                            let res = self.refresh().await;
+                            if res.is_ok() {
+                                retry_at = None;
+                            }
+                            let _ = reply_tx.send(res);
+                        }
+                        None => return,
+                    }
+                }
+            }
+        }
+    }
+
+    async fn refresh(&self) -> Result<TokenInfo> {
+        let info = refresh(&self.config, &self.options).await?;
+        *self.current.write() = info.clone();
+        Ok(info)
+    }
+}
+
+fn refresh_at(info: &TokenInfo, refresh_ahead: Duration) -> OffsetDateTime {
+    info.expired_at - refresh_ahead
+}
+
+async fn refresh(config: &Config, options: &TokenManagerOptions) -> Result<TokenInfo> {
+    let expired_at = (OffsetDateTime::now_utc() + options.validity)
+        .replace_nanosecond(0)
+        .expect("valid nanosecond");
+    config.reload_credentials()?;
+    let token = config.refresh_access_token(Some(expired_at)).await?;
+    config.set_access_token(token.clone());
+    tracing::info!(expired_at = %expired_at, "access token refreshed");
+
+    let info = TokenInfo { token, expired_at };
+    if let Some(store) = &options.store {
+        // the new token is already in use, so a failure of the store must not
+        // fail the refresh
+        if let Err(err) = store.save(&info) {
+            tracing::error!(error = %err, "failed to save access token");
+        }
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_file_token_store() {
+        let path = std::env::temp_dir().join(format!(
+            "longport-token-{}-{}.json",
+            std::process::id(),
+            OffsetDateTime::now_utc().unix_timestamp_nanos()
+        ));
+        let store = FileTokenStore::new(&path);
+        assert_eq!(store.load().unwrap(), None);
+
+        let info = TokenInfo {
+            token: "abc".to_string(),
+            expired_at: datetime!(2025-01-01 0:00 UTC),
+        };
+        store.save(&info).unwrap();
+        assert_eq!(store.load().unwrap(), Some(info));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_refresh_at() {
+        let info = TokenInfo {
+            token: "abc".to_string(),
+            expired_at: datetime!(2025-04-01 0:00 UTC),
+        };
+        assert_eq!(
+            refresh_at(&info, Duration::days(7)),
+            datetime!(2025-03-25 0:00 UTC)
+        );
+    }
+}
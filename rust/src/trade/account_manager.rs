@@ -0,0 +1,381 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+use futures_util::future::try_join_all;
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+use crate::{
+    Config, Error, Market, Result,
+    trade::{
+        AccountBalance, PushEvent, ReplaceOrderOptions, StockPosition, SubmitOrderOptions,
+        SubmitOrderResponse, TopicType, TradeContext,
+    },
+};
+
+/// A push event of an account
+#[derive(Debug)]
+pub struct AccountPushEvent {
+    /// Account id
+    pub account_id: String,
+    /// Event
+    pub event: PushEvent,
+}
+
+/// The balances of an account
+#[derive(Debug, Clone)]
+pub struct AccountBalances {
+    /// Account id
+    pub account_id: String,
+    /// Balances
+    pub balances: Vec<AccountBalance>,
+}
+
+/// A stock position of an account
+#[derive(Debug, Clone)]
+pub struct AccountStockPosition {
+    /// Account id
+    pub account_id: String,
+    /// Account type
+    pub account_channel: String,
+    /// Position
+    pub position: StockPosition,
+}
+
+/// The stock positions of a security across all accounts
+#[derive(Debug, Clone)]
+pub struct AggregatedStockPosition {
+    /// Stock code
+    pub symbol: String,
+    /// Stock name
+    pub symbol_name: String,
+    /// Market
+    pub market: Market,
+    /// Currency
+    pub currency: String,
+    /// The total number of holdings
+    pub quantity: Decimal,
+    /// The total available quantity
+    pub available_quantity: Decimal,
+    /// The cost price weighted by the quantity of each position
+    pub cost_price: Decimal,
+    /// The positions of each account
+    pub accounts: Vec<AccountStockPosition>,
+}
+
+/// The portfolio across all accounts
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    /// The balances of each account
+    pub balances: Vec<AccountBalances>,
+    /// The stock positions aggregated by security
+    pub stock_positions: Vec<AggregatedStockPosition>,
+}
+
+/// Manages the trade contexts of multiple accounts
+///
+/// Each account has its own [`Config`] and [`TradeContext`], all the push
+/// events are merged into one channel and tagged with the account id.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use longport::{
+///     Config,
+///     credentials::FileCredentialProvider,
+///     decimal,
+///     trade::{AccountManager, OrderSide, OrderType, SubmitOrderOptions, TimeInForceType},
+/// };
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let accounts = [
+///     (
+///         "main",
+///         Config::from_credential_provider(FileCredentialProvider::new("main.toml"))?,
+///     ),
+///     (
+///         "sub",
+///         Config::from_credential_provider(FileCredentialProvider::new("sub.toml"))?,
+///     ),
+/// ];
+/// let (manager, mut receiver) =
+///     AccountManager::try_new(accounts.map(|(id, config)| (id, Arc::new(config)))).await?;
+///
+/// let opts = SubmitOrderOptions::new(
+///     "700.HK",
+///     OrderType::LO,
+///     OrderSide::Buy,
+///     decimal!(200),
+///     TimeInForceType::Day,
+/// )
+/// .submitted_price(decimal!(50i32));
+/// manager.submit_order("sub", opts).await?;
+///
+/// while let Some(event) = receiver.recv().await {
+///     println!("{}: {:?}", event.account_id, event.event);
+/// }
+/// # Ok::<_, Box<dyn std::error::Error>>(())
+/// # });
+/// ```
+pub struct AccountManager {
+    accounts: BTreeMap<String, TradeContext>,
+}
+
+impl AccountManager {
+    /// Create an `AccountManager` with the configs of the accounts
+    ///
+    /// The account ids must be unique, [`Error::DuplicateAccount`] is returned
+    /// before connecting if an id appears more than once.
+    pub async fn try_new<I, K>(
+        accounts: I,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<AccountPushEvent>)>
+    where
+        I: IntoIterator<Item = (K, Arc<Config>)>,
+        K: Into<String>,
+    {
+        let accounts = accounts
+            .into_iter()
+            .map(|(account_id, config)| (account_id.into(), config))
+            .collect::<Vec<(String, _)>>();
+        let mut account_ids = HashSet::new();
+        for (account_id, _) in &accounts {
+            if !account_ids.insert(account_id.as_str()) {
+                return Err(Error::DuplicateAccount {
+                    account_id: account_id.clone(),
+                });
+            }
+        }
+
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let accounts = try_join_all(accounts.into_iter().map(|(account_id, config)| {
+            let push_tx = push_tx.clone();
+            async move {
+                let (ctx, mut receiver) = TradeContext::try_new(config).await?;
+                tokio::spawn({
+                    let account_id = account_id.clone();
+                    async move {
+                        while let Some(event) = receiver.recv().await {
+                            let event = AccountPushEvent {
+                                account_id: account_id.clone(),
+                                event,
+                            };
+                            if push_tx.send(event).is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+                Ok::<_, Error>((account_id, ctx))
+            }
+        }))
+        .await?;
+
+        Ok((
+            Self {
+                accounts: accounts.into_iter().collect(),
+            },
+            push_rx,
+        ))
+    }
+
+    /// Returns the ids of all accounts
+    pub fn account_ids(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    /// Returns the trade context of the account
+    pub fn context(&self, account_id: &str) -> Result<&TradeContext> {
+        self.accounts
+            .get(account_id)
+            .ok_or_else(|| Error::UnknownAccount {
+                account_id: account_id.to_string(),
+            })
+    }
+
+    /// Subscribe the topics of all accounts
+    pub async fn subscribe<I>(&self, topics: I) -> Result<()>
+    where
+        I: IntoIterator<Item = TopicType>,
+    {
+        let topics = topics.into_iter().collect::<Vec<_>>();
+        try_join_all(
+            self.accounts
+                .values()
+                .map(|ctx| ctx.subscribe(topics.iter().copied())),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Submit order with the account
+    pub async fn submit_order(
+        &self,
+        account_id: &str,
+        options: SubmitOrderOptions,
+    ) -> Result<SubmitOrderResponse> {
+        self.context(account_id)?.submit_order(options).await
+    }
+
+    /// Replace order with the account
+    pub async fn replace_order(
+        &self,
+        account_id: &str,
+        options: ReplaceOrderOptions,
+    ) -> Result<()> {
+        self.context(account_id)?.replace_order(options).await
+    }
+
+    /// Cancel order with the account
+    pub async fn cancel_order(&self, account_id: &str, order_id: impl Into<String>) -> Result<()> {
+        self.context(account_id)?.cancel_order(order_id).await
+    }
+
+    /// Get the balances of all accounts
+    pub async fn account_balances(&self, currency: Option<&str>) -> Result<Vec<AccountBalances>> {
+        try_join_all(self.accounts.iter().map(|(account_id, ctx)| async move {
+            Ok::<_, Error>(AccountBalances {
+                account_id: account_id.clone(),
+                balances: ctx.account_balance(currency).await?,
+            })
+        }))
+        .await
+    }
+
+    /// Get the stock positions of all accounts
+    pub async fn stock_positions(&self) -> Result<Vec<AccountStockPosition>> {
+        let positions = try_join_all(self.accounts.iter().map(|(account_id, ctx)| async move {
+            let resp = ctx.stock_positions(None).await?;
+            Ok::<_, Error>(
+                resp.channels
+                    .into_iter()
+                    .flat_map(|channel| {
+                        let account_channel = channel.account_channel;
+                        channel
+                            .positions
+                            .into_iter()
+                            .map(move |position| AccountStockPosition {
+                                account_id: account_id.clone(),
+                                account_channel: account_channel.clone(),
+                                position,
+                            })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }))
+        .await?;
+        Ok(positions.into_iter().flatten().collect())
+    }
+
+    /// Get the portfolio across all accounts, the stock positions are
+    /// aggregated by security
+    pub async fn portfolio(&self) -> Result<Portfolio> {
+        let (balances, positions) =
+            tokio::try_join!(self.account_balances(None), self.stock_positions())?;
+        Ok(Portfolio {
+            balances,
+            stock_positions: aggregate_stock_positions(positions),
+        })
+    }
+}
+
+fn aggregate_stock_positions(positions: Vec<AccountStockPosition>) -> Vec<AggregatedStockPosition> {
+    let mut res: BTreeMap<String, AggregatedStockPosition> = BTreeMap::new();
+
+    for item in positions {
+        let position = &item.position;
+        let aggregated =
+            res.entry(position.symbol.clone())
+                .or_insert_with(|| AggregatedStockPosition {
+                    symbol: position.symbol.clone(),
+                    symbol_name: position.symbol_name.clone(),
+                    market: position.market,
+                    currency: position.currency.clone(),
+                    quantity: Decimal::ZERO,
+                    available_quantity: Decimal::ZERO,
+                    cost_price: Decimal::ZERO,
+                    accounts: Vec::new(),
+                });
+        let total_cost =
+            aggregated.cost_price * aggregated.quantity + position.cost_price * position.quantity;
+        aggregated.quantity += position.quantity;
+        aggregated.available_quantity += position.available_quantity;
+        aggregated.cost_price = if aggregated.quantity.is_zero() {
+            Decimal::ZERO
+        } else {
+            total_cost / aggregated.quantity
+        };
+        aggregated.accounts.push(item);
+    }
+
+    res.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(
+        account_id: &str,
+        symbol: &str,
+        quantity: i64,
+        cost_price: i64,
+    ) -> AccountStockPosition {
+        AccountStockPosition {
+            account_id: account_id.to_string(),
+            account_channel: "lb".to_string(),
+            position: StockPosition {
+                symbol: symbol.to_string(),
+                symbol_name: symbol.to_string(),
+                quantity: quantity.into(),
+                available_quantity: quantity.into(),
+                currency: "HKD".to_string(),
+                cost_price: cost_price.into(),
+                market: Market::HK,
+                init_quantity: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_aggregate_stock_positions() {
+        let res = aggregate_stock_positions(vec![
+            position("a", "700.HK", 100, 300),
+            position("b", "9988.HK", 200, 80),
+            position("b", "700.HK", 300, 400),
+        ]);
+        assert_eq!(res.len(), 2);
+
+        assert_eq!(res[0].symbol, "700.HK");
+        assert_eq!(res[0].quantity, Decimal::from(400));
+        assert_eq!(res[0].available_quantity, Decimal::from(400));
+        assert_eq!(res[0].cost_price, Decimal::from(375));
+        assert_eq!(
+            res[0]
+                .accounts
+                .iter()
+                .map(|item| item.account_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+
+        assert_eq!(res[1].symbol, "9988.HK");
+        assert_eq!(res[1].quantity, Decimal::from(200));
+        assert_eq!(res[1].cost_price, Decimal::from(80));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_account() {
+        let config = Arc::new(Config::new("app_key", "app_secret", "access_token"));
+        let res =
+            AccountManager::try_new([("a", config.clone()), ("b", config.clone()), ("a", config)])
+                .await;
+        assert!(matches!(
+            res,
+            Err(Error::DuplicateAccount { account_id }) if account_id == "a"
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use longport_httpcli::{HttpClient, Json, Method};
+use longport_httpcli::{HttpClient, Json, Method, RateLimitBudget};
+use longport_macros::call;
 use longport_wscli::WsClientError;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -8,7 +9,7 @@ use tokio::sync::{mpsc, oneshot};
 use tracing::{Subscriber, dispatcher, instrument::WithSubscriber};
 
 use crate::{
-    Config, Result, serde_utils,
+    CallOptions, Config, Result, serde_utils, telemetry,
     trade::{
         AccountBalance, CashFlow, EstimateMaxPurchaseQuantityOptions, Execution,
         FundPositionsResponse, GetCashFlowOptions, GetFundPositionsOptions,
@@ -56,7 +57,7 @@ impl Drop for InnerTradeContext {
 
 /// Trade context
 #[derive(Clone)]
-pub struct TradeContext(Arc<InnerTradeContext>);
+pub struct TradeContext(Arc<InnerTradeContext>, CallOptions);
 
 impl TradeContext {
     /// Create a `TradeContext`
@@ -64,9 +65,15 @@ impl TradeContext {
         config: Arc<Config>,
     ) -> Result<(Self, mpsc::UnboundedReceiver<PushEvent>)> {
         let log_subscriber = config.create_log_subscriber("trade");
+        let region = config.resolve_region().await;
 
         dispatcher::with_default(&log_subscriber.clone().into(), || {
-            tracing::info!(language = ?config.language, "creating trade context");
+            tracing::info!(
+                language = ?config.language,
+                region = %region.region,
+                region_reason = %region.reason,
+                "creating trade context"
+            );
         });
 
         let http_cli = config.create_http_client();
@@ -82,11 +89,14 @@ impl TradeContext {
         });
 
         Ok((
-            TradeContext(Arc::new(InnerTradeContext {
-                http_cli,
-                command_tx,
-                log_subscriber,
-            })),
+            TradeContext(
+                Arc::new(InnerTradeContext {
+                    http_cli,
+                    command_tx,
+                    log_subscriber,
+                }),
+                CallOptions::default(),
+            ),
             push_rx,
         ))
     }
@@ -97,6 +107,51 @@ impl TradeContext {
         self.0.log_subscriber.clone()
     }
 
+    /// Returns the client-side rate limit budget of the HTTP endpoint, or
+    /// `None` if the endpoint is not rate limited.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use longport::{Config, TradeContext, httpclient::Method};
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// let config = Arc::new(Config::from_env()?);
+    /// let (ctx, _) = TradeContext::try_new(config).await?;
+    ///
+    /// if let Some(budget) = ctx.rate_limit_budget(&Method::POST, "/v1/trade/order") {
+    ///     println!("{} of {} requests remaining", budget.remaining, budget.max);
+    /// }
+    /// # Ok::<_, Box<dyn std::error::Error>>(())
+    /// # });
+    /// ```
+    pub fn rate_limit_budget(&self, method: &Method, path: &str) -> Option<RateLimitBudget> {
+        self.0.http_cli.rate_limit_budget(method, path)
+    }
+
+    /// Returns a `TradeContext` sharing the connection with this one, whose calls
+    /// use the specified options
+    #[must_use]
+    pub fn with_call_options(&self, options: CallOptions) -> Self {
+        Self(self.0.clone(), options)
+    }
+
+    /// Returns the options of the calls
+    #[inline]
+    pub fn call_options(&self) -> &CallOptions {
+        &self.1
+    }
+
+    /// Runs the call within a span, with the call options
+    async fn call<F, T>(&self, method: &str, fut: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        telemetry::call(&self.0.log_subscriber, "trade", method, self.1.run(fut)).await
+    }
+
     /// Subscribe
     ///
     /// Reference: <https://open.longportapp.com/en/docs/trade/trade-push#subscribe>
@@ -133,6 +188,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn subscribe<I>(&self, topics: I) -> Result<()>
     where
         I: IntoIterator<Item = TopicType>,
@@ -151,6 +207,7 @@ impl TradeContext {
     /// Unsubscribe
     ///
     /// Reference: <https://open.longportapp.com/en/docs/trade/trade-push#cancel-subscribe>
+    #[call]
     pub async fn unsubscribe<I>(&self, topics: I) -> Result<()>
     where
         I: IntoIterator<Item = TopicType>,
@@ -194,6 +251,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn history_executions(
         &self,
         options: impl Into<Option<GetHistoryExecutionsOptions>>,
@@ -240,6 +298,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn today_executions(
         &self,
         options: impl Into<Option<GetTodayExecutionsOptions>>,
@@ -293,6 +352,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn history_orders(
         &self,
         options: impl Into<Option<GetHistoryOrdersOptions>>,
@@ -343,6 +403,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn today_orders(
         &self,
         options: impl Into<Option<GetTodayOrdersOptions>>,
@@ -390,11 +451,13 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn replace_order(&self, options: ReplaceOrderOptions) -> Result<()> {
         Ok(self
             .0
             .http_cli
             .request(Method::PUT, "/v1/trade/order")
+            .idempotent(false)
             .body(Json(options))
             .response::<Json<EmptyResponse>>()
             .send()
@@ -434,11 +497,14 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn submit_order(&self, options: SubmitOrderOptions) -> Result<SubmitOrderResponse> {
+        telemetry::record_symbol(&options.symbol);
         let resp: SubmitOrderResponse = self
             .0
             .http_cli
             .request(Method::POST, "/v1/trade/order")
+            .idempotent(false)
             .body(Json(options))
             .response::<Json<_>>()
             .send()
@@ -470,6 +536,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn cancel_order(&self, order_id: impl Into<String>) -> Result<()> {
         #[derive(Debug, Serialize)]
         struct Request {
@@ -480,6 +547,7 @@ impl TradeContext {
             .0
             .http_cli
             .request(Method::DELETE, "/v1/trade/order")
+            .idempotent(false)
             .response::<Json<EmptyResponse>>()
             .query_params(Request {
                 order_id: order_id.into(),
@@ -510,6 +578,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn account_balance(&self, currency: Option<&str>) -> Result<Vec<AccountBalance>> {
         #[derive(Debug, Serialize)]
         struct Request<'a> {
@@ -559,6 +628,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn cash_flow(&self, options: GetCashFlowOptions) -> Result<Vec<CashFlow>> {
         #[derive(Debug, Deserialize)]
         struct Response {
@@ -598,6 +668,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn fund_positions(
         &self,
         opts: impl Into<Option<GetFundPositionsOptions>>,
@@ -634,6 +705,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn stock_positions(
         &self,
         opts: impl Into<Option<GetStockPositionsOptions>>,
@@ -670,7 +742,10 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn margin_ratio(&self, symbol: impl Into<String>) -> Result<MarginRatio> {
+        let symbol: String = symbol.into();
+        telemetry::record_symbol(&symbol);
         #[derive(Debug, Serialize)]
         struct Request {
             symbol: String,
@@ -680,9 +755,7 @@ impl TradeContext {
             .0
             .http_cli
             .request(Method::GET, "/v1/risk/margin-ratio")
-            .query_params(Request {
-                symbol: symbol.into(),
-            })
+            .query_params(Request { symbol })
             .response::<Json<MarginRatio>>()
             .send()
             .with_subscriber(self.0.log_subscriber.clone())
@@ -714,6 +787,7 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn order_detail(&self, order_id: impl Into<String>) -> Result<OrderDetail> {
         #[derive(Debug, Serialize)]
         struct Request {
@@ -766,10 +840,12 @@ impl TradeContext {
     /// # Ok::<_, Box<dyn std::error::Error>>(())
     /// # });
     /// ```
+    #[call]
     pub async fn estimate_max_purchase_quantity(
         &self,
         opts: EstimateMaxPurchaseQuantityOptions,
     ) -> Result<EstimateMaxPurchaseQuantityResponse> {
+        telemetry::record_symbol(&opts.symbol);
         Ok(self
             .0
             .http_cli
@@ -15,7 +15,7 @@ use tokio::{
 };
 
 use crate::{
-    Config, Result,
+    Config, Result, metrics,
     trade::{PushEvent, PushOrderChanged, TopicType, cmd_code},
 };
 
@@ -63,13 +63,14 @@ impl Core {
         tracing::info!("connecting to trade server");
         let (url, res) = config.create_trade_ws_request().await;
         let request = res.map_err(WsClientError::from)?;
-        let ws_cli = WsClient::open(
+        let ws_cli = WsClient::open_with_options(
             request,
             ProtocolVersion::Version1,
             CodecType::Protobuf,
             Platform::OpenAPI,
             event_tx.clone(),
             vec![],
+            config.ws_open_options(),
         )
         .await?;
 
@@ -102,18 +103,20 @@ impl Core {
             loop {
                 // reconnect
                 tokio::time::sleep(RECONNECT_DELAY).await;
+                metrics::record_reconnect_attempt("trade");
 
                 tracing::info!("connecting to trade server");
                 let (url, res) = self.config.create_trade_ws_request().await;
                 let request = res.expect("BUG: failed to create trade ws request");
 
-                match WsClient::open(
+                match WsClient::open_with_options(
                     request,
                     ProtocolVersion::Version1,
                     CodecType::Protobuf,
                     Platform::OpenAPI,
                     self.event_tx.clone(),
                     vec![],
+                    self.config.ws_open_options(),
                 )
                 .await
                 {
@@ -163,7 +166,10 @@ impl Core {
 
                 // handle reconnect
                 match self.resubscribe().await {
-                    Ok(()) => break,
+                    Ok(()) => {
+                        metrics::record_reconnect("trade");
+                        break;
+                    }
                     Err(err) => {
                         tracing::error!(error = %err, "failed to subscribe topics");
                         continue;
@@ -208,7 +214,24 @@ impl Core {
     async fn handle_push(&mut self, command_code: u8, body: Vec<u8>) -> Result<()> {
         match PushEvent::parse(command_code, &body) {
             Ok(Some(event)) => {
-                tracing::info!(event = ?event, "push event");
+                if self.config.log_redaction {
+                    match &event {
+                        PushEvent::OrderChanged(order) => tracing::info!(
+                            order_id = %order.order_id,
+                            symbol = %order.symbol,
+                            status = ?order.status,
+                            "push event"
+                        ),
+                    }
+                } else {
+                    tracing::info!(event = ?event, "push event");
+                }
+                metrics::record_push(
+                    "trade",
+                    match &event {
+                        PushEvent::OrderChanged(_) => "order_changed",
+                    },
+                );
                 let _ = self.push_tx.send(event);
             }
             Ok(None) => {}
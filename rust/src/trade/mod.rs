@@ -1,5 +1,6 @@
 //! Trade related types
 
+mod account_manager;
 mod cmd_code;
 mod context;
 mod core;
@@ -7,6 +8,10 @@ mod push_types;
 mod requests;
 mod types;
 
+pub use account_manager::{
+    AccountBalances, AccountManager, AccountPushEvent, AccountStockPosition,
+    AggregatedStockPosition, Portfolio,
+};
 pub use context::{EstimateMaxPurchaseQuantityResponse, SubmitOrderResponse, TradeContext};
 pub use push_types::{PushEvent, PushOrderChanged, TopicType};
 pub use requests::{
@@ -6,7 +6,7 @@ use crate::trade::{OrderSide, OrderType};
 /// Options for estimate maximum purchase quantity
 #[derive(Debug, Serialize, Clone)]
 pub struct EstimateMaxPurchaseQuantityOptions {
-    symbol: String,
+    pub(crate) symbol: String,
     order_type: OrderType,
     #[serde(skip_serializing_if = "Option::is_none")]
     price: Option<Decimal>,
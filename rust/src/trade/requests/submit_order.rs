@@ -10,7 +10,7 @@ use crate::{
 /// Options for submit order request
 #[derive(Debug, Serialize, Clone)]
 pub struct SubmitOrderOptions {
-    symbol: String,
+    pub(crate) symbol: String,
     order_type: OrderType,
     side: OrderSide,
     submitted_quantity: Decimal,